@@ -0,0 +1,360 @@
+use std::path::{Path, PathBuf};
+
+use native_dialog::Error;
+
+use crate::{
+    worker::Worker, DialogError, DialogKind, DialogResult, ImNativeDialogError, RequestToken,
+};
+
+/// An application chosen using [ImNativeAppChooserDialog].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Application {
+    /// The desktop entry id such as `org.gnome.TextEditor.desktop` on
+    /// GNU/Linux and BSD Unix, or the path of the application bundle
+    /// on macOS.
+    pub id: String,
+    /// The name of the application shown to users.
+    pub name: String,
+    /// The command line of the desktop entry, on GNU/Linux and BSD
+    /// Unix.
+    #[cfg_attr(not(all(unix, not(target_os = "macos"))), allow(dead_code))]
+    exec: String,
+}
+
+impl Application {
+    /// Opens `path` using this application, without waiting for it to
+    /// exit.
+    #[cfg(all(unix, not(target_os = "macos")))]
+    pub fn open(&self, path: &Path) -> std::io::Result<()> {
+        let mut args = Vec::new();
+        let mut has_path = false;
+        for arg in split_exec(&self.exec) {
+            match arg.as_str() {
+                "%f" | "%F" | "%u" | "%U" => {
+                    args.push(path.as_os_str().to_owned());
+                    has_path = true;
+                }
+                // The other field codes are deprecated or only
+                // describe the entry itself.
+                field if field.len() == 2 && field.starts_with('%') => {}
+                _ => args.push(arg.replace("%%", "%").into()),
+            }
+        }
+        if !has_path {
+            args.push(path.as_os_str().to_owned());
+        }
+
+        let (program, args) = args.split_first().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "empty Exec command")
+        })?;
+        std::process::Command::new(program).args(args).spawn()?;
+        Ok(())
+    }
+
+    /// Opens `path` using this application, without waiting for it to
+    /// exit.
+    #[cfg(target_os = "macos")]
+    pub fn open(&self, path: &Path) -> std::io::Result<()> {
+        std::process::Command::new("open")
+            .arg("-a")
+            .arg(&self.id)
+            .arg(path)
+            .spawn()?;
+        Ok(())
+    }
+
+    /// Opens `path` using this application. There is no chooser on
+    /// Windows, so this always fails.
+    #[cfg(target_os = "windows")]
+    pub fn open(&self, _path: &Path) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "no application chooser",
+        ))
+    }
+}
+
+/// An "open with" application chooser for use with immediate mode gui
+/// libraries, following the same pattern as
+/// [crate::ImNativeFileDialog]. The `show*()` methods show the chooser
+/// in a new thread, and the result is returned to this object ready to
+/// be polled by the ui using [ImNativeAppChooserDialog::check()].
+///
+/// On GNU/Linux and BSD Unix the applications installed for the type
+/// of the file are listed using `zenity` or `kdialog`, and on macOS
+/// using `osascript`. Windows has no chooser which returns the choice,
+/// so [DialogError::NoImplementation] is returned.
+pub struct ImNativeAppChooserDialog<T> {
+    worker: Worker<T>,
+}
+
+impl<T> Default for ImNativeAppChooserDialog<T> {
+    fn default() -> Self {
+        Self {
+            worker: Worker::default(),
+        }
+    }
+}
+
+impl ImNativeAppChooserDialog<Option<Application>> {
+    /// Shows a chooser for the application to open `path` with. The
+    /// result is `None` if the user cancelled the chooser.
+    pub fn show_app_chooser(
+        &mut self,
+        title: impl Into<String>,
+        path: impl Into<PathBuf>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let title = title.into();
+        let path = path.into();
+        self.worker.spawn(DialogKind::AppChooser, None, move || {
+            app_chooser(&title, &path)
+        })
+    }
+}
+
+impl<T: Send + 'static + Default> ImNativeAppChooserDialog<T> {
+    /// Set a callback to use for this dialog which will be called
+    /// immediately upon dialog close in the dialog monitoring thread.
+    pub fn with_callback<C>(&mut self, callback: C) -> &mut Self
+    where
+        C: FnOnce(&Result<T, DialogError>) + Send + 'static,
+    {
+        self.worker.set_callback(Box::new(callback));
+        self
+    }
+
+    /// Check if the chooser is complete. If it is complete it will
+    /// return `Some` with the result of the chooser, otherwise will
+    /// return `None`. This will update the status of
+    /// [ImNativeAppChooserDialog::is_open()].
+    pub fn check(&mut self) -> Option<Result<T, DialogError>> {
+        self.worker.check()
+    }
+
+    /// Like [ImNativeAppChooserDialog::check()], but tells cancellation apart
+    /// from failures, see [DialogResult].
+    pub fn check_result(&mut self) -> Option<DialogResult<T>> {
+        self.worker.check_result()
+    }
+
+    /// Returns `true` if the chooser is currently open, otherwise
+    /// returns `false`. Requires a previous call of
+    /// [ImNativeAppChooserDialog::check()] to update the current
+    /// status.
+    pub fn is_open(&self) -> bool {
+        self.worker.is_open()
+    }
+
+    /// Returns the kind of the open chooser, or `None` if it isn't open,
+    /// see [crate::ImNativeFileDialog::kind()].
+    pub fn kind(&self) -> Option<DialogKind> {
+        self.worker.kind()
+    }
+
+    /// Returns a short description of the open chooser, or `None` if it
+    /// isn't open, see [crate::ImNativeFileDialog::status()].
+    pub fn status(&self) -> Option<String> {
+        self.worker.status()
+    }
+}
+
+/// Splits the `Exec` key of a desktop entry into arguments, which may
+/// be double quoted.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn split_exec(exec: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut quoted = false;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' if quoted => arg.extend(chars.next()),
+            c if c.is_whitespace() && !quoted => {
+                if !arg.is_empty() {
+                    args.push(std::mem::take(&mut arg));
+                }
+            }
+            c => arg.push(c),
+        }
+    }
+    if !arg.is_empty() {
+        args.push(arg);
+    }
+    args
+}
+
+/// The applications in the desktop entries found in the XDG data
+/// directories, excluding hidden entries.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn applications() -> Vec<(Application, Vec<String>)> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_owned());
+
+    let mut applications: Vec<(Application, Vec<String>)> = Vec::new();
+    let dirs = data_home
+        .into_iter()
+        .chain(data_dirs.split(':').map(PathBuf::from));
+    for dir in dirs {
+        let entries = match std::fs::read_dir(dir.join("applications")) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let id = entry.file_name().to_string_lossy().into_owned();
+            // Entries in earlier directories take precedence.
+            if !id.ends_with(".desktop") || applications.iter().any(|(app, _)| app.id == id) {
+                continue;
+            }
+            if let Ok(contents) = std::fs::read_to_string(entry.path()) {
+                if let Some(application) = parse_desktop_entry(id, &contents) {
+                    applications.push(application);
+                }
+            }
+        }
+    }
+    applications.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+    applications
+}
+
+/// Parses the `[Desktop Entry]` group of a desktop entry, returning
+/// the application and the MIME types it supports.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn parse_desktop_entry(id: String, contents: &str) -> Option<(Application, Vec<String>)> {
+    let mut in_entry = false;
+    let (mut name, mut exec, mut mime_types) = (None, None, Vec::new());
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        match line.split_once('=') {
+            Some(("Type", value)) if value != "Application" => return None,
+            Some(("NoDisplay" | "Hidden", "true")) => return None,
+            Some(("Name", value)) => name = Some(value.to_owned()),
+            Some(("Exec", value)) => exec = Some(value.to_owned()),
+            Some(("MimeType", value)) => {
+                mime_types = value
+                    .split(';')
+                    .filter(|mime_type| !mime_type.is_empty())
+                    .map(str::to_owned)
+                    .collect()
+            }
+            _ => {}
+        }
+    }
+
+    Some((
+        Application {
+            id,
+            name: name?,
+            exec: exec?,
+        },
+        mime_types,
+    ))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn app_chooser(title: &str, path: &Path) -> Result<Option<Application>, Error> {
+    use crate::command::{self, GnuProgram};
+    use std::process::Command;
+
+    let program = command::gnu_program().ok_or(Error::NoImplementation)?;
+
+    // Prefer the applications which support the type of the file, if it
+    // is known.
+    let mime_types: Vec<&str> = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| crate::mime::mime_types(extension).collect())
+        .unwrap_or_default();
+    let mut applications = applications();
+    let supported: Vec<_> = applications
+        .iter()
+        .filter(|(_, supported)| {
+            supported
+                .iter()
+                .any(|supported| mime_types.contains(&supported.as_str()))
+        })
+        .cloned()
+        .collect();
+    if !supported.is_empty() {
+        applications = supported;
+    }
+
+    let text = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let output = match program {
+        GnuProgram::KDialog => {
+            let mut kdialog = Command::new("kdialog");
+            kdialog.arg("--title").arg(title).arg("--menu").arg(&text);
+            for (application, _) in &applications {
+                kdialog.arg(&application.id).arg(&application.name);
+            }
+            command::run(&mut kdialog)?
+        }
+        GnuProgram::Zenity => {
+            let mut zenity = Command::new("zenity");
+            zenity
+                .arg("--list")
+                .arg(format!("--title={}", title))
+                .arg(format!("--text={}", text))
+                .args(["--column=Application", "--column=Id"])
+                .args(["--hide-column=2", "--print-column=2"]);
+            for (application, _) in &applications {
+                zenity.arg(&application.name).arg(&application.id);
+            }
+            command::run(&mut zenity)?
+        }
+    };
+
+    match output.filter(|id| !id.is_empty()) {
+        Some(id) => applications
+            .into_iter()
+            .map(|(application, _)| application)
+            .find(|application| application.id == id)
+            .map(Some)
+            .ok_or(Error::UnexpectedOutput("application chooser")),
+        None => Ok(None),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn app_chooser(title: &str, _path: &Path) -> Result<Option<Application>, Error> {
+    use crate::command::{applescript_string, osascript};
+
+    let output = osascript(&format!(
+        "POSIX path of (choose application with title {} as alias)",
+        applescript_string(title),
+    ))?;
+
+    Ok(output.map(|id| {
+        let id = id.trim_end_matches('/').to_owned();
+        let name = Path::new(&id)
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| id.clone());
+        Application {
+            id,
+            name,
+            exec: String::new(),
+        }
+    }))
+}
+
+#[cfg(target_os = "windows")]
+fn app_chooser(_title: &str, _path: &Path) -> Result<Option<Application>, Error> {
+    // SHOpenWithDialog opens the file itself without returning the
+    // chosen application.
+    Err(Error::NoImplementation)
+}
@@ -0,0 +1,172 @@
+use std::{
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use crate::{
+    panic_hook,
+    worker::{run_dialog, Notifier, Selection},
+    DialogError, DialogKind, DialogObserver, DialogOptions,
+};
+
+/// A wrapper around [native_dialog::FileDialog] for use with async
+/// applications, available with the `async-std` or `futures` features.
+/// The `show*()` methods return a [DialogFuture] which resolves to the
+/// result of the dialog, and can be awaited using any executor.
+///
+/// With the `async-std` feature the dialog runs using
+/// `async_std::task::spawn_blocking()`, otherwise it runs in a new
+/// thread which returns the result using a
+/// `futures::channel::oneshot` channel.
+#[derive(Default)]
+pub struct AsyncFileDialog {
+    observer: Option<Arc<dyn DialogObserver>>,
+}
+
+impl AsyncFileDialog {
+    /// Set an observer to use for every dialog shown by this object,
+    /// in addition to the observer set using [crate::set_observer()].
+    pub fn with_observer<O: DialogObserver + 'static>(&mut self, observer: O) -> &mut Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Shows a dialog that let users to open multiple files using [native_dialog::FileDialog::show_open_multiple_file()].
+    pub fn show_open_multiple_file(
+        &self,
+        options: impl Into<DialogOptions>,
+    ) -> DialogFuture<Vec<PathBuf>> {
+        let options = options.into();
+        self.spawn(DialogKind::OpenMultipleFile, options.clone(), move || {
+            options.show_open_multiple_file()
+        })
+    }
+
+    /// Shows a dialog that let users to open multiple directories, see
+    /// [crate::ImNativeFileDialog::show_open_multiple_dir()].
+    pub fn show_open_multiple_dir(
+        &self,
+        options: impl Into<DialogOptions>,
+    ) -> DialogFuture<Vec<PathBuf>> {
+        let options = options.into();
+        self.spawn(DialogKind::OpenMultipleDir, options.clone(), move || {
+            crate::open_multiple_dir(&options)
+        })
+    }
+
+    /// Shows a dialog that let users to open one directory using [native_dialog::FileDialog::show_open_single_dir()].
+    pub fn open_single_dir(
+        &self,
+        options: impl Into<DialogOptions>,
+    ) -> DialogFuture<Option<PathBuf>> {
+        let options = options.into();
+        self.spawn(DialogKind::OpenSingleDir, options.clone(), move || {
+            options.show_open_single_dir()
+        })
+    }
+
+    /// Shows a dialog that let users to open one file using [native_dialog::FileDialog::show_open_single_file()].
+    pub fn open_single_file(
+        &self,
+        options: impl Into<DialogOptions>,
+    ) -> DialogFuture<Option<PathBuf>> {
+        let options = options.into();
+        self.spawn(DialogKind::OpenSingleFile, options.clone(), move || {
+            options.show_open_single_file()
+        })
+    }
+
+    /// Shows a dialog that let users to save one file using [native_dialog::FileDialog::show_save_single_file()].
+    pub fn show_save_single_file(
+        &self,
+        options: impl Into<DialogOptions>,
+    ) -> DialogFuture<Option<PathBuf>> {
+        let options = options.into();
+        self.spawn(DialogKind::SaveSingleFile, options.clone(), move || {
+            options.show_save()
+        })
+    }
+
+    fn spawn<T, F>(&self, kind: DialogKind, options: DialogOptions, run: F) -> DialogFuture<T>
+    where
+        T: Selection + Send + 'static,
+        F: FnOnce() -> Result<T, native_dialog::Error> + Send + 'static,
+    {
+        let notifier = Notifier::new(kind, Some(options.clone()), self.observer.clone());
+        notifier.opened();
+
+        let thread_notifier = notifier.clone();
+        let run = move || {
+            let notifier = thread_notifier;
+            let result = panic_hook::catch(kind, Some(&options), || run_dialog(kind, run))
+                .unwrap_or_else(|| {
+                    Err(DialogError::Implementation(
+                        "dialog thread panicked".to_owned(),
+                    ))
+                });
+            notifier.closed(&result);
+            notifier.finished();
+            result
+        };
+
+        #[cfg(feature = "async-std")]
+        let handle = async_std::task::spawn_blocking(run);
+
+        #[cfg(not(feature = "async-std"))]
+        let handle = {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            let spawned = std::thread::Builder::new().spawn(move || {
+                // The future may have been dropped without awaiting it.
+                let _ = sender.send(run());
+            });
+            match spawned {
+                Ok(_) => receiver,
+                Err(error) => {
+                    let result: Result<(), DialogError> = Err(DialogError::Implementation(
+                        format!("failed to spawn dialog thread: {}", error),
+                    ));
+                    notifier.closed(&result);
+                    notifier.finished();
+
+                    let (sender, receiver) = futures::channel::oneshot::channel();
+                    let _ = sender.send(Err(DialogError::Io(error)));
+                    receiver
+                }
+            }
+        };
+
+        DialogFuture { handle }
+    }
+}
+
+/// The result of a dialog shown using [AsyncFileDialog], which
+/// resolves once the dialog is closed.
+pub struct DialogFuture<T> {
+    #[cfg(feature = "async-std")]
+    handle: async_std::task::JoinHandle<Result<T, DialogError>>,
+    #[cfg(not(feature = "async-std"))]
+    handle: futures::channel::oneshot::Receiver<Result<T, DialogError>>,
+}
+
+impl<T> Future for DialogFuture<T> {
+    type Output = Result<T, DialogError>;
+
+    #[cfg(feature = "async-std")]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.handle).poll(cx)
+    }
+
+    #[cfg(not(feature = "async-std"))]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.handle).poll(cx).map(|result| {
+            result.unwrap_or_else(|_| {
+                Err(DialogError::Implementation(
+                    "dialog thread panicked".to_owned(),
+                ))
+            })
+        })
+    }
+}
@@ -0,0 +1,207 @@
+use std::path::{Path, PathBuf};
+
+use crate::DialogError;
+
+/// A path chosen by the user, along with data which can be stored to
+/// access the path again after the application restarts, see
+/// [crate::ImNativeFileDialog::open_single_file_bookmark()].
+///
+/// Sandboxed macOS applications, such as those distributed using the
+/// App Store, lose access to a chosen path when they quit. On macOS the
+/// data is a security-scoped bookmark which restores access, and on
+/// other platforms it is the path itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub path: PathBuf,
+    pub data: Vec<u8>,
+}
+
+impl Bookmark {
+    /// Create a bookmark for `path`. On macOS this must be called
+    /// while the application has access to the path, such as straight
+    /// after it was chosen.
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self, DialogError> {
+        let path = path.into();
+        let data = imp::create(&path)?;
+        Ok(Self { path, data })
+    }
+
+    /// Resolve the bookmark `data` created using [Bookmark::create()],
+    /// and start accessing its path until the returned
+    /// [BookmarkAccess] is dropped.
+    pub fn resolve(data: &[u8]) -> Result<BookmarkAccess, DialogError> {
+        imp::resolve(data)
+    }
+}
+
+/// Access to the path of a resolved [Bookmark], which ends when this
+/// is dropped.
+#[derive(Debug)]
+pub struct BookmarkAccess {
+    path: PathBuf,
+    stale: bool,
+    /// Held to keep accessing the path until dropped.
+    #[cfg(target_os = "macos")]
+    _url: imp::ScopedUrl,
+}
+
+impl BookmarkAccess {
+    /// The path of the bookmark.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns `true` if the bookmark data is out of date, for example
+    /// because the file moved, in which case it should be replaced
+    /// using [Bookmark::create()] with [BookmarkAccess::path()].
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::{
+        ffi::CStr,
+        os::raw::c_char,
+        path::{Path, PathBuf},
+    };
+
+    use objc::{
+        class, msg_send,
+        runtime::{Object, BOOL, NO},
+        sel, sel_impl,
+    };
+
+    use super::BookmarkAccess;
+    use crate::{
+        macos::{autoreleased, string},
+        DialogError,
+    };
+
+    type Id = *mut Object;
+
+    const NIL: Id = std::ptr::null_mut();
+    const CREATION_WITH_SECURITY_SCOPE: usize = 1 << 11;
+    const RESOLUTION_WITH_SECURITY_SCOPE: usize = 1 << 10;
+
+    /// A retained `NSURL` which is accessing its security-scoped
+    /// resource.
+    #[derive(Debug)]
+    pub(super) struct ScopedUrl(Id);
+
+    // NSURL is immutable, and can be used from any thread.
+    unsafe impl Send for ScopedUrl {}
+    unsafe impl Sync for ScopedUrl {}
+
+    impl Drop for ScopedUrl {
+        fn drop(&mut self) {
+            unsafe {
+                let _: () = msg_send![self.0, stopAccessingSecurityScopedResource];
+                let _: () = msg_send![self.0, release];
+            }
+        }
+    }
+
+    unsafe fn to_string(string: Id) -> String {
+        let bytes: *const c_char = msg_send![string, UTF8String];
+        CStr::from_ptr(bytes).to_string_lossy().into_owned()
+    }
+
+    unsafe fn error(error: Id) -> DialogError {
+        if error.is_null() {
+            return DialogError::Implementation("unknown bookmark error".to_owned());
+        }
+        let description: Id = msg_send![error, localizedDescription];
+        DialogError::Implementation(to_string(description))
+    }
+
+    pub(super) fn create(path: &Path) -> Result<Vec<u8>, DialogError> {
+        let path = path.to_str().ok_or_else(|| {
+            DialogError::Implementation("bookmark path is not valid UTF-8".to_owned())
+        })?;
+
+        autoreleased(|| unsafe {
+            let url: Id = msg_send![class!(NSURL), fileURLWithPath: string(path)];
+            let mut failure: Id = NIL;
+            let data: Id = msg_send![url,
+                bookmarkDataWithOptions: CREATION_WITH_SECURITY_SCOPE
+                includingResourceValuesForKeys: NIL
+                relativeToURL: NIL
+                error: &mut failure];
+            if data.is_null() {
+                return Err(error(failure));
+            }
+
+            let length: usize = msg_send![data, length];
+            let bytes: *const u8 = msg_send![data, bytes];
+            Ok(std::slice::from_raw_parts(bytes, length).to_vec())
+        })
+    }
+
+    pub(super) fn resolve(data: &[u8]) -> Result<BookmarkAccess, DialogError> {
+        autoreleased(|| unsafe {
+            let data: Id =
+                msg_send![class!(NSData), dataWithBytes: data.as_ptr() length: data.len()];
+            let mut stale: BOOL = NO;
+            let mut failure: Id = NIL;
+            let url: Id = msg_send![class!(NSURL),
+                URLByResolvingBookmarkData: data
+                options: RESOLUTION_WITH_SECURITY_SCOPE
+                relativeToURL: NIL
+                bookmarkDataIsStale: &mut stale
+                error: &mut failure];
+            if url.is_null() {
+                return Err(error(failure));
+            }
+
+            let accessing: BOOL = msg_send![url, startAccessingSecurityScopedResource];
+            if accessing == NO {
+                return Err(DialogError::Implementation(
+                    "access to the bookmark was denied".to_owned(),
+                ));
+            }
+            let _: Id = msg_send![url, retain];
+            let url = ScopedUrl(url);
+
+            let path: Id = msg_send![url.0, path];
+            Ok(BookmarkAccess {
+                path: PathBuf::from(to_string(path)),
+                stale: stale != NO,
+                _url: url,
+            })
+        })
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod imp {
+    use std::path::{Path, PathBuf};
+
+    use super::BookmarkAccess;
+    use crate::DialogError;
+
+    pub(super) fn create(path: &Path) -> Result<Vec<u8>, DialogError> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            Ok(path.as_os_str().as_bytes().to_vec())
+        }
+        #[cfg(not(unix))]
+        {
+            Ok(path.to_string_lossy().into_owned().into_bytes())
+        }
+    }
+
+    pub(super) fn resolve(data: &[u8]) -> Result<BookmarkAccess, DialogError> {
+        #[cfg(unix)]
+        let path = {
+            use std::os::unix::ffi::OsStrExt;
+            PathBuf::from(std::ffi::OsStr::from_bytes(data))
+        };
+        #[cfg(not(unix))]
+        let path = PathBuf::from(String::from_utf8_lossy(data).into_owned());
+
+        Ok(BookmarkAccess { path, stale: false })
+    }
+}
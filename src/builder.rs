@@ -0,0 +1,135 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    guard::Group, worker::Worker, DialogObserver, DialogOptions, ExpiryPolicy, GuardPolicy,
+    ImNativeFileDialog,
+};
+
+/// What [ImNativeFileDialog::check()] returns when the dialog thread
+/// exits without sending a result, for example because a custom
+/// dialog shown using [ImNativeFileDialog::show()] dropped its sender,
+/// see [ImNativeFileDialogBuilder::on_disconnect()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DisconnectPolicy {
+    /// Return the same empty result as when the user cancels the
+    /// dialog.
+    #[default]
+    Default,
+    /// Return [crate::DialogError::Implementation].
+    Error,
+}
+
+/// A builder for an [ImNativeFileDialog], setting the configuration
+/// which applies to every dialog it shows in one place. Created using
+/// [ImNativeFileDialog::builder()].
+pub struct ImNativeFileDialogBuilder<T> {
+    worker: Worker<T>,
+}
+
+impl<T> Default for ImNativeFileDialogBuilder<T> {
+    fn default() -> Self {
+        Self {
+            worker: Worker::default(),
+        }
+    }
+}
+
+impl<T: Send + 'static + Default> ImNativeFileDialogBuilder<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the options which fill in the options that aren't set in
+    /// the options passed to each `show*()` method, such as a default
+    /// location or filters. Flags enabled in either are enabled, and
+    /// the default filters are only used if the dialog has none.
+    pub fn default_options(mut self, options: impl Into<DialogOptions>) -> Self {
+        self.worker.set_defaults(options.into());
+        self
+    }
+
+    /// Sets an observer, see [ImNativeFileDialog::with_observer()].
+    pub fn observer<O: DialogObserver + 'static>(mut self, observer: O) -> Self {
+        self.worker.set_observer(Arc::new(observer));
+        self
+    }
+
+    /// Sets a function to wake the ui, see
+    /// [ImNativeFileDialog::with_waker()].
+    pub fn waker<W>(mut self, waker: W) -> Self
+    where
+        W: Fn() + Send + Sync + 'static,
+    {
+        self.worker.set_wake(Arc::new(waker));
+        self
+    }
+
+    /// Sets the debounce window, see
+    /// [ImNativeFileDialog::with_debounce()].
+    pub fn debounce(mut self, window: Duration) -> Self {
+        self.worker.set_debounce(window);
+        self
+    }
+
+    /// Sets the watchdog threshold, see
+    /// [ImNativeFileDialog::with_watchdog()].
+    pub fn watchdog(mut self, threshold: Duration) -> Self {
+        self.worker.set_watchdog(threshold);
+        self
+    }
+
+    /// Sets the expiry policy, see
+    /// [ImNativeFileDialog::with_expiry()].
+    pub fn expiry(mut self, expiry: ExpiryPolicy) -> Self {
+        self.worker.set_expiry(expiry);
+        self
+    }
+
+    /// Sets the group, see [ImNativeFileDialog::with_group()].
+    pub fn group(mut self, name: impl Into<String>, policy: GuardPolicy) -> Self {
+        self.worker.set_group(Group {
+            name: name.into(),
+            policy,
+        });
+        self
+    }
+
+    /// Watches the selected paths, see
+    /// [ImNativeFileDialog::with_watcher()]. Available with the
+    /// `watch` feature.
+    #[cfg(feature = "watch")]
+    pub fn watcher(mut self) -> Self {
+        self.worker.enable_watcher();
+        self
+    }
+
+    /// Sets the name of the threads the dialogs are shown in, which
+    /// appears in debuggers and panic messages.
+    pub fn thread_name(mut self, name: impl Into<String>) -> Self {
+        self.worker.set_thread_name(name.into());
+        self
+    }
+
+    /// Sets the stack size in bytes of the threads the dialogs are
+    /// shown in, for backends or custom dialogs which need more than
+    /// the default.
+    pub fn stack_size(mut self, size: usize) -> Self {
+        self.worker.set_stack_size(size);
+        self
+    }
+
+    /// Sets what [ImNativeFileDialog::check()] returns when the dialog
+    /// thread exits without sending a result. Defaults to
+    /// [DisconnectPolicy::Default].
+    pub fn on_disconnect(mut self, policy: DisconnectPolicy) -> Self {
+        self.worker.set_disconnect_policy(policy);
+        self
+    }
+
+    pub fn build(self) -> ImNativeFileDialog<T> {
+        ImNativeFileDialog {
+            worker: self.worker,
+            stream: None,
+        }
+    }
+}
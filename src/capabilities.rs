@@ -0,0 +1,108 @@
+/// The backend used to show dialogs, see [capabilities()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// The Windows common dialogs.
+    Windows,
+    /// The macOS panels.
+    MacOs,
+    /// The `kdialog` program on GNU/Linux and BSD Unix.
+    KDialog,
+    /// The `zenity` program on GNU/Linux and BSD Unix.
+    Zenity,
+    /// The xdg-desktop-portal file chooser, which shows file dialogs
+    /// inside Flatpak and Snap sandboxes, see [crate::is_sandboxed()].
+    Portal,
+    /// Prompts on the terminal with the `terminal` feature, because
+    /// there is no display.
+    Terminal,
+    /// There is no backend available, dialogs return
+    /// [crate::DialogError::NoImplementation].
+    Unavailable,
+}
+
+/// What the active backend supports, returned by [capabilities()], so
+/// the ui can adapt to it instead of discovering limitations from the
+/// dialog results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Capabilities {
+    pub backend: Backend,
+    /// File filters set using [crate::DialogOptions::filter()] are
+    /// applied.
+    pub filters: bool,
+    /// Several directories can be chosen in one dialog.
+    /// [crate::ImNativeFileDialog::show_open_multiple_dir()] shows the
+    /// dialog repeatedly otherwise.
+    pub multiple_dirs: bool,
+    /// The title set using [crate::DialogOptions::title()] is shown.
+    pub title: bool,
+    /// Dialogs are attached to the window set using
+    /// [crate::DialogOptions::owner()].
+    pub owner: bool,
+    /// Users can choose [crate::MessageChoice::Cancel] in
+    /// [crate::ImNativeMessageDialog::show_yes_no_cancel()].
+    pub custom_buttons: bool,
+    /// Hidden files are shown when requested using
+    /// [crate::DialogOptions::show_hidden()].
+    pub hidden_files: bool,
+    /// The label set using [crate::DialogOptions::accept_label()] is
+    /// shown.
+    pub accept_label: bool,
+    /// Controls added using [crate::DialogOptions::checkbox()] and
+    /// [crate::DialogOptions::combo()] are shown.
+    pub choices: bool,
+    /// The filter selected when the dialog was confirmed is reported
+    /// by [crate::ImNativeFileDialog::show_save_single_file_filter()].
+    pub selected_filter: bool,
+}
+
+/// Returns what the backend used to show dialogs supports, which on
+/// GNU/Linux and BSD Unix depends on the dialog programs installed and
+/// the session.
+pub fn capabilities() -> Capabilities {
+    let backend = backend();
+    let shows_dialogs = !matches!(backend, Backend::Terminal | Backend::Unavailable);
+    Capabilities {
+        backend,
+        filters: shows_dialogs,
+        multiple_dirs: false,
+        title: backend != Backend::Unavailable,
+        owner: matches!(backend, Backend::Windows | Backend::MacOs),
+        custom_buttons: shows_dialogs,
+        hidden_files: matches!(backend, Backend::Windows | Backend::MacOs),
+        accept_label: matches!(backend, Backend::Windows | Backend::MacOs | Backend::Portal),
+        choices: matches!(backend, Backend::Windows | Backend::MacOs),
+        selected_filter: backend == Backend::Windows,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn backend() -> Backend {
+    Backend::Windows
+}
+
+#[cfg(target_os = "macos")]
+fn backend() -> Backend {
+    Backend::MacOs
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn backend() -> Backend {
+    use crate::command::{self, GnuProgram};
+
+    #[cfg(feature = "terminal")]
+    if crate::terminal::is_available() {
+        return Backend::Terminal;
+    }
+
+    if crate::portal::is_sandboxed() {
+        return Backend::Portal;
+    }
+
+    match command::gnu_program() {
+        Some(GnuProgram::KDialog) => Backend::KDialog,
+        Some(GnuProgram::Zenity) => Backend::Zenity,
+        None => Backend::Unavailable,
+    }
+}
@@ -0,0 +1,43 @@
+//! The channels used to return results to the ui, implemented using
+//! [crossbeam-channel](https://crates.io/crates/crossbeam-channel)
+//! with the default `crossbeam-channel` feature,
+//! [flume](https://crates.io/crates/flume) with the `flume` feature, or
+//! [std::sync::mpsc] with the `std-channel` feature.
+
+// The watchdog doesn't wait on a channel with the `inline` feature.
+#![cfg_attr(feature = "inline", allow(unused_imports))]
+
+#[cfg(feature = "flume")]
+pub(crate) use flume::{
+    bounded, unbounded, RecvTimeoutError, Sender as UnboundedSender, TryRecvError,
+};
+#[cfg(feature = "flume")]
+pub use flume::{Receiver, Sender};
+
+#[cfg(all(feature = "std-channel", not(feature = "flume")))]
+pub(crate) use std::sync::mpsc::{
+    channel as unbounded, sync_channel as bounded, RecvTimeoutError, Sender as UnboundedSender,
+    TryRecvError,
+};
+#[cfg(all(feature = "std-channel", not(feature = "flume")))]
+pub use std::sync::mpsc::{Receiver, SyncSender as Sender};
+
+#[cfg(all(
+    feature = "crossbeam-channel",
+    not(any(feature = "flume", feature = "std-channel"))
+))]
+pub(crate) use crossbeam_channel::{
+    bounded, unbounded, RecvTimeoutError, Sender as UnboundedSender, TryRecvError,
+};
+#[cfg(all(
+    feature = "crossbeam-channel",
+    not(any(feature = "flume", feature = "std-channel"))
+))]
+pub use crossbeam_channel::{Receiver, Sender};
+
+#[cfg(not(any(
+    feature = "crossbeam-channel",
+    feature = "flume",
+    feature = "std-channel"
+)))]
+compile_error!("one of the `crossbeam-channel`, `flume` or `std-channel` features must be enabled");
@@ -0,0 +1,117 @@
+use std::path::{Path, PathBuf};
+
+/// Facts about a chosen path, gathered in the dialog thread, returned
+/// in a [CheckedPath] so the ui can warn about read-only targets or
+/// insufficient space without blocking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PathChecks {
+    pub exists: bool,
+    pub is_dir: bool,
+    /// The path exists and is read-only.
+    pub readonly: bool,
+    /// The length of the file in bytes, if it exists.
+    pub len: Option<u64>,
+    /// The free space in bytes available to this process on the volume
+    /// of the path, or of its nearest existing ancestor for a path
+    /// which doesn't exist yet, if it can be read.
+    pub free_space: Option<u64>,
+    /// The path is on a network share, so reading it may block for a
+    /// long time, see [crate::DialogOptions::reject_network_paths()].
+    pub network: bool,
+    /// The path is a file whose contents are only stored online, so
+    /// reading it blocks until it is downloaded, see
+    /// [crate::DialogOptions::reject_cloud_placeholders()].
+    pub placeholder: bool,
+}
+
+impl PathChecks {
+    pub(crate) fn new(path: &Path) -> Self {
+        let metadata = path.metadata().ok();
+        let volume = path.ancestors().find(|ancestor| ancestor.exists());
+        Self {
+            exists: metadata.is_some(),
+            is_dir: metadata.as_ref().is_some_and(|metadata| metadata.is_dir()),
+            readonly: metadata
+                .as_ref()
+                .is_some_and(|metadata| metadata.permissions().readonly()),
+            len: metadata
+                .as_ref()
+                .filter(|metadata| metadata.is_file())
+                .map(|metadata| metadata.len()),
+            free_space: volume.and_then(free_space),
+            network: crate::remote::is_network(path),
+            placeholder: crate::remote::is_placeholder(path),
+        }
+    }
+}
+
+/// A path chosen using
+/// [crate::ImNativeFileDialog::open_single_file_checked()] or
+/// [crate::ImNativeFileDialog::show_save_single_file_checked()], along
+/// with its [PathChecks].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckedPath {
+    pub path: PathBuf,
+    /// The path as chosen, when [CheckedPath::path] is the target of
+    /// symbolic links resolved using
+    /// [crate::DialogOptions::resolve_symlinks()].
+    pub link: Option<PathBuf>,
+    pub checks: PathChecks,
+}
+
+impl CheckedPath {
+    /// Checks `path`, first resolving its symbolic links if
+    /// `resolve_symlinks` is set.
+    pub(crate) fn new(path: PathBuf, resolve_symlinks: bool) -> Self {
+        let (path, link) = match resolve_symlinks
+            .then(|| crate::paths::resolve_symlinks(&path))
+            .flatten()
+        {
+            Some(resolved) => (resolved, Some(path)),
+            None => (path, None),
+        };
+        let checks = PathChecks::new(&path);
+        Self { path, link, checks }
+    }
+}
+
+#[cfg(unix)]
+fn free_space(path: &Path) -> Option<u64> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // Safety: `path` is a valid C string, and `stat` is only read once
+    // statvfs has initialized it.
+    let stat = unsafe {
+        if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return None;
+        }
+        stat.assume_init()
+    };
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(target_os = "windows")]
+fn free_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::{fileapi::GetDiskFreeSpaceExW, winnt::ULARGE_INTEGER};
+
+    let path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+    // Safety: `path` is nul terminated, and the totals which aren't
+    // needed may be null.
+    let succeeded = unsafe {
+        GetDiskFreeSpaceExW(
+            path.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if succeeded == 0 {
+        return None;
+    }
+    Some(unsafe { *available.QuadPart() })
+}
@@ -0,0 +1,58 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// A control added to a file dialog using
+/// [crate::DialogOptions::checkbox()] or
+/// [crate::DialogOptions::combo()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Choice {
+    Checkbox {
+        label: String,
+        checked: bool,
+    },
+    Combo {
+        label: String,
+        items: Vec<String>,
+        selected: usize,
+    },
+}
+
+impl Choice {
+    /// The value of the control before the user changes it.
+    pub(crate) fn initial(&self) -> ChoiceValue {
+        match self {
+            Choice::Checkbox { checked, .. } => ChoiceValue::Checked(*checked),
+            Choice::Combo { selected, .. } => ChoiceValue::Selected(*selected),
+        }
+    }
+}
+
+/// The value of a control added using
+/// [crate::DialogOptions::checkbox()] or
+/// [crate::DialogOptions::combo()] when the dialog was confirmed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChoiceValue {
+    /// Whether a checkbox was checked.
+    Checked(bool),
+    /// The index of the item selected in a combo box.
+    Selected(usize),
+}
+
+/// Where the backend stores the values of the controls once the dialog
+/// is confirmed.
+pub(crate) type ChoiceValues = Arc<Mutex<Option<Vec<ChoiceValue>>>>;
+
+/// A chosen path along with the values of the controls added to the
+/// dialog, returned by
+/// [crate::ImNativeFileDialog::open_single_file_choices()] and
+/// [crate::ImNativeFileDialog::show_save_single_file_choices()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChoicePath {
+    pub path: PathBuf,
+    /// The value of each control, in the order they were added. This
+    /// holds the initial values with backends which don't support
+    /// controls, see [crate::Capabilities::choices].
+    pub choices: Vec<ChoiceValue>,
+}
@@ -0,0 +1,63 @@
+//! Reading a path from the system clipboard, see
+//! [crate::ImNativeFileDialog::paste_path()].
+
+use std::path::PathBuf;
+
+use native_dialog::Error;
+
+/// Returns the path on the clipboard, or `None` if the clipboard is
+/// empty or doesn't contain the path of an existing file or directory.
+pub(crate) fn path() -> Result<Option<PathBuf>, Error> {
+    Ok(read()?.as_deref().and_then(parse))
+}
+
+/// Parses the first line of `text` as a path, which may be quoted (as
+/// copied by Windows Explorer) or a `file://` URI (as copied by most
+/// file managers).
+fn parse(text: &str) -> Option<PathBuf> {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line
+        .strip_prefix('"')
+        .and_then(|line| line.strip_suffix('"'))
+        .unwrap_or(line);
+    let path = match line.strip_prefix("file://") {
+        Some(uri) => PathBuf::from(crate::portal::percent_decode(uri)?),
+        None => PathBuf::from(line),
+    };
+
+    if path.exists() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn read() -> Result<Option<String>, Error> {
+    use std::process::Command;
+
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some_and(|display| !display.is_empty());
+    if wayland && which::which("wl-paste").is_ok() {
+        crate::command::run(Command::new("wl-paste").arg("--no-newline"))
+    } else if which::which("xclip").is_ok() {
+        crate::command::run(Command::new("xclip").args(["-selection", "clipboard", "-o"]))
+    } else if which::which("xsel").is_ok() {
+        crate::command::run(Command::new("xsel").args(["--clipboard", "--output"]))
+    } else {
+        Err(Error::NoImplementation)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read() -> Result<Option<String>, Error> {
+    crate::command::run(&mut std::process::Command::new("pbpaste"))
+}
+
+#[cfg(target_os = "windows")]
+fn read() -> Result<Option<String>, Error> {
+    // Files copied in Explorer are a file drop list rather than text.
+    crate::command::powershell(
+        "$files = Get-Clipboard -Format FileDropList; \
+        if ($files) { $files[0].FullName } else { Get-Clipboard -Raw }",
+    )
+}
@@ -0,0 +1,33 @@
+//! The time used for debouncing and measuring how long dialogs are
+//! open, which can be advanced in tests with the `testing` feature, see
+//! [crate::testing::MockBackend::advance()].
+
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "testing")]
+static OFFSET: std::sync::Mutex<Duration> = std::sync::Mutex::new(Duration::ZERO);
+
+/// The current time.
+pub(crate) fn now() -> Instant {
+    #[cfg(feature = "testing")]
+    return Instant::now() + *OFFSET.lock().expect("clock lock poisoned");
+    #[cfg(not(feature = "testing"))]
+    Instant::now()
+}
+
+/// The time elapsed since `instant`.
+pub(crate) fn elapsed(instant: Instant) -> Duration {
+    now().saturating_duration_since(instant)
+}
+
+/// Moves the time returned by [now()] forward by `duration`.
+#[cfg(feature = "testing")]
+pub(crate) fn advance(duration: Duration) {
+    *OFFSET.lock().expect("clock lock poisoned") += duration;
+}
+
+/// Moves the time returned by [now()] back to the real time.
+#[cfg(feature = "testing")]
+pub(crate) fn reset() {
+    *OFFSET.lock().expect("clock lock poisoned") = Duration::ZERO;
+}
@@ -0,0 +1,239 @@
+use native_dialog::Error;
+
+use crate::{
+    command, worker::Worker, DialogError, DialogKind, DialogResult, ImNativeDialogError,
+    RequestToken,
+};
+
+/// A color chosen using [ImNativeColorDialog].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Rgba {
+    /// Create an opaque color.
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+}
+
+/// A color picker for use with immediate mode gui libraries,
+/// following the same pattern as [crate::ImNativeFileDialog]. The
+/// `show*()` methods show the platform's color picker in a new
+/// thread, and the result is returned to this object ready to be
+/// polled by the ui using [ImNativeColorDialog::check()].
+pub struct ImNativeColorDialog<T> {
+    worker: Worker<T>,
+}
+
+impl<T> Default for ImNativeColorDialog<T> {
+    fn default() -> Self {
+        Self {
+            worker: Worker::default(),
+        }
+    }
+}
+
+impl ImNativeColorDialog<Option<Rgba>> {
+    /// Shows a color picker, initially set to `initial`. The result is
+    /// `None` if the user cancelled the picker. Not every platform
+    /// picker lets users choose the alpha, in which case the alpha of
+    /// `initial` is returned.
+    pub fn show_color(
+        &mut self,
+        title: impl Into<String>,
+        initial: Rgba,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let title = title.into();
+        self.worker
+            .spawn(DialogKind::Color, None, move || color(&title, initial))
+    }
+}
+
+impl<T: Send + 'static + Default> ImNativeColorDialog<T> {
+    /// Set a callback to use for this dialog which will be called
+    /// immediately upon dialog close in the dialog monitoring thread.
+    pub fn with_callback<C>(&mut self, callback: C) -> &mut Self
+    where
+        C: FnOnce(&Result<T, DialogError>) + Send + 'static,
+    {
+        self.worker.set_callback(Box::new(callback));
+        self
+    }
+
+    /// Check if the picker is complete. If it is complete it will
+    /// return `Some` with the result of the picker, otherwise will
+    /// return `None`. This will update the status of
+    /// [ImNativeColorDialog::is_open()].
+    pub fn check(&mut self) -> Option<Result<T, DialogError>> {
+        self.worker.check()
+    }
+
+    /// Like [ImNativeColorDialog::check()], but tells cancellation apart
+    /// from failures, see [DialogResult].
+    pub fn check_result(&mut self) -> Option<DialogResult<T>> {
+        self.worker.check_result()
+    }
+
+    /// Returns `true` if the picker is currently open, otherwise
+    /// returns `false`. Requires a previous call of
+    /// [ImNativeColorDialog::check()] to update the current status.
+    pub fn is_open(&self) -> bool {
+        self.worker.is_open()
+    }
+
+    /// Returns the kind of the open picker, or `None` if it isn't open,
+    /// see [crate::ImNativeFileDialog::kind()].
+    pub fn kind(&self) -> Option<DialogKind> {
+        self.worker.kind()
+    }
+
+    /// Returns a short description of the open picker, or `None` if it
+    /// isn't open, see [crate::ImNativeFileDialog::status()].
+    pub fn status(&self) -> Option<String> {
+        self.worker.status()
+    }
+}
+
+/// Parses a comma separated list of integer components.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn components(list: &str) -> Option<Vec<u32>> {
+    list.split(',')
+        .map(|component| component.trim().parse().ok())
+        .collect()
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn color(title: &str, initial: Rgba) -> Result<Option<Rgba>, Error> {
+    use std::process::Command;
+
+    let hex = format!("#{:02x}{:02x}{:02x}", initial.r, initial.g, initial.b);
+    let mut command = match command::gnu_program() {
+        Some(command::GnuProgram::KDialog) => {
+            let mut command = Command::new("kdialog");
+            command
+                .arg("--title")
+                .arg(title)
+                .arg("--getcolor")
+                .arg("--default")
+                .arg(hex);
+            command
+        }
+        Some(command::GnuProgram::Zenity) => {
+            let mut command = Command::new("zenity");
+            command
+                .arg("--color-selection")
+                .arg(format!("--title={}", title))
+                .arg(format!("--color={}", hex));
+            command
+        }
+        None => return Err(Error::NoImplementation),
+    };
+
+    match command::run(&mut command)? {
+        Some(output) => parse_gnu(&output, initial.a)
+            .map(Some)
+            .ok_or(Error::UnexpectedOutput("color")),
+        None => Ok(None),
+    }
+}
+
+/// Parses the `#rrggbb` (kdialog), `rgb(r,g,b)` or `rgba(r,g,b,a)`
+/// (zenity) formats.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn parse_gnu(output: &str, alpha: u8) -> Option<Rgba> {
+    let output = output.trim();
+    if let Some(hex) = output.strip_prefix('#') {
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        return match hex.len() {
+            6 => Some(Rgba {
+                r: (value >> 16) as u8,
+                g: (value >> 8) as u8,
+                b: value as u8,
+                a: alpha,
+            }),
+            _ => None,
+        };
+    }
+
+    let (list, has_alpha) = if let Some(list) = output.strip_prefix("rgba(") {
+        (list.strip_suffix(')')?, true)
+    } else {
+        (output.strip_prefix("rgb(")?.strip_suffix(')')?, false)
+    };
+
+    let parts: Vec<&str> = list.split(',').map(str::trim).collect();
+    let (rgb, a) = match (parts.as_slice(), has_alpha) {
+        ([r, g, b], false) => ([r, g, b], alpha),
+        ([r, g, b, a], true) => {
+            let a: f32 = a.parse().ok()?;
+            ([r, g, b], (a.clamp(0.0, 1.0) * 255.0).round() as u8)
+        }
+        _ => return None,
+    };
+
+    Some(Rgba {
+        r: rgb[0].parse().ok()?,
+        g: rgb[1].parse().ok()?,
+        b: rgb[2].parse().ok()?,
+        a,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn color(title: &str, initial: Rgba) -> Result<Option<Rgba>, Error> {
+    // The title is not supported by `choose color`, which uses 16 bit
+    // components.
+    let _ = title;
+    let output = command::osascript(&format!(
+        "choose color default color {{{}, {}, {}}}",
+        u32::from(initial.r) * 257,
+        u32::from(initial.g) * 257,
+        u32::from(initial.b) * 257,
+    ))?;
+
+    match output {
+        Some(output) => match components(&output).as_deref() {
+            Some([r, g, b]) => Ok(Some(Rgba {
+                r: (r / 257) as u8,
+                g: (g / 257) as u8,
+                b: (b / 257) as u8,
+                a: initial.a,
+            })),
+            _ => Err(Error::UnexpectedOutput("osascript")),
+        },
+        None => Ok(None),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn color(title: &str, initial: Rgba) -> Result<Option<Rgba>, Error> {
+    // ColorDialog has no title.
+    let _ = title;
+    let output = command::powershell(&format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+        $dialog = New-Object System.Windows.Forms.ColorDialog; \
+        $dialog.FullOpen = $true; \
+        $dialog.Color = [System.Drawing.Color]::FromArgb({}, {}, {}); \
+        if ($dialog.ShowDialog() -ne 'OK') {{ exit 1 }}; \
+        '{{0}},{{1}},{{2}}' -f $dialog.Color.R, $dialog.Color.G, $dialog.Color.B",
+        initial.r, initial.g, initial.b,
+    ))?;
+
+    match output {
+        Some(output) => match components(&output).as_deref() {
+            Some([r, g, b]) => Ok(Some(Rgba {
+                r: *r as u8,
+                g: *g as u8,
+                b: *b as u8,
+                a: initial.a,
+            })),
+            _ => Err(Error::UnexpectedOutput("powershell")),
+        },
+        None => Ok(None),
+    }
+}
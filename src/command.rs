@@ -0,0 +1,246 @@
+//! Helpers for showing the dialogs which [native_dialog] doesn't
+//! provide, by running the platform's dialog programs (zenity/kdialog,
+//! osascript or PowerShell).
+
+use std::process::Command;
+
+use native_dialog::Error;
+
+/// The dialog program available on GNU/Linux and BSD Unix.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) enum GnuProgram {
+    KDialog,
+    Zenity,
+}
+
+/// Chooses the dialog program in the same way as [native_dialog],
+/// preferring kdialog in a KDE session and zenity otherwise.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) fn gnu_program() -> Option<GnuProgram> {
+    let has_display = match std::env::var("DISPLAY") {
+        Ok(display) => !display.is_empty(),
+        _ => false,
+    };
+
+    if !has_display {
+        return None;
+    }
+
+    let kdialog_available = which::which("kdialog").is_ok();
+    if let Ok(desktop) = std::env::var("XDG_CURRENT_DESKTOP") {
+        if kdialog_available && desktop == "KDE" {
+            return Some(GnuProgram::KDialog);
+        }
+    }
+
+    if which::which("zenity").is_ok() {
+        return Some(GnuProgram::Zenity);
+    }
+
+    if kdialog_available {
+        return Some(GnuProgram::KDialog);
+    }
+
+    None
+}
+
+/// Shows the file dialog described by `options` and `prompt` using
+/// `program`, returning the chosen paths, which are empty if the user
+/// cancelled the dialog. [native_dialog] chooses the program itself,
+/// so this is used when it is chosen using
+/// [crate::DialogOptions::backend()].
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) fn show_file_dialog(
+    program: GnuProgram,
+    options: &crate::DialogOptions,
+    prompt: crate::options::Prompt,
+    multiple: bool,
+) -> Result<Vec<std::path::PathBuf>, Error> {
+    use crate::{options::Prompt, Theme};
+
+    let preselection = options.preselection(false);
+    let start = match &preselection {
+        Some((dir, name)) => Some(dir.join(name)),
+        None => options.location.clone(),
+    };
+    let filters = options.filters.iter().map(|filter| {
+        let patterns: Vec<String> = filter
+            .extensions
+            .iter()
+            .map(|extension| format!("*.{}", extension))
+            .collect();
+        (filter.description.as_str(), patterns.join(" "))
+    });
+
+    let mut command = match program {
+        GnuProgram::KDialog => {
+            let mut command = Command::new("kdialog");
+            command.arg(match prompt {
+                Prompt::File => "--getopenfilename",
+                Prompt::Dir => "--getexistingdirectory",
+                Prompt::Save => "--getsavefilename",
+            });
+            command.arg(start.unwrap_or_else(|| ".".into()));
+            if prompt != Prompt::Dir {
+                let filters: Vec<String> = filters
+                    .map(|(description, patterns)| format!("{} ({})", description, patterns))
+                    .collect();
+                command.arg(filters.join("\n"));
+            }
+            if multiple {
+                command.args(["--multiple", "--separate-output"]);
+            }
+            if let Some(title) = &options.title {
+                command.arg("--title").arg(title);
+            }
+            command
+        }
+        GnuProgram::Zenity => {
+            let mut command = Command::new("zenity");
+            command.args(["--file-selection", "--separator=\n"]);
+            match prompt {
+                Prompt::File => {}
+                Prompt::Dir => {
+                    command.arg("--directory");
+                }
+                Prompt::Save => {
+                    command.arg("--save");
+                }
+            }
+            if multiple {
+                command.arg("--multiple");
+            }
+            if let Some(mut start) = start.and_then(|start| start.to_str().map(str::to_owned)) {
+                // A trailing separator opens the directory rather
+                // than selecting it.
+                if preselection.is_none() && !start.ends_with('/') {
+                    start.push('/');
+                }
+                command.arg(format!("--filename={}", start));
+            }
+            if prompt != Prompt::Dir {
+                for (description, patterns) in filters {
+                    command.arg(format!("--file-filter={} | {}", description, patterns));
+                }
+            }
+            if let Some(title) = &options.title {
+                command.arg(format!("--title={}", title));
+            }
+            command
+        }
+    };
+    if let Some(locale) = &options.locale {
+        command
+            .env("LANGUAGE", locale)
+            .env("LC_MESSAGES", format!("{}.UTF-8", locale));
+    }
+    match (program, options.theme) {
+        (GnuProgram::Zenity, Some(Theme::Dark)) => {
+            // GTK 3 reads the theme variant, and libadwaita the color
+            // scheme.
+            command
+                .env("GTK_THEME", "Adwaita:dark")
+                .env("ADW_DEBUG_COLOR_SCHEME", "prefer-dark");
+        }
+        (GnuProgram::Zenity, Some(Theme::Light)) => {
+            command
+                .env("GTK_THEME", "Adwaita")
+                .env("ADW_DEBUG_COLOR_SCHEME", "prefer-light");
+        }
+        _ => {}
+    }
+    Ok(run(&mut command)?
+        .map(|output| {
+            output
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(std::path::PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Runs `command` and returns its output without the trailing line
+/// ending, or `None` if the user cancelled the dialog (exit code 1).
+pub(crate) fn run(command: &mut Command) -> Result<Option<String>, Error> {
+    let output = command.output()?;
+
+    if output.status.success() {
+        let mut stdout = String::from_utf8(output.stdout)?;
+        if stdout.ends_with('\n') {
+            stdout.pop();
+        }
+        if stdout.ends_with('\r') {
+            stdout.pop();
+        }
+        Ok(Some(stdout))
+    } else if output.status.code() == Some(1) {
+        Ok(None)
+    } else {
+        Err(Error::ImplementationError(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ))
+    }
+}
+
+/// Runs an AppleScript `script` using `osascript`, see [run()].
+#[cfg(target_os = "macos")]
+pub(crate) fn osascript(script: &str) -> Result<Option<String>, Error> {
+    run(Command::new("osascript").arg("-e").arg(script))
+}
+
+/// Quotes `value` as an AppleScript string literal.
+#[cfg(target_os = "macos")]
+pub(crate) fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Runs a PowerShell `script` without showing a console window, see
+/// [run()]. The script should `exit 1` if the user cancelled.
+#[cfg(target_os = "windows")]
+pub(crate) fn powershell(script: &str) -> Result<Option<String>, Error> {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let script = format!(
+        "[Console]::OutputEncoding = [System.Text.Encoding]::UTF8; {}",
+        script
+    );
+    run(Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command"])
+        .arg(script)
+        .creation_flags(CREATE_NO_WINDOW))
+}
+
+/// Quotes `value` as a PowerShell string literal.
+#[cfg(target_os = "windows")]
+pub(crate) fn powershell_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    for c in value.chars() {
+        // PowerShell also ends single quoted strings at the typographic
+        // single quotes, which are escaped by doubling them too.
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doubles_every_single_quote_in_powershell_strings() {
+        assert_eq!(powershell_string("it's"), "'it''s'");
+        assert_eq!(
+            powershell_string("\u{2018}a\u{2019}b\u{201A}c\u{201B}"),
+            "'\u{2018}\u{2018}a\u{2019}\u{2019}b\u{201A}\u{201A}c\u{201B}\u{201B}'"
+        );
+        assert_eq!(powershell_string("$env:PATH \"`n\""), "'$env:PATH \"`n\"'");
+    }
+}
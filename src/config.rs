@@ -0,0 +1,67 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use crate::{Backend, DialogOptions, DisconnectPolicy};
+
+/// Configuration applied to every dialog object created after
+/// [init()], so applications with many dialogs don't repeat it for
+/// each of them. Settings made on a dialog object take precedence.
+#[derive(Clone, Default)]
+pub struct GlobalConfig {
+    /// The location file dialogs open at, when they aren't given one.
+    pub default_location: Option<PathBuf>,
+    /// The description and extensions of the filters for file dialogs
+    /// which aren't given any, see [crate::DialogOptions::filter()].
+    pub filters: Vec<(String, Vec<String>)>,
+    /// A function to wake the ui, see
+    /// [crate::ImNativeFileDialog::with_waker()].
+    pub waker: Option<Arc<dyn Fn() + Send + Sync>>,
+    /// The name of the dialog threads, see
+    /// [crate::ImNativeFileDialogBuilder::thread_name()].
+    pub thread_name: Option<String>,
+    /// The stack size of the dialog threads, see
+    /// [crate::ImNativeFileDialogBuilder::stack_size()].
+    pub stack_size: Option<usize>,
+    /// See [crate::ImNativeFileDialogBuilder::on_disconnect()].
+    pub disconnect: DisconnectPolicy,
+    /// The backend file dialogs are shown using, see
+    /// [crate::DialogOptions::backend()].
+    pub backend: Option<Backend>,
+}
+
+impl GlobalConfig {
+    /// The default options for file dialogs.
+    pub(crate) fn options(&self) -> Option<DialogOptions> {
+        if self.default_location.is_none() && self.filters.is_empty() && self.backend.is_none() {
+            return None;
+        }
+        let mut options = DialogOptions::new();
+        if let Some(location) = &self.default_location {
+            options = options.location(location);
+        }
+        for (description, extensions) in &self.filters {
+            let extensions: Vec<&str> = extensions.iter().map(String::as_str).collect();
+            options = options.filter(description, &extensions);
+        }
+        if let Some(backend) = self.backend {
+            options = options.backend(backend);
+        }
+        Some(options)
+    }
+}
+
+static CONFIG: RwLock<Option<GlobalConfig>> = RwLock::new(None);
+
+/// Set the configuration for every dialog object created from now on,
+/// replacing any previous configuration. Call this once at startup,
+/// before creating dialogs.
+pub fn init(config: GlobalConfig) {
+    *CONFIG.write().expect("config lock poisoned") = Some(config);
+}
+
+/// The configuration set using [init()].
+pub(crate) fn global_config() -> Option<GlobalConfig> {
+    CONFIG.read().expect("config lock poisoned").clone()
+}
@@ -0,0 +1,187 @@
+use std::{fmt, path::PathBuf, sync::Mutex};
+
+use crate::{capabilities, Backend};
+
+static DIAGNOSTICS: Mutex<Option<Diagnostics>> = Mutex::new(None);
+
+/// What this crate detected about the platform and session to choose
+/// the backend, returned by [diagnostics()], to include in bug reports
+/// about dialogs which don't appear. The [fmt::Display] implementation
+/// writes one `key: value` line per field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Diagnostics {
+    pub backend: Backend,
+    /// The operating system, such as `linux`, see
+    /// [std::env::consts::OS].
+    pub os: &'static str,
+    /// The display server the session runs on, `wayland` or `x11`,
+    /// or `None` without a display.
+    pub display_server: Option<&'static str>,
+    /// The `DISPLAY` of the session.
+    pub display: Option<String>,
+    /// The desktop of the session, from `XDG_CURRENT_DESKTOP`.
+    pub desktop: Option<String>,
+    /// The sandbox the application runs inside, `flatpak` or `snap`.
+    pub sandbox: Option<&'static str>,
+    /// The path of the dialog program, zenity or kdialog.
+    pub program: Option<PathBuf>,
+    /// The version the dialog program reports.
+    pub program_version: Option<String>,
+    /// The major version of GTK used by zenity, which is 4 from zenity
+    /// 4.0.
+    pub gtk_version: Option<u32>,
+    /// The version of the xdg-desktop-portal file chooser interface.
+    pub portal_version: Option<u32>,
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn line<T: fmt::Display>(
+            f: &mut fmt::Formatter<'_>,
+            key: &str,
+            value: Option<T>,
+        ) -> fmt::Result {
+            match value {
+                Some(value) => writeln!(f, "{}: {}", key, value),
+                None => writeln!(f, "{}: none", key),
+            }
+        }
+
+        writeln!(f, "backend: {:?}", self.backend)?;
+        writeln!(f, "os: {}", self.os)?;
+        line(f, "display server", self.display_server)?;
+        line(f, "display", self.display.as_deref())?;
+        line(f, "desktop", self.desktop.as_deref())?;
+        line(f, "sandbox", self.sandbox)?;
+        line(
+            f,
+            "program",
+            self.program.as_ref().map(|path| path.display()),
+        )?;
+        line(f, "program version", self.program_version.as_deref())?;
+        line(f, "gtk version", self.gtk_version)?;
+        line(f, "portal version", self.portal_version)
+    }
+}
+
+/// Returns what this crate detected about the platform and session,
+/// which on GNU/Linux and BSD Unix runs the dialog program and `gdbus`
+/// to ask for their versions. This is detected once, when first
+/// called or when the first dialog is shown, and logged then with the
+/// `log` or `tracing` features.
+pub fn diagnostics() -> Diagnostics {
+    DIAGNOSTICS
+        .lock()
+        .expect("diagnostics lock poisoned")
+        .get_or_insert_with(detect)
+        .clone()
+}
+
+/// Logs the [diagnostics()] the first time it is called, from the
+/// thread of the first dialog, so detecting them doesn't block the ui.
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub(crate) fn log_once() {
+    static LOGGED: std::sync::Once = std::sync::Once::new();
+    LOGGED.call_once(|| {
+        let diagnostics = diagnostics();
+        #[cfg(feature = "log")]
+        log::info!("Dialog diagnostics:\n{}", diagnostics);
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            backend = ?diagnostics.backend,
+            os = diagnostics.os,
+            display_server = diagnostics.display_server,
+            display = diagnostics.display.as_deref(),
+            desktop = diagnostics.desktop.as_deref(),
+            sandbox = diagnostics.sandbox,
+            program = ?diagnostics.program,
+            program_version = diagnostics.program_version.as_deref(),
+            gtk_version = diagnostics.gtk_version,
+            portal_version = diagnostics.portal_version,
+            "dialog diagnostics"
+        );
+    });
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn non_empty_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn detect() -> Diagnostics {
+    use crate::command::{self, GnuProgram};
+
+    let display = non_empty_var("DISPLAY");
+    let display_server = match non_empty_var("XDG_SESSION_TYPE").as_deref() {
+        Some("wayland") => Some("wayland"),
+        Some("x11") => Some("x11"),
+        _ if non_empty_var("WAYLAND_DISPLAY").is_some() => Some("wayland"),
+        _ if display.is_some() => Some("x11"),
+        _ => None,
+    };
+    let sandbox = if crate::is_flatpak() {
+        Some("flatpak")
+    } else if crate::is_snap() {
+        Some("snap")
+    } else {
+        None
+    };
+    let name = command::gnu_program().map(|program| match program {
+        GnuProgram::KDialog => "kdialog",
+        GnuProgram::Zenity => "zenity",
+    });
+    let program = name.and_then(|name| which::which(name).ok());
+    let program_version = program.as_ref().and_then(|program| {
+        let output = std::process::Command::new(program)
+            .arg("--version")
+            .output()
+            .ok()?;
+        let version = String::from_utf8_lossy(&output.stdout);
+        // kdialog prints its name before the version.
+        let version = version.trim().trim_start_matches("kdialog").trim();
+        (!version.is_empty()).then(|| version.to_owned())
+    });
+    let gtk_version = match (name, &program_version) {
+        (Some("zenity"), Some(version)) => version
+            .split('.')
+            .next()
+            .and_then(|major| major.parse::<u32>().ok())
+            .and_then(|major| match major {
+                3 => Some(3),
+                major if major >= 4 => Some(4),
+                _ => None,
+            }),
+        _ => None,
+    };
+
+    Diagnostics {
+        backend: capabilities().backend,
+        os: std::env::consts::OS,
+        display_server,
+        display,
+        desktop: non_empty_var("XDG_CURRENT_DESKTOP"),
+        sandbox,
+        program,
+        program_version,
+        gtk_version,
+        portal_version: crate::portal::file_chooser_version(),
+    }
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn detect() -> Diagnostics {
+    Diagnostics {
+        backend: capabilities().backend,
+        os: std::env::consts::OS,
+        display_server: None,
+        display: None,
+        desktop: None,
+        sandbox: None,
+        program: None,
+        program_version: None,
+        gtk_version: None,
+        portal_version: None,
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+#[cfg(feature = "replay")]
+use crate::DialogOutcome;
+use crate::{worker::Selection, DialogError, DialogOptions};
+
+/// A kind of dialog defined outside this crate, such as picking a
+/// project file and parsing its manifest, shown using
+/// [crate::ImNativeFileDialog::show_request()] or the dialogs of a
+/// [crate::DialogManager]. Requests are shown as
+/// [crate::DialogKind::Request], so they are queued by dialog groups,
+/// reported to observers and answered by a
+/// `testing::MockBackend` like the dialogs of this crate.
+pub trait DialogRequest: Send + 'static {
+    /// The result of the dialog.
+    type Output: Send + 'static;
+
+    /// Shows the dialog in the dialog thread using `options`, which
+    /// have been filled in from the defaults of the object showing it,
+    /// returning `None` if the user cancelled it.
+    fn show(self, options: DialogOptions) -> Result<Option<Self::Output>, DialogError>;
+
+    /// The paths `output` refers to, reported to observers and
+    /// recorded in the [crate::DialogOutcome] of the dialog.
+    fn paths(_output: &Self::Output) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Recreates the result from the paths of a recorded or mocked
+    /// dialog, or returns `None` if it can't be, in which case the
+    /// dialog can't be replayed.
+    fn from_paths(_paths: &[PathBuf]) -> Option<Self::Output> {
+        None
+    }
+}
+
+/// The result of a [DialogRequest], so observers and the mock backend
+/// can handle it like the results of this crate.
+pub(crate) struct Requested<R: DialogRequest>(pub(crate) Option<R::Output>);
+
+impl<R: DialogRequest> Selection for Requested<R> {
+    fn is_cancelled(&self) -> bool {
+        self.0.is_none()
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        self.0.as_ref().map(R::paths).unwrap_or_default()
+    }
+
+    #[cfg(feature = "replay")]
+    fn replay(outcome: &DialogOutcome) -> Option<Result<Self, DialogError>> {
+        if let Some(error) = &outcome.error {
+            return Some(Err(DialogError::Implementation(error.clone())));
+        }
+        if outcome.cancelled {
+            return Some(Ok(Self(None)));
+        }
+        R::from_paths(&outcome.paths).map(|output| Ok(Self(Some(output))))
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{DialogError, ImNativeFileDialog};
+
+/// An [ImNativeFileDialog] stored in the [egui::Memory] of an
+/// [egui::Context] under an [egui::Id], available with the `egui`
+/// feature. This lets a widget without a persistent field in the
+/// application show a native dialog and retrieve its result in a later
+/// frame, in the same way as egui's own popups.
+///
+/// The dialog is stored in [egui::Memory::id_data_temp], so it isn't
+/// saved between runs.
+pub struct EguiDialog<T> {
+    dialog: Arc<Mutex<ImNativeFileDialog<T>>>,
+}
+
+impl<T> Clone for EguiDialog<T> {
+    fn clone(&self) -> Self {
+        Self {
+            dialog: self.dialog.clone(),
+        }
+    }
+}
+
+impl<T: Send + 'static + Default> EguiDialog<T> {
+    /// The dialog stored under `id`, created if it doesn't exist yet.
+    pub fn get(ctx: &egui::Context, id: egui::Id) -> Self {
+        ctx.memory()
+            .id_data_temp
+            .get_or_insert_with(id, || Self {
+                dialog: Arc::default(),
+            })
+            .clone()
+    }
+
+    /// Lock the dialog to show it or configure it.
+    pub fn lock(&self) -> MutexGuard<'_, ImNativeFileDialog<T>> {
+        self.dialog.lock().expect("dialog lock poisoned")
+    }
+
+    /// Check the dialog stored under `id`, see
+    /// [ImNativeFileDialog::check()]. While the dialog is open this
+    /// requests a repaint, so the result is returned as soon as
+    /// possible.
+    pub fn check(ctx: &egui::Context, id: egui::Id) -> Option<Result<T, DialogError>> {
+        let dialog = Self::get(ctx, id);
+        let mut dialog = dialog.lock();
+        let result = dialog.check();
+        if dialog.is_open() {
+            ctx.request_repaint();
+        }
+        result
+    }
+
+    /// Remove the dialog stored under `id`. If the dialog is open its
+    /// result is discarded.
+    pub fn remove(ctx: &egui::Context, id: egui::Id) {
+        ctx.memory().id_data_temp.remove(&id);
+    }
+}
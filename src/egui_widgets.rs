@@ -0,0 +1,83 @@
+use crate::{i18n, ImNativeFileDialog, Text};
+
+/// While `dialog` is open, dims the whole ui, blocks its input, and
+/// shows a notice that a dialog is open, available with the `egui`
+/// feature. Call this every frame after drawing the rest of the ui,
+/// instead of disabling each widget while the dialog is open. This
+/// also requests a repaint while the dialog is open, so the overlay is
+/// removed as soon as it closes.
+pub fn modal_overlay<T>(ctx: &egui::CtxRef, dialog: &ImNativeFileDialog<T>)
+where
+    T: Send + 'static + Default,
+{
+    if !dialog.is_open() {
+        return;
+    }
+
+    let screen = ctx.input().screen_rect();
+    egui::Area::new("im_native_dialog_modal_overlay")
+        .order(egui::Order::Foreground)
+        .fixed_pos(screen.min)
+        .show(ctx, |ui| {
+            // Covering the screen takes the pointer input, and keeping
+            // the focus takes the keyboard input.
+            let response = ui.allocate_rect(screen, egui::Sense::click_and_drag());
+            response.request_focus();
+
+            let painter = ui.painter();
+            painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                i18n::text(Text::DialogOpen),
+                egui::TextStyle::Heading,
+                egui::Color32::WHITE,
+            );
+        });
+    ctx.request_repaint();
+}
+
+/// While `dialog` is open, shows a spinner and its
+/// [ImNativeFileDialog::status()], with a button to cancel it using
+/// [ImNativeFileDialog::cancel()], available with the `egui` feature.
+/// Returns `None` if the dialog isn't open, otherwise the response of
+/// the cancel button.
+pub fn dialog_status<T>(
+    ui: &mut egui::Ui,
+    dialog: &mut ImNativeFileDialog<T>,
+) -> Option<egui::Response>
+where
+    T: Send + 'static + Default,
+{
+    let status = dialog.status()?;
+
+    let response = ui
+        .horizontal(|ui| {
+            spinner(ui);
+            ui.label(status);
+            let response = ui.button(i18n::text(Text::Cancel));
+            if response.clicked() {
+                dialog.cancel();
+            }
+            response
+        })
+        .inner;
+    ui.ctx().request_repaint();
+    Some(response)
+}
+
+/// Paints a rotating arc the height of a line of text.
+fn spinner(ui: &mut egui::Ui) {
+    let size = ui.spacing().interact_size.y;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let radius = rect.height() / 2.0 - 2.0;
+    let start = ui.input().time as f32 * std::f32::consts::TAU;
+    let points = (0..=24)
+        .map(|i| {
+            let angle = start + i as f32 / 24.0 * std::f32::consts::PI * 1.5;
+            rect.center() + radius * egui::vec2(angle.cos(), angle.sin())
+        })
+        .collect();
+    let stroke = egui::Stroke::new(2.0, ui.visuals().strong_text_color());
+    ui.painter().add(egui::Shape::line(points, stroke));
+}
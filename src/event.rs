@@ -0,0 +1,140 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use crate::{channel, DialogKind};
+
+/// Identifies a single dialog shown by this crate, unique for the
+/// lifetime of the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DialogId(u64);
+
+impl DialogId {
+    pub(crate) fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// An event emitted to the receivers returned by [subscribe_events()].
+///
+/// Only [DialogEvent::Opened] and [DialogEvent::Closed] are emitted
+/// for dialogs shown using [crate::ImNativeFileDialog::show()], because
+/// the result is sent by the caller.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DialogEvent {
+    /// The dialog was shown.
+    Opened { id: DialogId, kind: DialogKind },
+    /// The user made a selection, after the dialog was open for
+    /// `duration`.
+    Completed {
+        id: DialogId,
+        kind: DialogKind,
+        duration: Duration,
+    },
+    /// The user cancelled the dialog, after it was open for `duration`.
+    Cancelled {
+        id: DialogId,
+        kind: DialogKind,
+        duration: Duration,
+    },
+    /// The dialog failed with `error`, after it was open for
+    /// `duration`.
+    Failed {
+        id: DialogId,
+        kind: DialogKind,
+        error: String,
+        duration: Duration,
+    },
+    /// The dialog has been open for `duration`, longer than the
+    /// threshold set using [crate::ImNativeFileDialog::with_watchdog()],
+    /// which may mean the backend failed to show it. Emitted once per
+    /// dialog, before it completes.
+    MaybeHung {
+        id: DialogId,
+        kind: DialogKind,
+        duration: Duration,
+    },
+    /// The dialog thread has processed `done` of the `total` bytes of
+    /// the file at `path` after the user made a selection, such as
+    /// while hashing it using
+    /// `ImNativeFileDialog::open_single_file_hashed()`, see
+    /// [crate::ImNativeFileDialog::check_progress()].
+    Progress {
+        id: DialogId,
+        kind: DialogKind,
+        path: PathBuf,
+        done: u64,
+        total: u64,
+    },
+    /// The result of the dialog, which returned `paths`, was discarded
+    /// because it wasn't checked for `age` after the dialog closed,
+    /// see [crate::ExpiryPolicy::report()].
+    Expired {
+        id: DialogId,
+        kind: DialogKind,
+        paths: Vec<PathBuf>,
+        age: Duration,
+    },
+    /// The native dialog closed, after any of the other events. The
+    /// application can use this to focus its main window again, along
+    /// with the widget which showed the dialog.
+    Closed { id: DialogId, kind: DialogKind },
+}
+
+impl DialogEvent {
+    /// The dialog this event is about.
+    pub fn id(&self) -> DialogId {
+        match self {
+            DialogEvent::Opened { id, .. }
+            | DialogEvent::Completed { id, .. }
+            | DialogEvent::Cancelled { id, .. }
+            | DialogEvent::Failed { id, .. }
+            | DialogEvent::MaybeHung { id, .. }
+            | DialogEvent::Progress { id, .. }
+            | DialogEvent::Expired { id, .. }
+            | DialogEvent::Closed { id, .. } => *id,
+        }
+    }
+
+    /// The kind of the dialog this event is about.
+    pub fn kind(&self) -> DialogKind {
+        match self {
+            DialogEvent::Opened { kind, .. }
+            | DialogEvent::Completed { kind, .. }
+            | DialogEvent::Cancelled { kind, .. }
+            | DialogEvent::Failed { kind, .. }
+            | DialogEvent::MaybeHung { kind, .. }
+            | DialogEvent::Progress { kind, .. }
+            | DialogEvent::Expired { kind, .. }
+            | DialogEvent::Closed { kind, .. } => *kind,
+        }
+    }
+}
+
+static SUBSCRIBERS: Mutex<Vec<channel::UnboundedSender<DialogEvent>>> = Mutex::new(Vec::new());
+
+/// Returns a receiver for the [DialogEvent]s of every dialog shown by
+/// this crate from now on, so parts of the application that don't own
+/// a dialog can react to dialog activity. Dropping the receiver
+/// unsubscribes it.
+pub fn subscribe_events() -> channel::Receiver<DialogEvent> {
+    let (sender, receiver) = channel::unbounded();
+    SUBSCRIBERS
+        .lock()
+        .expect("subscribers lock poisoned")
+        .push(sender);
+    receiver
+}
+
+/// Sends `event` to every subscriber, forgetting the subscribers whose
+/// receiver has been dropped.
+pub(crate) fn emit(event: DialogEvent) {
+    let mut subscribers = SUBSCRIBERS.lock().expect("subscribers lock poisoned");
+    subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+}
@@ -0,0 +1,168 @@
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{clock, event, DialogEvent, DialogId, DialogKind};
+
+static FRAME: AtomicU64 = AtomicU64::new(0);
+
+/// Counts a frame for [ExpiryPolicy::after_frames()]. Call this once
+/// per frame of the ui, such as at the end of the egui update.
+pub fn end_frame() {
+    FRAME.fetch_add(1, Ordering::Relaxed);
+}
+
+/// When the result of a dialog which hasn't been checked since it
+/// closed is discarded, set using
+/// [crate::ImNativeFileDialog::with_expiry()], so a result which was
+/// long forgotten, for example because the panel checking it was
+/// hidden, isn't applied when the panel is shown again. Expired
+/// results are logged, and [crate::ImNativeFileDialog::check()]
+/// returns `None` for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ExpiryPolicy {
+    after: Option<Duration>,
+    after_frames: Option<u64>,
+    report: bool,
+}
+
+impl ExpiryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Results expire once `duration` has passed since the dialog
+    /// closed.
+    pub fn after(mut self, duration: Duration) -> Self {
+        self.after = Some(duration);
+        self
+    }
+
+    /// Results expire once `frames` frames have been counted using
+    /// [end_frame()] since the dialog closed.
+    pub fn after_frames(mut self, frames: u64) -> Self {
+        self.after_frames = Some(frames);
+        self
+    }
+
+    /// Emits [DialogEvent::Expired] with the paths of expired results,
+    /// so they can still be handled elsewhere.
+    pub fn report(mut self) -> Self {
+        self.report = true;
+        self
+    }
+
+    fn is_expired(&self, completion: &Completion) -> bool {
+        self.after
+            .is_some_and(|after| clock::elapsed(completion.at) > after)
+            || self.after_frames.is_some_and(|frames| {
+                FRAME
+                    .load(Ordering::Relaxed)
+                    .saturating_sub(completion.frame)
+                    > frames
+            })
+    }
+
+    /// Returns `true` if the result described by `completion` has
+    /// expired, in which case it has been logged and reported.
+    pub(crate) fn expire(&self, id: DialogId, kind: DialogKind, completion: Completion) -> bool {
+        if !self.is_expired(&completion) {
+            return false;
+        }
+        let age = clock::elapsed(completion.at);
+        #[cfg(feature = "log")]
+        log::warn!(
+            "{:?} dialog result discarded, it wasn't checked for {:?}",
+            kind,
+            age
+        );
+        #[cfg(feature = "tracing")]
+        tracing::warn!(kind = ?kind, ?age, "dialog result expired");
+        if self.report {
+            event::emit(DialogEvent::Expired {
+                id,
+                kind,
+                paths: completion.paths,
+                age,
+            });
+        }
+        true
+    }
+}
+
+/// When a dialog closed, the paths it returned, and whether the user
+/// cancelled it.
+#[derive(Debug, Clone)]
+pub(crate) struct Completion {
+    at: Instant,
+    frame: u64,
+    paths: Vec<PathBuf>,
+    cancelled: bool,
+}
+
+impl Completion {
+    pub(crate) fn now(paths: Vec<PathBuf>, cancelled: bool) -> Self {
+        Self {
+            at: clock::now(),
+            frame: FRAME.load(Ordering::Relaxed),
+            paths,
+            cancelled,
+        }
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn never_expires_without_limits() {
+        let completion = Completion::now(Vec::new(), false);
+        assert!(!ExpiryPolicy::new().is_expired(&completion));
+    }
+
+    #[test]
+    fn expires_after_frames() {
+        let policy = ExpiryPolicy::new().after_frames(2);
+        let completion = Completion::now(Vec::new(), false);
+        end_frame();
+        end_frame();
+        assert!(!policy.is_expired(&completion));
+        end_frame();
+        assert!(policy.is_expired(&completion));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn expires_after_duration() {
+        /// Moves the clock back when dropped, even if the test fails.
+        struct Advanced;
+
+        impl Drop for Advanced {
+            fn drop(&mut self) {
+                clock::reset();
+            }
+        }
+
+        let _serial = crate::serial_test();
+        let _advanced = Advanced;
+        let policy = ExpiryPolicy::new().after(Duration::from_secs(60));
+        let completion = Completion::now(vec![PathBuf::from("a.txt")], false);
+        clock::advance(Duration::from_secs(59));
+        assert!(!policy.is_expired(&completion));
+        assert!(!policy.expire(
+            DialogId::next(),
+            DialogKind::OpenSingleFile,
+            completion.clone()
+        ));
+        clock::advance(Duration::from_secs(2));
+        assert!(policy.is_expired(&completion));
+        assert!(policy.expire(DialogId::next(), DialogKind::OpenSingleFile, completion));
+    }
+}
@@ -0,0 +1,28 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// Where the backend stores the index of the filter which was selected
+/// once the dialog is confirmed.
+pub(crate) type FilterSlot = Arc<Mutex<Option<usize>>>;
+
+/// A filter added using [crate::DialogOptions::filter()] which was
+/// selected when the dialog was confirmed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SelectedFilter {
+    /// The index of the filter, in the order they were added.
+    pub index: usize,
+    pub description: String,
+}
+
+/// A chosen path along with the filter which was selected, returned by
+/// [crate::ImNativeFileDialog::open_single_file_filter()] and
+/// [crate::ImNativeFileDialog::show_save_single_file_filter()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterPath {
+    pub path: PathBuf,
+    /// The selected filter, or `None` with backends which don't report
+    /// it, see [crate::Capabilities::selected_filter].
+    pub filter: Option<SelectedFilter>,
+}
@@ -0,0 +1,227 @@
+use std::path::PathBuf;
+
+use native_dialog::{MessageDialog, MessageType};
+
+use crate::{worker::run_dialog, DialogError, DialogKind, DialogOptions};
+
+/// Runs a step with the state and the default options of the dialog
+/// showing the flow, returning `false` to abort the flow.
+type Step<S> = Box<dyn FnOnce(&mut S, &DialogOptions) -> Result<bool, DialogError> + Send>;
+
+/// A sequence of dialogs shown one after another in the same dialog
+/// thread, such as picking an input file, then an output directory,
+/// then confirming to overwrite files which exist. Each step reads and
+/// fills in the state `S`, so it can depend on the results of the
+/// previous steps. Shown using [crate::ImNativeFileDialog::show_flow()],
+/// whose result is the state after the last step, or `None` if the
+/// user cancelled any of them, in which case the remaining steps are
+/// skipped.
+pub struct DialogFlow<S> {
+    state: S,
+    steps: Vec<Step<S>>,
+}
+
+impl<S: Send + 'static> DialogFlow<S> {
+    /// Creates a flow without any steps, starting with `state`.
+    pub fn new(state: S) -> Self {
+        Self {
+            state,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Adds a step showing a dialog that lets users open one file,
+    /// using the options returned by `options`, and calling `apply`
+    /// with the chosen path.
+    pub fn open_file<O, A>(self, options: O, apply: A) -> Self
+    where
+        O: FnOnce(&S) -> DialogOptions + Send + 'static,
+        A: FnOnce(&mut S, PathBuf) + Send + 'static,
+    {
+        self.step(move |state, defaults| {
+            let options = options(state).or_defaults(defaults);
+            let path = run_dialog(DialogKind::OpenSingleFile, || {
+                options.show_open_single_file()
+            })?;
+            match path {
+                Some(path) => {
+                    apply(state, path);
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        })
+    }
+
+    /// Adds a step showing a dialog that lets users open multiple
+    /// files, using the options returned by `options`, and calling
+    /// `apply` with the chosen paths.
+    pub fn open_files<O, A>(self, options: O, apply: A) -> Self
+    where
+        O: FnOnce(&S) -> DialogOptions + Send + 'static,
+        A: FnOnce(&mut S, Vec<PathBuf>) + Send + 'static,
+    {
+        self.step(move |state, defaults| {
+            let options = options(state).or_defaults(defaults);
+            let paths = run_dialog(DialogKind::OpenMultipleFile, || {
+                options.show_open_multiple_file()
+            })?;
+            if paths.is_empty() {
+                return Ok(false);
+            }
+            apply(state, paths);
+            Ok(true)
+        })
+    }
+
+    /// Adds a step showing a dialog that lets users open one
+    /// directory, using the options returned by `options`, and calling
+    /// `apply` with the chosen path.
+    pub fn open_dir<O, A>(self, options: O, apply: A) -> Self
+    where
+        O: FnOnce(&S) -> DialogOptions + Send + 'static,
+        A: FnOnce(&mut S, PathBuf) + Send + 'static,
+    {
+        self.step(move |state, defaults| {
+            let options = options(state).or_defaults(defaults);
+            let path = run_dialog(DialogKind::OpenSingleDir, || options.show_open_single_dir())?;
+            match path {
+                Some(path) => {
+                    apply(state, path);
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        })
+    }
+
+    /// Adds a step showing a dialog that lets users save one file,
+    /// using the options returned by `options`, and calling `apply`
+    /// with the chosen path.
+    pub fn save_file<O, A>(self, options: O, apply: A) -> Self
+    where
+        O: FnOnce(&S) -> DialogOptions + Send + 'static,
+        A: FnOnce(&mut S, PathBuf) + Send + 'static,
+    {
+        self.step(move |state, defaults| {
+            let options = options(state).or_defaults(defaults);
+            let path = run_dialog(DialogKind::SaveSingleFile, || options.show_save())?;
+            match path {
+                Some(path) => {
+                    apply(state, path);
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        })
+    }
+
+    /// Adds a step asking users to confirm using the title and text
+    /// returned by `message`, which is skipped if it returns `None`,
+    /// for example when there are no files to overwrite. The flow is
+    /// aborted unless the user chooses Yes.
+    pub fn confirm<M>(self, message: M) -> Self
+    where
+        M: FnOnce(&S) -> Option<(String, String)> + Send + 'static,
+    {
+        self.step(move |state, _defaults| match message(state) {
+            Some((title, text)) => run_dialog(DialogKind::Confirm, || {
+                MessageDialog::new()
+                    .set_title(&title)
+                    .set_text(&text)
+                    .set_type(MessageType::Warning)
+                    .show_confirm()
+            }),
+            None => Ok(true),
+        })
+    }
+
+    /// Adds a step calling `run` with the state without showing a
+    /// dialog, for example to check the previous results. Returning
+    /// `Ok(false)` aborts the flow, and returning an error fails it.
+    pub fn then<R>(self, run: R) -> Self
+    where
+        R: FnOnce(&mut S) -> Result<bool, DialogError> + Send + 'static,
+    {
+        self.step(move |state, _defaults| run(state))
+    }
+
+    fn step<F>(mut self, step: F) -> Self
+    where
+        F: FnOnce(&mut S, &DialogOptions) -> Result<bool, DialogError> + Send + 'static,
+    {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// Runs the steps in order, with `defaults` filling in the options
+    /// of each dialog.
+    pub(crate) fn run(self, defaults: &DialogOptions) -> Result<Option<S>, DialogError> {
+        let Self { mut state, steps } = self;
+        for step in steps {
+            if !step(&mut state, defaults)? {
+                return Ok(None);
+            }
+        }
+        Ok(Some(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_steps_in_order() {
+        let flow = DialogFlow::new(Vec::new())
+            .then(|steps| {
+                steps.push(1);
+                Ok(true)
+            })
+            .then(|steps| {
+                steps.push(steps.len() + 1);
+                Ok(true)
+            });
+        assert_eq!(
+            flow.run(&DialogOptions::default()).unwrap(),
+            Some(vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn returns_state_without_steps() {
+        let flow = DialogFlow::new("state");
+        assert_eq!(flow.run(&DialogOptions::default()).unwrap(), Some("state"));
+    }
+
+    #[test]
+    fn skips_remaining_steps_when_aborted() {
+        let flow = DialogFlow::new(0)
+            .then(|count| {
+                *count += 1;
+                Ok(false)
+            })
+            .then(|_| panic!("the flow was aborted"));
+        assert_eq!(flow.run(&DialogOptions::default()).unwrap(), None);
+    }
+
+    #[test]
+    fn fails_with_the_error_of_a_step() {
+        let flow = DialogFlow::new(())
+            .then(|_| Err(DialogError::NoImplementation))
+            .then(|_| panic!("the flow failed"));
+        assert!(matches!(
+            flow.run(&DialogOptions::default()),
+            Err(DialogError::NoImplementation)
+        ));
+    }
+
+    #[test]
+    fn skips_confirmation_without_message() {
+        let flow = DialogFlow::new(false).confirm(|_| None).then(|confirmed| {
+            *confirmed = true;
+            Ok(true)
+        });
+        assert_eq!(flow.run(&DialogOptions::default()).unwrap(), Some(true));
+    }
+}
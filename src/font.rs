@@ -0,0 +1,188 @@
+use native_dialog::Error;
+
+use crate::{
+    worker::Worker, DialogError, DialogKind, DialogResult, ImNativeDialogError, RequestToken,
+};
+
+/// A font chosen using [ImNativeFontDialog].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Font {
+    pub family: String,
+    /// Size in points.
+    pub size: f32,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+/// A font chooser for use with immediate mode gui libraries, following
+/// the same pattern as [crate::ImNativeFileDialog]. The `show*()`
+/// methods show the platform's font chooser in a new thread, and the
+/// result is returned to this object ready to be polled by the ui
+/// using [ImNativeFontDialog::check()].
+///
+/// The font chooser is available on Windows, and on GNU/Linux and BSD
+/// Unix when `yad` is installed. Otherwise
+/// [DialogError::NoImplementation] is returned.
+pub struct ImNativeFontDialog<T> {
+    worker: Worker<T>,
+}
+
+impl<T> Default for ImNativeFontDialog<T> {
+    fn default() -> Self {
+        Self {
+            worker: Worker::default(),
+        }
+    }
+}
+
+impl ImNativeFontDialog<Option<Font>> {
+    /// Shows a font chooser, initially set to `initial`. The result is
+    /// `None` if the user cancelled the chooser.
+    pub fn show_font(
+        &mut self,
+        title: impl Into<String>,
+        initial: Font,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let title = title.into();
+        self.worker
+            .spawn(DialogKind::Font, None, move || font(&title, &initial))
+    }
+}
+
+impl<T: Send + 'static + Default> ImNativeFontDialog<T> {
+    /// Set a callback to use for this dialog which will be called
+    /// immediately upon dialog close in the dialog monitoring thread.
+    pub fn with_callback<C>(&mut self, callback: C) -> &mut Self
+    where
+        C: FnOnce(&Result<T, DialogError>) + Send + 'static,
+    {
+        self.worker.set_callback(Box::new(callback));
+        self
+    }
+
+    /// Check if the chooser is complete. If it is complete it will
+    /// return `Some` with the result of the chooser, otherwise will
+    /// return `None`. This will update the status of
+    /// [ImNativeFontDialog::is_open()].
+    pub fn check(&mut self) -> Option<Result<T, DialogError>> {
+        self.worker.check()
+    }
+
+    /// Like [ImNativeFontDialog::check()], but tells cancellation apart
+    /// from failures, see [DialogResult].
+    pub fn check_result(&mut self) -> Option<DialogResult<T>> {
+        self.worker.check_result()
+    }
+
+    /// Returns `true` if the chooser is currently open, otherwise
+    /// returns `false`. Requires a previous call of
+    /// [ImNativeFontDialog::check()] to update the current status.
+    pub fn is_open(&self) -> bool {
+        self.worker.is_open()
+    }
+
+    /// Returns the kind of the open chooser, or `None` if it isn't open,
+    /// see [crate::ImNativeFileDialog::kind()].
+    pub fn kind(&self) -> Option<DialogKind> {
+        self.worker.kind()
+    }
+
+    /// Returns a short description of the open chooser, or `None` if it
+    /// isn't open, see [crate::ImNativeFileDialog::status()].
+    pub fn status(&self) -> Option<String> {
+        self.worker.status()
+    }
+}
+
+/// Parses the `family|style|size` output of both backends, where the
+/// style is a list of words such as `Bold Italic` (yad) or
+/// `Bold, Italic` (Windows).
+#[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+fn parse(output: &str) -> Option<Font> {
+    let mut fields = output.trim().split('|');
+    let family = fields.next()?.to_owned();
+    let style = fields.next()?.to_lowercase();
+    let size = fields.next()?.trim().parse().ok()?;
+
+    Some(Font {
+        family,
+        size,
+        bold: style.contains("bold"),
+        italic: style.contains("italic") || style.contains("oblique"),
+    })
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn font(title: &str, initial: &Font) -> Result<Option<Font>, Error> {
+    use std::process::Command;
+
+    if which::which("yad").is_err() {
+        return Err(Error::NoImplementation);
+    }
+
+    let mut style = Vec::new();
+    if initial.bold {
+        style.push("Bold");
+    }
+    if initial.italic {
+        style.push("Italic");
+    }
+
+    let output = crate::command::run(
+        Command::new("yad")
+            .arg("--font")
+            .arg("--separate-output")
+            .arg(format!("--title={}", title))
+            .arg(format!(
+                "--fontname={} {} {}",
+                initial.family,
+                style.join(" "),
+                initial.size
+            )),
+    )?;
+
+    match output {
+        Some(output) => parse(&output)
+            .map(Some)
+            .ok_or(Error::UnexpectedOutput("yad")),
+        None => Ok(None),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn font(_title: &str, _initial: &Font) -> Result<Option<Font>, Error> {
+    // NSFontPanel is not modal, and there is no dialog program for it.
+    Err(Error::NoImplementation)
+}
+
+#[cfg(target_os = "windows")]
+fn font(title: &str, initial: &Font) -> Result<Option<Font>, Error> {
+    use crate::command;
+
+    // FontDialog has no title.
+    let _ = title;
+    let style = match (initial.bold, initial.italic) {
+        (true, true) => "Bold, Italic",
+        (true, false) => "Bold",
+        (false, true) => "Italic",
+        (false, false) => "Regular",
+    };
+    let output = command::powershell(&format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+        $dialog = New-Object System.Windows.Forms.FontDialog; \
+        $dialog.Font = New-Object System.Drawing.Font({}, {}, [System.Drawing.FontStyle]{}); \
+        if ($dialog.ShowDialog() -ne 'OK') {{ exit 1 }}; \
+        $size = $dialog.Font.SizeInPoints.ToString([System.Globalization.CultureInfo]::InvariantCulture); \
+        '{{0}}|{{1}}|{{2}}' -f $dialog.Font.Name, $dialog.Font.Style, $size",
+        command::powershell_string(&initial.family),
+        initial.size,
+        command::powershell_string(style),
+    ))?;
+
+    match output {
+        Some(output) => parse(&output)
+            .map(Some)
+            .ok_or(Error::UnexpectedOutput("powershell")),
+        None => Ok(None),
+    }
+}
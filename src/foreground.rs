@@ -0,0 +1,113 @@
+//! Bringing dialogs to the foreground on Windows, where a dialog shown
+//! from a thread other than the owner's often opens behind the main
+//! window, and moving them to the monitor they were asked to open on.
+
+use std::{mem::zeroed, ptr::null_mut, time::Duration};
+
+use winapi::{
+    shared::{
+        minwindef::{BOOL, FALSE, LPARAM, TRUE},
+        windef::{HWND, RECT},
+    },
+    um::{
+        processthreadsapi::GetCurrentThreadId,
+        winuser::{
+            BringWindowToTop, EnumThreadWindows, GetMonitorInfoW, GetWindowRect, IsWindowVisible,
+            MonitorFromRect, SetForegroundWindow, SetWindowPos, MONITORINFO,
+            MONITOR_DEFAULTTONEAREST, SWP_NOACTIVATE, SWP_NOSIZE, SWP_NOZORDER,
+        },
+    },
+};
+
+use crate::ScreenRect;
+
+/// How long to wait for the dialog window to appear.
+const TIMEOUT: Duration = Duration::from_secs(2);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+unsafe extern "system" fn find_visible(window: HWND, found: LPARAM) -> BOOL {
+    if IsWindowVisible(window) == FALSE {
+        return TRUE;
+    }
+    *(found as *mut HWND) = window;
+    FALSE
+}
+
+/// Waits in a new thread for the current thread to show a window, and
+/// calls `apply` with it.
+fn on_next_window<F>(apply: F)
+where
+    F: FnOnce(HWND) + Send + 'static,
+{
+    let thread = unsafe { GetCurrentThreadId() };
+    // Changing the window is best effort, so failing to spawn the
+    // thread is ignored.
+    let _ = std::thread::Builder::new().spawn(move || {
+        let mut waited = Duration::ZERO;
+        while waited < TIMEOUT {
+            std::thread::sleep(POLL_INTERVAL);
+            waited += POLL_INTERVAL;
+
+            let mut window: HWND = null_mut();
+            unsafe {
+                EnumThreadWindows(
+                    thread,
+                    Some(find_visible),
+                    &mut window as *mut HWND as LPARAM,
+                );
+            }
+            if !window.is_null() {
+                apply(window);
+                return;
+            }
+        }
+    });
+}
+
+/// Waits in a new thread for the current thread to show a window, and
+/// brings it to the foreground.
+pub(crate) fn raise_next_window() {
+    on_next_window(|window| unsafe {
+        SetForegroundWindow(window);
+        BringWindowToTop(window);
+    });
+}
+
+/// Waits in a new thread for the current thread to show a window, and
+/// centers it on `rect`, keeping it within the work area of the monitor
+/// `rect` is on.
+pub(crate) fn move_next_window(rect: ScreenRect) {
+    on_next_window(move |window| unsafe {
+        let mut frame: RECT = zeroed();
+        if GetWindowRect(window, &mut frame) == FALSE {
+            return;
+        }
+        let (width, height) = (frame.right - frame.left, frame.bottom - frame.top);
+        let target = RECT {
+            left: rect.x,
+            top: rect.y,
+            right: rect.x.saturating_add(rect.width as i32),
+            bottom: rect.y.saturating_add(rect.height as i32),
+        };
+        let (center_x, center_y) = rect.center();
+        let (mut x, mut y) = (center_x - width / 2, center_y - height / 2);
+
+        let monitor = MonitorFromRect(&target, MONITOR_DEFAULTTONEAREST);
+        let mut info: MONITORINFO = zeroed();
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if GetMonitorInfoW(monitor, &mut info) != FALSE {
+            let work = info.rcWork;
+            x = x.min(work.right - width).max(work.left);
+            y = y.min(work.bottom - height).max(work.top);
+        }
+        SetWindowPos(
+            window,
+            null_mut(),
+            x,
+            y,
+            0,
+            0,
+            SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+        );
+    });
+}
@@ -0,0 +1,406 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{DialogKind, RequestToken};
+
+/// What happens when a dialog is shown while another dialog is open,
+/// see [set_global_guard()] and [crate::ImNativeFileDialog::with_group()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GuardPolicy {
+    /// The `show*()` methods return [crate::ImNativeDialogError::Busy].
+    Reject,
+    /// The dialog is shown once the other dialog closes, the next time
+    /// it is checked. Until then the dialog is reported as open.
+    /// Queued dialogs are shown in order of the priority set using
+    /// [crate::ImNativeFileDialog::with_priority()], then in the order
+    /// they were queued, so a dialog which isn't checked holds back the
+    /// dialogs behind it.
+    Queue,
+}
+
+static GLOBAL_POLICY: Mutex<Option<GuardPolicy>> = Mutex::new(None);
+static GLOBAL_OPEN: AtomicBool = AtomicBool::new(false);
+static GROUPS_OPEN: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+static WAITING: Mutex<Vec<Waiter>> = Mutex::new(Vec::new());
+
+/// A named group of dialogs where only one member can be open at a
+/// time, see [crate::ImNativeFileDialog::with_group()].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Group {
+    pub name: String,
+    pub policy: GuardPolicy,
+}
+
+/// Only allow one dialog shown by this crate to be open at a time
+/// across the whole process, using `policy` for the others, or remove
+/// the guard with `None`. Several native dialogs open at once confuse
+/// users, and some platforms handle it badly.
+pub fn set_global_guard(policy: Option<GuardPolicy>) {
+    *GLOBAL_POLICY.lock().expect("guard lock poisoned") = policy;
+}
+
+/// A queued dialog, see [GuardPolicy::Queue].
+struct Waiter {
+    ticket: u64,
+    token: RequestToken,
+    kind: DialogKind,
+    priority: i32,
+    group: Option<String>,
+    /// Set by [cancel_queued()], until the dialog is checked.
+    cancelled: bool,
+}
+
+impl Waiter {
+    /// Returns `true` if this dialog is to be shown before the request
+    /// `token` with `priority`.
+    fn is_ahead_of(&self, priority: i32, token: RequestToken) -> bool {
+        self.priority > priority || (self.priority == priority && self.token < token)
+    }
+}
+
+/// A dialog waiting for another dialog to close, returned by
+/// [queued_requests()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedRequestInfo {
+    pub token: RequestToken,
+    pub kind: DialogKind,
+    pub priority: i32,
+    /// The group the dialog is queued in, see
+    /// [crate::ImNativeFileDialog::with_group()], or `None` if it is
+    /// queued by the guard set using [set_global_guard()].
+    pub group: Option<String>,
+}
+
+/// The dialogs shown by this crate which are waiting for another dialog
+/// to close, in the order they will be shown, so the ui can show how
+/// many dialogs are pending, see [GuardPolicy::Queue].
+pub fn queued_requests() -> Vec<QueuedRequestInfo> {
+    let waiting = WAITING.lock().expect("guard lock poisoned");
+    let mut queued: Vec<&Waiter> = waiting.iter().filter(|waiter| !waiter.cancelled).collect();
+    queued.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.token.cmp(&b.token)));
+    queued
+        .into_iter()
+        .map(|waiter| QueuedRequestInfo {
+            token: waiter.token,
+            kind: waiter.kind,
+            priority: waiter.priority,
+            group: waiter.group.clone(),
+        })
+        .collect()
+}
+
+/// Removes the queued request `token` from the queue before it is
+/// shown, returning `false` if it isn't queued. The next check of the
+/// object which showed it returns the same empty result as when the
+/// user cancels a dialog.
+pub fn cancel_queued(token: RequestToken) -> bool {
+    match WAITING
+        .lock()
+        .expect("guard lock poisoned")
+        .iter_mut()
+        .find(|waiter| waiter.token == token && !waiter.cancelled)
+    {
+        Some(waiter) => {
+            waiter.cancelled = true;
+            true
+        }
+        None => false,
+    }
+}
+
+/// A place in the queue of dialogs waiting for the guard, left when
+/// dropped.
+pub(crate) struct Ticket(u64);
+
+impl Ticket {
+    /// Queues the request `token` with `priority` in `group`.
+    pub(crate) fn new(
+        token: RequestToken,
+        kind: DialogKind,
+        priority: i32,
+        group: Option<&Group>,
+    ) -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let ticket = NEXT.fetch_add(1, Ordering::Relaxed);
+        WAITING.lock().expect("guard lock poisoned").push(Waiter {
+            ticket,
+            token,
+            kind,
+            priority,
+            group: group.map(|group| group.name.clone()),
+            cancelled: false,
+        });
+        Self(ticket)
+    }
+
+    /// Moves the dialog to the place in the queue for `priority`.
+    pub(crate) fn set_priority(&self, priority: i32) {
+        if let Some(waiter) = WAITING
+            .lock()
+            .expect("guard lock poisoned")
+            .iter_mut()
+            .find(|waiter| waiter.ticket == self.0)
+        {
+            waiter.priority = priority;
+        }
+    }
+
+    /// Returns `true` if the dialog was cancelled using
+    /// [cancel_queued()].
+    pub(crate) fn is_cancelled(&self) -> bool {
+        WAITING
+            .lock()
+            .expect("guard lock poisoned")
+            .iter()
+            .any(|waiter| waiter.ticket == self.0 && waiter.cancelled)
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        WAITING
+            .lock()
+            .expect("guard lock poisoned")
+            .retain(|waiter| waiter.ticket != self.0);
+    }
+}
+
+/// Returns `true` if a queued dialog competing with the request
+/// `token` in `group` is to be shown before it.
+fn is_queued_ahead(group: Option<&Group>, priority: i32, token: RequestToken) -> bool {
+    let global = GLOBAL_POLICY.lock().expect("guard lock poisoned").is_some();
+    WAITING
+        .lock()
+        .expect("guard lock poisoned")
+        .iter()
+        .filter(|waiter| waiter.token != token && !waiter.cancelled)
+        .filter(|waiter| {
+            global || group.is_some_and(|group| waiter.group.as_ref() == Some(&group.name))
+        })
+        .any(|waiter| waiter.is_ahead_of(priority, token))
+}
+
+/// Permission to show a dialog, released when dropped.
+pub(crate) struct Permit {
+    global: bool,
+    group: Option<String>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if self.global {
+            GLOBAL_OPEN.store(false, Ordering::Release);
+        }
+        if let Some(group) = &self.group {
+            if let Some(groups) = GROUPS_OPEN.lock().expect("guard lock poisoned").as_mut() {
+                groups.remove(group);
+            }
+        }
+    }
+}
+
+/// Acquire permission to show the request `token` with `priority` in
+/// `group`, or return the policy to use if another dialog is open or
+/// queued ahead of it.
+pub(crate) fn acquire(
+    group: Option<&Group>,
+    priority: i32,
+    token: RequestToken,
+) -> Result<Permit, GuardPolicy> {
+    let policy = *GLOBAL_POLICY.lock().expect("guard lock poisoned");
+    if is_queued_ahead(group, priority, token) {
+        return Err(policy
+            .or(group.map(|group| group.policy))
+            .unwrap_or(GuardPolicy::Queue));
+    }
+
+    let mut permit = Permit {
+        global: false,
+        group: None,
+    };
+
+    if let Some(policy) = policy {
+        if GLOBAL_OPEN
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return Err(policy);
+        }
+        permit.global = true;
+    }
+
+    if let Some(group) = group {
+        let mut groups = GROUPS_OPEN.lock().expect("guard lock poisoned");
+        let groups = groups.get_or_insert_with(HashSet::new);
+        if groups.contains(&group.name) {
+            // Dropping the permit releases the global guard.
+            return Err(group.policy);
+        }
+        groups.insert(group.name.clone());
+        permit.group = Some(group.name.clone());
+    }
+
+    Ok(permit)
+}
+
+/// Returns `true` if [acquire()] would currently fail for the request
+/// `token`, without allocating, so queued dialogs can be polled
+/// cheaply.
+pub(crate) fn is_blocked(group: Option<&Group>, priority: i32, token: RequestToken) -> bool {
+    let global = GLOBAL_POLICY.lock().expect("guard lock poisoned").is_some()
+        && GLOBAL_OPEN.load(Ordering::Acquire);
+    global
+        || is_queued_ahead(group, priority, token)
+        || group.is_some_and(|group| {
+            GROUPS_OPEN
+                .lock()
+                .expect("guard lock poisoned")
+                .as_ref()
+                .is_some_and(|groups| groups.contains(&group.name))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(name: &str, policy: GuardPolicy) -> Group {
+        Group {
+            name: name.to_owned(),
+            policy,
+        }
+    }
+
+    /// Removes the global guard when dropped, even if the test fails.
+    struct Guarded;
+
+    impl Drop for Guarded {
+        fn drop(&mut self) {
+            set_global_guard(None);
+        }
+    }
+
+    #[test]
+    fn allows_one_open_dialog_per_group() {
+        let _serial = crate::serial_test();
+        let reject = group("guard reject", GuardPolicy::Reject);
+        let other = group("guard reject other", GuardPolicy::Reject);
+        let permit = acquire(Some(&reject), 0, RequestToken::next()).unwrap();
+        assert!(matches!(
+            acquire(Some(&reject), 0, RequestToken::next()),
+            Err(GuardPolicy::Reject)
+        ));
+        assert!(is_blocked(Some(&reject), 0, RequestToken::next()));
+        // Other groups and dialogs without a group aren't affected.
+        let _other = acquire(Some(&other), 0, RequestToken::next()).unwrap();
+        let _ungrouped = acquire(None, 0, RequestToken::next()).unwrap();
+        drop(permit);
+        assert!(!is_blocked(Some(&reject), 0, RequestToken::next()));
+        acquire(Some(&reject), 0, RequestToken::next()).unwrap();
+    }
+
+    #[test]
+    fn allows_one_open_dialog_with_global_guard() {
+        let _serial = crate::serial_test();
+        let _guarded = Guarded;
+        set_global_guard(Some(GuardPolicy::Reject));
+        let permit = acquire(None, 0, RequestToken::next()).unwrap();
+        assert!(matches!(
+            acquire(None, 0, RequestToken::next()),
+            Err(GuardPolicy::Reject)
+        ));
+        let grouped = group("guard global", GuardPolicy::Queue);
+        assert!(matches!(
+            acquire(Some(&grouped), 0, RequestToken::next()),
+            Err(GuardPolicy::Reject)
+        ));
+        drop(permit);
+        acquire(Some(&grouped), 0, RequestToken::next()).unwrap();
+    }
+
+    #[test]
+    fn shows_queued_dialogs_in_order() {
+        let _serial = crate::serial_test();
+        let queue = group("guard queue", GuardPolicy::Queue);
+        let permit = acquire(Some(&queue), 0, RequestToken::next()).unwrap();
+        let (first, second) = (RequestToken::next(), RequestToken::next());
+        assert!(matches!(
+            acquire(Some(&queue), 0, first),
+            Err(GuardPolicy::Queue)
+        ));
+        let first_ticket = Ticket::new(first, DialogKind::OpenSingleFile, 0, Some(&queue));
+        let _second_ticket = Ticket::new(second, DialogKind::SaveSingleFile, 0, Some(&queue));
+        drop(permit);
+
+        // The second request waits for the first even once the group is
+        // free.
+        assert!(is_blocked(Some(&queue), 0, second));
+        assert!(matches!(
+            acquire(Some(&queue), 0, second),
+            Err(GuardPolicy::Queue)
+        ));
+        assert!(!is_blocked(Some(&queue), 0, first));
+        let permit = acquire(Some(&queue), 0, first).unwrap();
+        drop(first_ticket);
+        assert!(matches!(
+            acquire(Some(&queue), 0, second),
+            Err(GuardPolicy::Queue)
+        ));
+        drop(permit);
+        acquire(Some(&queue), 0, second).unwrap();
+    }
+
+    #[test]
+    fn cancels_queued_dialogs() {
+        let _serial = crate::serial_test();
+        let queue = group("guard cancel", GuardPolicy::Queue);
+        let (first, second) = (RequestToken::next(), RequestToken::next());
+        let first_ticket = Ticket::new(first, DialogKind::OpenSingleFile, 0, Some(&queue));
+        let _second_ticket = Ticket::new(second, DialogKind::OpenSingleFile, 0, Some(&queue));
+        assert!(cancel_queued(first));
+        assert!(first_ticket.is_cancelled());
+        assert!(!cancel_queued(first));
+        // A cancelled dialog no longer holds back the others.
+        assert!(!is_blocked(Some(&queue), 0, second));
+        assert!(!queued_requests().iter().any(|queued| queued.token == first));
+        drop(first_ticket);
+        assert!(!cancel_queued(first));
+    }
+
+    #[test]
+    fn shows_queued_dialogs_by_priority() {
+        let _serial = crate::serial_test();
+        let queue = group("guard priority", GuardPolicy::Queue);
+        let permit = acquire(Some(&queue), 0, RequestToken::next()).unwrap();
+        let (low, high, later) = (
+            RequestToken::next(),
+            RequestToken::next(),
+            RequestToken::next(),
+        );
+        let _low_ticket = Ticket::new(low, DialogKind::OpenSingleFile, 0, Some(&queue));
+        let high_ticket = Ticket::new(high, DialogKind::SaveSingleFile, 5, Some(&queue));
+        let later_ticket = Ticket::new(later, DialogKind::OpenSingleDir, 0, Some(&queue));
+        let queued: Vec<RequestToken> = queued_requests()
+            .into_iter()
+            .filter(|queued| queued.group.as_deref() == Some("guard priority"))
+            .map(|queued| queued.token)
+            .collect();
+        assert_eq!(queued, vec![high, low, later]);
+        drop(permit);
+
+        assert!(is_blocked(Some(&queue), 0, low));
+        let permit = acquire(Some(&queue), 5, high).unwrap();
+        drop(high_ticket);
+        drop(permit);
+
+        // Raising the priority of a queued dialog moves it ahead.
+        later_ticket.set_priority(1);
+        assert!(is_blocked(Some(&queue), 0, low));
+        assert!(!is_blocked(Some(&queue), 1, later));
+    }
+}
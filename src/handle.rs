@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    worker::Worker, DialogError, DialogId, DialogKind, DialogOptions, DialogResult, Request,
+};
+
+/// A dialog moved out of an [crate::ImNativeFileDialog] using
+/// [crate::ImNativeFileDialog::detach()], which is polled on its own
+/// so that one object can show several dialogs at the same time.
+pub struct DialogHandle<T> {
+    worker: Worker<T>,
+}
+
+impl<T: Send + 'static + Default> DialogHandle<T> {
+    pub(crate) fn new(worker: Worker<T>) -> Self {
+        Self { worker }
+    }
+
+    /// Check if the dialog is complete. If it is complete it will
+    /// return `Some` with the result of the dialog, otherwise will
+    /// return `None`. This will update the status of
+    /// [DialogHandle::is_open()].
+    pub fn check(&mut self) -> Option<Result<T, DialogError>> {
+        self.worker.check()
+    }
+
+    /// Like [DialogHandle::check()], but tells cancellation apart
+    /// from failures, see [DialogResult].
+    pub fn check_result(&mut self) -> Option<DialogResult<T>> {
+        self.worker.check_result()
+    }
+
+    /// Like [DialogHandle::check()], but also returns the [Request]
+    /// the result belongs to, see
+    /// [crate::ImNativeFileDialog::check_request()].
+    pub fn check_request(&mut self) -> Option<(Request, Result<T, DialogError>)> {
+        self.worker.check_request()
+    }
+
+    /// Like [DialogHandle::check()], but also returns the context
+    /// attached to the dialog, see
+    /// [crate::ImNativeFileDialog::check_context()].
+    pub fn check_context<C: 'static>(&mut self) -> Option<(Option<C>, Result<T, DialogError>)> {
+        self.worker
+            .check_context()
+            .map(|(_request, context, result)| {
+                let context = context
+                    .and_then(|context| context.downcast().ok())
+                    .map(|context| *context);
+                (context, result)
+            })
+    }
+
+    /// Returns `true` if the dialog is currently open, otherwise
+    /// returns `false`. Requires a previous call of
+    /// [DialogHandle::check()] to update the current status.
+    pub fn is_open(&self) -> bool {
+        self.worker.is_open()
+    }
+
+    /// Returns the kind of the dialog, or `None` once its result has
+    /// been checked, see [crate::ImNativeFileDialog::kind()].
+    pub fn kind(&self) -> Option<DialogKind> {
+        self.worker.kind()
+    }
+
+    /// Returns a short description of the dialog, or `None` once its
+    /// result has been checked, see
+    /// [crate::ImNativeFileDialog::status()].
+    pub fn status(&self) -> Option<String> {
+        self.worker.status()
+    }
+
+    /// Returns the options the dialog was shown with, or `None` once
+    /// its result has been checked, see
+    /// [crate::ImNativeFileDialog::pending_options()].
+    pub fn pending_options(&self) -> Option<&DialogOptions> {
+        self.worker.pending_options()
+    }
+
+    /// Returns when the dialog was shown, or `None` once it has
+    /// closed, see [crate::ImNativeFileDialog::opened_at()].
+    pub fn opened_at(&self) -> Option<Instant> {
+        self.worker.opened_at()
+    }
+
+    /// Returns the [DialogId] of the dialog, or `None` once it has
+    /// closed, see [crate::ImNativeFileDialog::id()].
+    pub fn id(&self) -> Option<DialogId> {
+        self.worker.id()
+    }
+
+    /// Returns how long the dialog has been open, or `None` once it
+    /// has closed, see [crate::ImNativeFileDialog::elapsed()].
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.worker.elapsed()
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+use sha2::Digest;
+
+use crate::progress::Reporter;
+
+/// The algorithm used to hash the files chosen using
+/// [crate::ImNativeFileDialog::open_single_file_hashed()] or
+/// [crate::ImNativeFileDialog::show_open_multiple_file_hashed()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HashAlgorithm {
+    #[default]
+    Blake3,
+    Sha256,
+}
+
+/// The 32 byte hash of a file's contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileHash {
+    pub algorithm: HashAlgorithm,
+    pub bytes: [u8; 32],
+}
+
+impl FileHash {
+    /// The hash as lowercase hexadecimal, as printed by `b3sum` and
+    /// `sha256sum`.
+    pub fn to_hex(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for FileHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.bytes
+            .iter()
+            .try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+/// A path chosen using
+/// [crate::ImNativeFileDialog::open_single_file_hashed()] or
+/// [crate::ImNativeFileDialog::show_open_multiple_file_hashed()],
+/// along with the hash of its contents, computed in the dialog thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashedPath {
+    pub path: PathBuf,
+    pub hash: FileHash,
+}
+
+impl HashedPath {
+    /// Hashes the file at `path`, reporting the progress for large
+    /// files.
+    pub(crate) fn new(path: PathBuf, algorithm: HashAlgorithm) -> io::Result<Self> {
+        let mut file = File::open(&path)?;
+        let total = file.metadata()?.len();
+        let mut hasher = match algorithm {
+            HashAlgorithm::Blake3 => Hasher::Blake3(Box::default()),
+            HashAlgorithm::Sha256 => Hasher::Sha256(sha2::Sha256::new()),
+        };
+        let mut buffer = vec![0; 64 * 1024];
+        let mut reporter = Reporter::new(&path, total);
+        let mut done = 0;
+        loop {
+            let read = match file.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            hasher.update(&buffer[..read]);
+            done += read as u64;
+            reporter.update(done);
+        }
+        reporter.finish(done);
+        let hash = FileHash {
+            algorithm,
+            bytes: hasher.finalize(),
+        };
+        Ok(Self { path, hash })
+    }
+}
+
+enum Hasher {
+    // The BLAKE3 hasher is much larger than the SHA-256 one.
+    Blake3(Box<blake3::Hasher>),
+    Sha256(sha2::Sha256),
+}
+
+impl Hasher {
+    fn update(&mut self, input: &[u8]) {
+        match self {
+            Hasher::Blake3(hasher) => {
+                hasher.update(input);
+            }
+            Hasher::Sha256(hasher) => hasher.update(input),
+        }
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        match self {
+            Hasher::Blake3(hasher) => *hasher.finalize().as_bytes(),
+            Hasher::Sha256(hasher) => hasher.finalize().into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hashes `input` written to a temporary file.
+    fn hash(input: &[u8], algorithm: HashAlgorithm) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "im-native-dialog-hash-{:?}-{}-{}",
+            algorithm,
+            input.len(),
+            std::process::id()
+        ));
+        std::fs::write(&path, input).unwrap();
+        let hashed = HashedPath::new(path.clone(), algorithm);
+        std::fs::remove_file(&path).unwrap();
+        let hashed = hashed.unwrap();
+        assert_eq!(hashed.hash.algorithm, algorithm);
+        hashed.hash.to_hex()
+    }
+
+    /// The input of the official BLAKE3 test vectors.
+    fn blake3_input(len: usize) -> Vec<u8> {
+        (0..len).map(|index| (index % 251) as u8).collect()
+    }
+
+    #[test]
+    fn sha256_matches_nist_vectors() {
+        let cases: &[(&[u8], &str)] = &[
+            (
+                b"",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(hash(input, HashAlgorithm::Sha256), *expected);
+        }
+        // Read in several buffers.
+        assert_eq!(
+            hash(&vec![b'a'; 1_000_000], HashAlgorithm::Sha256),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+    }
+
+    #[test]
+    fn blake3_matches_official_vectors() {
+        let cases = [
+            (
+                0,
+                "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+            ),
+            (
+                1,
+                "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213",
+            ),
+            // More than one chunk.
+            (
+                1025,
+                "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+            ),
+            // More than one read buffer.
+            (
+                102_400,
+                "bc3e3d41a1146b069abffad3c0d44860cf664390afce4d9661f7902e7943e085",
+            ),
+        ];
+        for (len, expected) in cases {
+            assert_eq!(hash(&blake3_input(len), HashAlgorithm::Blake3), expected);
+        }
+    }
+
+    #[test]
+    fn reports_missing_file() {
+        let path = std::env::temp_dir().join("im-native-dialog-hash-missing");
+        assert!(HashedPath::new(path, HashAlgorithm::default()).is_err());
+    }
+}
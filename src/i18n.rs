@@ -0,0 +1,131 @@
+use std::sync::{Arc, RwLock};
+
+/// A user-visible string which this crate supplies itself, rather than
+/// the caller or the platform, for example the button labels of
+/// [crate::ImNativeMessageDialog::show_yes_no_cancel()] where it is
+/// emulated using a dialog program. Filter descriptions, titles and
+/// messages are always supplied by the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Text {
+    /// The label of a button which accepts the dialog.
+    Ok,
+    /// The label of a button which cancels the dialog.
+    Cancel,
+    /// The label of a button which answers yes.
+    Yes,
+    /// The label of a button which answers no.
+    No,
+    /// Marks a file which already exists, in the confirmation of
+    /// [crate::ImNativeFileDialog::show_export()].
+    Exists,
+    /// The notice shown by `modal_overlay()`.
+    DialogOpen,
+    /// Explains [crate::DialogOptions::max_files()] before showing the
+    /// dialog again, followed by the limit.
+    TooManyFiles,
+    /// Explains [crate::DialogOptions::min_files()] before showing the
+    /// dialog again, followed by the limit.
+    TooFewFiles,
+    /// Explains [crate::DialogOptions::must_exist()] before showing the
+    /// dialog again, followed by the directory.
+    DirMissing,
+    /// Explains [crate::DialogOptions::must_be_writable()] before
+    /// showing the dialog again, followed by the directory.
+    DirNotWritable,
+    /// Explains [crate::DialogOptions::must_be_empty()] before showing
+    /// the dialog again, followed by the directory.
+    DirNotEmpty,
+    /// Explains [crate::DialogOptions::restrict_to()] before showing
+    /// the dialog again, followed by the directory.
+    OutsideRoot,
+    /// Explains [crate::DialogOptions::reject_network_paths()] before
+    /// showing the dialog again, followed by the path.
+    NetworkPath,
+    /// Explains [crate::DialogOptions::reject_cloud_placeholders()]
+    /// before showing the dialog again, followed by the path.
+    CloudPlaceholder,
+    /// The status of a dialog choosing a file to open, see
+    /// [crate::ImNativeFileDialog::status()].
+    WaitingForFile,
+    /// The status of a dialog choosing several files to open.
+    WaitingForFiles,
+    /// The status of a dialog choosing a directory.
+    WaitingForDir,
+    /// The status of a dialog choosing several directories.
+    WaitingForDirs,
+    /// The status of a dialog choosing where to save.
+    WaitingForSave,
+    /// The status of a color, font or application chooser.
+    WaitingForChoice,
+    /// The status of a message dialog or prompt.
+    WaitingForAnswer,
+    /// The status of [crate::ImNativeFileDialog::list_dir()].
+    ReadingDir,
+}
+
+impl Text {
+    /// The English string used when there is no [Translator], or it
+    /// has no translation.
+    pub fn english(self) -> &'static str {
+        match self {
+            Text::Ok => "OK",
+            Text::Cancel => "Cancel",
+            Text::Yes => "Yes",
+            Text::No => "No",
+            Text::Exists => "already exists",
+            Text::DialogOpen => "A dialog is open",
+            Text::TooManyFiles => "Too many files were selected, the most you can select is",
+            Text::TooFewFiles => "Too few files were selected, the fewest you can select is",
+            Text::DirMissing => "The directory doesn't exist",
+            Text::DirNotWritable => "The directory can't be written to",
+            Text::DirNotEmpty => "The directory isn't empty",
+            Text::OutsideRoot => "Only files inside this directory can be chosen",
+            Text::NetworkPath => "Files on network shares can't be chosen",
+            Text::CloudPlaceholder => {
+                "Files which are only stored online can't be chosen, make them available offline first"
+            }
+            Text::WaitingForFile => "Waiting for you to choose a file…",
+            Text::WaitingForFiles => "Waiting for you to choose files…",
+            Text::WaitingForDir => "Waiting for you to choose a folder…",
+            Text::WaitingForDirs => "Waiting for you to choose folders…",
+            Text::WaitingForSave => "Waiting for you to choose where to save…",
+            Text::WaitingForChoice => "Waiting for you to make a choice…",
+            Text::WaitingForAnswer => "Waiting for you to answer the dialog…",
+            Text::ReadingDir => "Reading the folder…",
+        }
+    }
+}
+
+/// Translates the strings this crate shows to users, registered using
+/// [set_translator()]. The strings shown by the platform's own dialogs
+/// are already localized by the platform.
+pub trait Translator: Send + Sync {
+    /// Returns the translation of `text`, or `None` to use
+    /// [Text::english()].
+    fn translate(&self, text: Text) -> Option<String>;
+}
+
+static TRANSLATOR: RwLock<Option<Arc<dyn Translator>>> = RwLock::new(None);
+
+/// Set the translator for every dialog shown by this crate from now
+/// on, replacing any previous translator.
+pub fn set_translator<T: Translator + 'static>(translator: T) {
+    *TRANSLATOR.write().expect("translator lock poisoned") = Some(Arc::new(translator));
+}
+
+/// Remove the translator set using [set_translator()].
+pub fn clear_translator() {
+    *TRANSLATOR.write().expect("translator lock poisoned") = None;
+}
+
+/// The translation of `text` using the translator set using
+/// [set_translator()].
+pub(crate) fn text(text: Text) -> String {
+    TRANSLATOR
+        .read()
+        .expect("translator lock poisoned")
+        .as_ref()
+        .and_then(|translator| translator.translate(text))
+        .unwrap_or_else(|| text.english().to_owned())
+}
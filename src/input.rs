@@ -0,0 +1,433 @@
+use native_dialog::Error;
+use secrecy::SecretString;
+
+use crate::{
+    command,
+    worker::{Selection, Worker},
+    DialogError, DialogKind, DialogResult, ImNativeDialogError, RequestToken,
+};
+
+/// A text input prompt for use with immediate mode gui libraries,
+/// following the same pattern as [crate::ImNativeFileDialog]. The
+/// `show*()` methods show the prompt in a new thread using the
+/// platform's dialog program (zenity/kdialog, osascript or
+/// PowerShell), and the result is returned to this object ready to be
+/// polled by the ui using [ImNativeInputDialog::check()]. With the
+/// `egui` feature, prompts which no dialog program can show are drawn
+/// using `ImNativeInputDialog::show_egui_fallback()` instead.
+pub struct ImNativeInputDialog<T> {
+    worker: Worker<T>,
+    /// The prompt being shown by the worker, kept to show it in egui if
+    /// there is no dialog program.
+    #[cfg(feature = "egui")]
+    pending: Option<Fallback<T>>,
+    #[cfg(feature = "egui")]
+    fallback: Option<Fallback<T>>,
+    /// The answer to the prompt shown in egui, until it is checked.
+    #[cfg(feature = "egui")]
+    answer: Option<T>,
+}
+
+impl<T> Default for ImNativeInputDialog<T> {
+    fn default() -> Self {
+        Self {
+            worker: Worker::default(),
+            #[cfg(feature = "egui")]
+            pending: None,
+            #[cfg(feature = "egui")]
+            fallback: None,
+            #[cfg(feature = "egui")]
+            answer: None,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Prompt {
+    title: String,
+    text: String,
+    default: String,
+    masked: bool,
+}
+
+/// A prompt drawn using [ImNativeInputDialog::show_egui_fallback()].
+#[cfg(feature = "egui")]
+struct Fallback<T> {
+    prompt: Prompt,
+    entry: String,
+    focused: bool,
+    convert: fn(Option<String>) -> T,
+}
+
+impl ImNativeInputDialog<Option<String>> {
+    /// Shows a prompt that let users to enter a single line of text,
+    /// initially containing `default`. The result is `None` if the
+    /// user cancelled the prompt.
+    pub fn show_input(
+        &mut self,
+        title: impl Into<String>,
+        text: impl Into<String>,
+        default: impl Into<String>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let prompt = Prompt {
+            title: title.into(),
+            text: text.into(),
+            default: default.into(),
+            masked: false,
+        };
+        self.spawn_prompt(DialogKind::Input, prompt, |text| text)
+    }
+}
+
+impl ImNativeInputDialog<Option<SecretString>> {
+    /// Shows a prompt that let users to enter a password, with the
+    /// entered text masked. The result is `None` if the user
+    /// cancelled the prompt. The password is never logged.
+    pub fn show_password(
+        &mut self,
+        title: impl Into<String>,
+        text: impl Into<String>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let prompt = Prompt {
+            title: title.into(),
+            text: text.into(),
+            default: String::new(),
+            masked: true,
+        };
+        self.spawn_prompt(DialogKind::Password, prompt, |password| {
+            password.map(SecretString::new)
+        })
+    }
+}
+
+impl<T: Send + 'static + Default> ImNativeInputDialog<T> {
+    fn spawn_prompt(
+        &mut self,
+        kind: DialogKind,
+        prompt: Prompt,
+        convert: fn(Option<String>) -> T,
+    ) -> Result<RequestToken, ImNativeDialogError>
+    where
+        T: Selection,
+    {
+        #[cfg(feature = "egui")]
+        let fallback = Fallback {
+            prompt: prompt.clone(),
+            entry: prompt.default.clone(),
+            focused: false,
+            convert,
+        };
+        let token = self.worker.spawn(kind, None, move || {
+            input(&prompt.title, &prompt.text, &prompt.default, prompt.masked).map(convert)
+        })?;
+        #[cfg(feature = "egui")]
+        {
+            self.pending = Some(fallback);
+        }
+        Ok(token)
+    }
+
+    /// Keeps the prompt to show in egui instead of returning `result`,
+    /// if it failed because there is no dialog program.
+    #[cfg(feature = "egui")]
+    fn fall_back<R>(&mut self, result: Option<R>, failed: impl FnOnce(&R) -> bool) -> Option<R> {
+        let result = result?;
+        match self.pending.take() {
+            Some(pending) if failed(&result) => {
+                self.fallback = Some(pending);
+                None
+            }
+            _ => Some(result),
+        }
+    }
+
+    /// Draws the prompt in an egui window while it is open, if no
+    /// dialog program could show it, available with the `egui`
+    /// feature. Call this every frame, the answer is returned by
+    /// [ImNativeInputDialog::check()] once the user confirms or
+    /// cancels the prompt. The callback set using
+    /// [ImNativeInputDialog::with_callback()] has already been called
+    /// with [DialogError::NoImplementation] by then.
+    #[cfg(feature = "egui")]
+    pub fn show_egui_fallback(&mut self, ctx: &egui::CtxRef) {
+        use crate::i18n::{self, Text};
+
+        let fallback = match &mut self.fallback {
+            Some(fallback) => fallback,
+            None => return,
+        };
+        let mut answer = None;
+        egui::Window::new(&fallback.prompt.title)
+            .id(egui::Id::new("im_native_dialog_input_fallback"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(&fallback.prompt.text);
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut fallback.entry)
+                        .password(fallback.prompt.masked),
+                );
+                if !fallback.focused {
+                    response.request_focus();
+                    fallback.focused = true;
+                }
+                let entered = response.lost_focus() && ui.input().key_pressed(egui::Key::Enter);
+                ui.horizontal(|ui| {
+                    if ui.button(i18n::text(Text::Ok)).clicked() || entered {
+                        answer = Some(Some(fallback.entry.clone()));
+                    }
+                    if ui.button(i18n::text(Text::Cancel)).clicked()
+                        || ui.input().key_pressed(egui::Key::Escape)
+                    {
+                        answer = Some(None);
+                    }
+                });
+            });
+        if let Some(answer) = answer {
+            if let Some(fallback) = self.fallback.take() {
+                self.answer = Some((fallback.convert)(answer));
+            }
+        }
+    }
+    /// Set a callback to use for this dialog which will be called
+    /// immediately upon dialog close in the dialog monitoring thread.
+    pub fn with_callback<C>(&mut self, callback: C) -> &mut Self
+    where
+        C: FnOnce(&Result<T, DialogError>) + Send + 'static,
+    {
+        self.worker.set_callback(Box::new(callback));
+        self
+    }
+
+    /// Check if the prompt is complete. If it is complete it will
+    /// return `Some` with the result of the prompt, otherwise will
+    /// return `None`. This will update the status of
+    /// [ImNativeInputDialog::is_open()].
+    pub fn check(&mut self) -> Option<Result<T, DialogError>> {
+        #[cfg(feature = "egui")]
+        {
+            if let Some(answer) = self.answer.take() {
+                return Some(Ok(answer));
+            }
+            let result = self.worker.check();
+            self.fall_back(result, |result| {
+                matches!(result, Err(DialogError::NoImplementation))
+            })
+        }
+        #[cfg(not(feature = "egui"))]
+        self.worker.check()
+    }
+
+    /// Like [ImNativeInputDialog::check()], but tells cancellation apart
+    /// from failures, see [DialogResult].
+    pub fn check_result(&mut self) -> Option<DialogResult<T>> {
+        #[cfg(feature = "egui")]
+        {
+            if let Some(answer) = self.answer.take() {
+                return Some(DialogResult::Selected(answer));
+            }
+            let result = self.worker.check_result();
+            self.fall_back(result, |result| {
+                matches!(result, DialogResult::Failed(DialogError::NoImplementation))
+            })
+        }
+        #[cfg(not(feature = "egui"))]
+        self.worker.check_result()
+    }
+
+    /// Returns `true` if the prompt is currently open, otherwise
+    /// returns `false`. Requires a previous call of
+    /// [ImNativeInputDialog::check()] to update the current status.
+    pub fn is_open(&self) -> bool {
+        #[cfg(feature = "egui")]
+        if self.fallback.is_some() {
+            return true;
+        }
+        self.worker.is_open()
+    }
+
+    /// Returns the kind of the open prompt, or `None` if it isn't open,
+    /// see [crate::ImNativeFileDialog::kind()].
+    pub fn kind(&self) -> Option<DialogKind> {
+        self.worker.kind()
+    }
+
+    /// Returns a short description of the open prompt, or `None` if it
+    /// isn't open, see [crate::ImNativeFileDialog::status()].
+    pub fn status(&self) -> Option<String> {
+        self.worker.status()
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn input(title: &str, text: &str, default: &str, masked: bool) -> Result<Option<String>, Error> {
+    use std::process::Command;
+
+    let mut command = match command::gnu_program() {
+        Some(command::GnuProgram::KDialog) => {
+            let mut command = Command::new("kdialog");
+            command.arg("--title").arg(title);
+            if masked {
+                command.arg("--password").arg(text);
+            } else {
+                command.arg("--inputbox").arg(text).arg(default);
+            }
+            command
+        }
+        Some(command::GnuProgram::Zenity) => {
+            let mut command = Command::new("zenity");
+            command
+                .arg("--entry")
+                .arg(format!("--title={}", title))
+                .arg(format!("--text={}", text))
+                .arg(format!("--entry-text={}", default));
+            if masked {
+                command.arg("--hide-text");
+            }
+            command
+        }
+        None => return Err(Error::NoImplementation),
+    };
+    command::run(&mut command)
+}
+
+#[cfg(target_os = "macos")]
+fn input(title: &str, text: &str, default: &str, masked: bool) -> Result<Option<String>, Error> {
+    command::osascript(&format!(
+        "text returned of (display dialog {} default answer {} with title {}{})",
+        command::applescript_string(text),
+        command::applescript_string(default),
+        command::applescript_string(title),
+        if masked { " with hidden answer" } else { "" },
+    ))
+}
+
+#[cfg(target_os = "windows")]
+fn input(title: &str, text: &str, default: &str, masked: bool) -> Result<Option<String>, Error> {
+    if masked {
+        return password_form(title, text);
+    }
+
+    // InputBox returns an empty string when cancelled.
+    command::powershell(&format!(
+        "Add-Type -AssemblyName Microsoft.VisualBasic; \
+        $text = [Microsoft.VisualBasic.Interaction]::InputBox({}, {}, {}); \
+        if ($text -eq '') {{ exit 1 }}; $text",
+        command::powershell_string(text),
+        command::powershell_string(title),
+        command::powershell_string(default),
+    ))
+}
+
+/// InputBox has no masked variant, so a small form is built instead.
+#[cfg(target_os = "windows")]
+fn password_form(title: &str, text: &str) -> Result<Option<String>, Error> {
+    use crate::i18n::{self, Text};
+
+    command::powershell(&format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+        $form = New-Object System.Windows.Forms.Form; \
+        $form.Text = {}; $form.FormBorderStyle = 'FixedDialog'; \
+        $form.StartPosition = 'CenterScreen'; $form.TopMost = $true; \
+        $form.MinimizeBox = $false; $form.MaximizeBox = $false; \
+        $form.ClientSize = '320,105'; \
+        $label = New-Object System.Windows.Forms.Label; \
+        $label.Text = {}; $label.Location = '10,10'; $label.AutoSize = $true; \
+        $box = New-Object System.Windows.Forms.TextBox; \
+        $box.UseSystemPasswordChar = $true; $box.Location = '10,35'; $box.Width = 300; \
+        $ok = New-Object System.Windows.Forms.Button; \
+        $ok.Text = {}; $ok.Location = '154,70'; $ok.DialogResult = 'OK'; \
+        $cancel = New-Object System.Windows.Forms.Button; \
+        $cancel.Text = {}; $cancel.Location = '235,70'; $cancel.DialogResult = 'Cancel'; \
+        $form.AcceptButton = $ok; $form.CancelButton = $cancel; \
+        $form.Controls.AddRange(@($label, $box, $ok, $cancel)); \
+        if ($form.ShowDialog() -ne 'OK') {{ exit 1 }}; $box.Text",
+        command::powershell_string(title),
+        command::powershell_string(text),
+        command::powershell_string(&i18n::text(Text::Ok)),
+        command::powershell_string(&i18n::text(Text::Cancel)),
+    ))
+}
+
+#[cfg(all(test, feature = "egui"))]
+mod tests {
+    use super::*;
+
+    fn fallen_back() -> ImNativeInputDialog<Option<String>> {
+        let mut dialog = ImNativeInputDialog::default();
+        dialog.pending = Some(Fallback {
+            prompt: Prompt {
+                title: "Rename".to_owned(),
+                text: "New name:".to_owned(),
+                default: "a.txt".to_owned(),
+                masked: false,
+            },
+            entry: "a.txt".to_owned(),
+            focused: false,
+            convert: |text| text,
+        });
+        let failed = Some(Err::<Option<String>, _>(DialogError::NoImplementation));
+        let result = dialog.fall_back(failed, |result| {
+            matches!(result, Err(DialogError::NoImplementation))
+        });
+        assert!(result.is_none());
+        dialog
+    }
+
+    fn frame(
+        ctx: &mut egui::CtxRef,
+        dialog: &mut ImNativeInputDialog<Option<String>>,
+        key: egui::Key,
+    ) {
+        let mut input = egui::RawInput::default();
+        input.events.push(egui::Event::Key {
+            key,
+            pressed: true,
+            modifiers: egui::Modifiers::default(),
+        });
+        ctx.begin_frame(input);
+        dialog.show_egui_fallback(ctx);
+        let _ = ctx.end_frame();
+    }
+
+    #[test]
+    fn shows_prompt_in_egui_without_dialog_program() {
+        let mut dialog = fallen_back();
+        assert!(dialog.is_open());
+        assert!(dialog.check().is_none());
+
+        let mut ctx = egui::CtxRef::default();
+        ctx.begin_frame(egui::RawInput::default());
+        dialog.show_egui_fallback(&ctx);
+        let _ = ctx.end_frame();
+        assert!(dialog.is_open());
+        frame(&mut ctx, &mut dialog, egui::Key::Enter);
+        assert!(!dialog.is_open());
+        assert_eq!(dialog.check().unwrap().unwrap(), Some("a.txt".to_owned()));
+        assert!(dialog.check().is_none());
+    }
+
+    #[test]
+    fn cancels_prompt_in_egui_with_escape() {
+        let mut dialog = fallen_back();
+        let mut ctx = egui::CtxRef::default();
+        frame(&mut ctx, &mut dialog, egui::Key::Escape);
+        assert!(!dialog.is_open());
+        assert_eq!(dialog.check().unwrap().unwrap(), None);
+    }
+
+    #[test]
+    fn returns_other_failures() {
+        let mut dialog = ImNativeInputDialog::<Option<String>>::default();
+        dialog.pending = fallen_back().fallback;
+        let result = dialog.fall_back(
+            Some(Err::<Option<String>, _>(DialogError::Implementation(
+                "zenity crashed".to_owned(),
+            ))),
+            |result| matches!(result, Err(DialogError::NoImplementation)),
+        );
+        assert!(matches!(result, Some(Err(DialogError::Implementation(_)))));
+        assert!(!dialog.is_open());
+    }
+}
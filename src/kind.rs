@@ -0,0 +1,59 @@
+use crate::Text;
+
+/// The kind of dialog being shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DialogKind {
+    OpenSingleFile,
+    OpenMultipleFile,
+    OpenSingleDir,
+    OpenMultipleDir,
+    OpenMultipleEntry,
+    SaveSingleFile,
+    Export,
+    /// A path read from the clipboard using
+    /// [crate::ImNativeFileDialog::paste_path()].
+    Clipboard,
+    Input,
+    Password,
+    Color,
+    Font,
+    Alert,
+    Confirm,
+    YesNoCancel,
+    AppChooser,
+    /// A dialog shown using [crate::ImNativeFileDialog::show()].
+    Custom,
+    /// A sequence of dialogs shown using
+    /// [crate::ImNativeFileDialog::show_flow()].
+    Flow,
+    /// A dialog defined outside this crate, shown using
+    /// [crate::ImNativeFileDialog::show_request()].
+    Request,
+    /// A directory read using [crate::ImNativeFileDialog::list_dir()],
+    /// without showing a dialog.
+    ListDir,
+}
+
+impl DialogKind {
+    /// Describes what the user is being asked while the dialog is open.
+    pub(crate) fn waiting_text(self) -> Text {
+        match self {
+            DialogKind::OpenSingleFile | DialogKind::Clipboard => Text::WaitingForFile,
+            DialogKind::OpenMultipleFile | DialogKind::OpenMultipleEntry => Text::WaitingForFiles,
+            DialogKind::OpenSingleDir => Text::WaitingForDir,
+            DialogKind::OpenMultipleDir => Text::WaitingForDirs,
+            DialogKind::SaveSingleFile | DialogKind::Export => Text::WaitingForSave,
+            DialogKind::Color | DialogKind::Font | DialogKind::AppChooser => Text::WaitingForChoice,
+            DialogKind::Input
+            | DialogKind::Password
+            | DialogKind::Alert
+            | DialogKind::Confirm
+            | DialogKind::YesNoCancel
+            | DialogKind::Custom
+            | DialogKind::Flow
+            | DialogKind::Request => Text::WaitingForAnswer,
+            DialogKind::ListDir => Text::ReadingDir,
+        }
+    }
+}
@@ -1,31 +1,241 @@
 //! This crate is a wrapper around [FileDialog] for use with immediate mode gui
-//! libraries. See [ImNativeFileDialog] for more information.
+//! libraries. See [ImNativeFileDialog] for more information. Simple
+//! text prompts are available using [ImNativeInputDialog], color
+//! pickers using [ImNativeColorDialog], font choosers using
+//! [ImNativeFontDialog], message dialogs using
+//! [ImNativeMessageDialog] and "open with" application choosers using
+//! [ImNativeAppChooserDialog].
 
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use native_dialog::FileDialog;
 use thiserror::Error;
 
+/// How often [ImNativeFileDialog::show_modal_with()] checks the dialog
+/// and calls the pump closure, about once per frame.
+const MODAL_POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+mod app;
+#[cfg(any(feature = "async-std", feature = "futures"))]
+mod asynchronous;
+mod bookmark;
+mod builder;
+mod cancel;
+mod capabilities;
+mod channel;
+mod checks;
+mod choices;
+mod clipboard;
+mod clock;
+mod color;
+mod command;
+mod config;
+mod diagnostics;
+mod dialog_request;
+#[cfg(feature = "egui")]
+mod egui_memory;
+#[cfg(feature = "egui")]
+mod egui_widgets;
+mod event;
+mod expiry;
+mod filter_path;
+mod flow;
+mod font;
+#[cfg(target_os = "windows")]
+mod foreground;
+mod guard;
+mod handle;
+#[cfg(feature = "hash")]
+mod hash;
+mod i18n;
+mod input;
+mod kind;
+mod listing;
+#[cfg(target_os = "macos")]
+mod macos;
+mod manager;
+mod message;
+mod mime;
+mod observer;
+mod options;
+mod outcome;
+mod panic_hook;
+mod paths;
+mod portal;
+pub mod prelude;
+mod prewarm;
+mod progress;
+mod rate_limit;
+mod remote;
+#[cfg(feature = "replay")]
+mod replay;
+mod request;
+mod result;
+mod reveal;
+mod selection_filter;
+mod shared;
+mod shutdown;
+mod state;
+mod sync_dialog;
+mod template;
+#[cfg(all(feature = "terminal", unix, not(target_os = "macos")))]
+mod terminal;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod thumbnail;
+mod volumes;
+#[cfg(feature = "watch")]
+mod watch;
+#[cfg(target_os = "windows")]
+mod windows;
+mod worker;
+
+pub use app::{Application, ImNativeAppChooserDialog};
+#[cfg(any(feature = "async-std", feature = "futures"))]
+pub use asynchronous::{AsyncFileDialog, DialogFuture};
+pub use bookmark::{Bookmark, BookmarkAccess};
+pub use builder::{DisconnectPolicy, ImNativeFileDialogBuilder};
+pub use cancel::CancelToken;
+pub use capabilities::{capabilities, Backend, Capabilities};
+pub use channel::{Receiver, Sender};
+pub use checks::{CheckedPath, PathChecks};
+pub use choices::{ChoicePath, ChoiceValue};
+pub use color::{ImNativeColorDialog, Rgba};
+pub use config::{init, GlobalConfig};
+pub use diagnostics::{diagnostics, Diagnostics};
+pub use dialog_request::DialogRequest;
+use dialog_request::Requested;
+#[cfg(feature = "egui")]
+pub use egui_memory::EguiDialog;
+#[cfg(feature = "egui")]
+pub use egui_widgets::{dialog_status, modal_overlay};
+pub use event::{subscribe_events, DialogEvent, DialogId};
+pub use expiry::{end_frame, ExpiryPolicy};
+pub use filter_path::{FilterPath, SelectedFilter};
+pub use flow::DialogFlow;
+pub use font::{Font, ImNativeFontDialog};
+pub use guard::{cancel_queued, queued_requests, set_global_guard, GuardPolicy, QueuedRequestInfo};
+pub use handle::DialogHandle;
+#[cfg(feature = "hash")]
+pub use hash::{FileHash, HashAlgorithm, HashedPath};
+pub use i18n::{clear_translator, set_translator, Text, Translator};
+pub use input::ImNativeInputDialog;
+pub use kind::DialogKind;
+pub use listing::{DirEntryInfo, DirListing, EntryType};
+#[cfg(target_os = "macos")]
+pub use macos::DialogOptionsExtMacOs;
+pub use manager::{DialogManager, ReadyBatch};
+pub use message::{ImNativeMessageDialog, MessageChoice};
+pub use native_dialog::{self, Error as NativeDialogError, MessageType};
+#[cfg(feature = "watch")]
+pub use notify;
+pub use observer::{clear_observer, set_observer, DialogObserver};
+pub use options::{DialogOptions, LimitPolicy, ScreenRect, SortOrder, Theme};
+pub use outcome::{DialogOutcome, OptionsSnapshot};
+pub use panic_hook::{clear_panic_hook, set_panic_hook, DialogPanic};
+pub use paths::{add_long_path_prefix, strip_long_path_prefix, DisplayPath, LongPathPrefix};
+pub use portal::{is_flatpak, is_sandboxed, is_snap, PortalDocument};
+pub use prewarm::prewarm;
+pub use progress::Progress;
+pub use rate_limit::{set_rate_limit, RateLimit, RateLimitPolicy};
+pub use raw_window_handle;
+#[cfg(feature = "replay")]
+pub use replay::{start_replay, stop_replay, Recorder};
+pub use request::{Request, RequestToken};
+pub use result::DialogResult;
+pub use reveal::reveal_in_file_manager;
+pub use secrecy;
+pub use selection_filter::{FilteredSelection, RejectReason, Rejected, SelectionFilter};
+pub use shared::SharedDialog;
+pub use shutdown::{shutdown, DiscardedRequest, ShutdownReport};
+pub use state::DialogState;
+pub use sync_dialog::SyncDialog;
+pub use template::{set_template_counters, template_counters, TemplateCounters};
+pub use thumbnail::{
+    clear_image_decoder, set_image_decoder, ImageDecoder, Thumbnail, ThumbnailPath,
+};
+pub use volumes::{volumes, Volume};
+#[cfg(feature = "watch")]
+pub use watch::PathWatcher;
+#[cfg(target_os = "windows")]
+pub use windows::DialogOptionsExtWindows;
+use worker::Worker;
+
 /// Error associated with [NativeFileDialog].
 #[derive(Error, Debug)]
 pub enum ImNativeDialogError {
     #[error("The dialog is already open.")]
     AlreadyOpen,
+    #[error("Another dialog is already open.")]
+    Busy,
+    #[error("Too many dialogs were opened recently.")]
+    RateLimited,
+    #[error("The dialog thread could not be spawned.")]
+    SpawnFailed(#[source] std::io::Error),
+}
+
+/// Error returned by a dialog via [ImNativeFileDialog::check()], which
+/// doesn't depend on the backend used to show the dialog.
+#[derive(Error, Debug)]
+pub enum DialogError {
+    #[error("System error or I/O failure.")]
+    Io(#[from] std::io::Error),
+    #[error("No dialog implementation is available.")]
+    NoImplementation,
+    #[error("The dialog implementation failed: {0}")]
+    Implementation(String),
+}
+
+impl From<native_dialog::Error> for DialogError {
+    fn from(error: native_dialog::Error) -> Self {
+        match error {
+            native_dialog::Error::IoFailure(error) => DialogError::Io(error),
+            native_dialog::Error::NoImplementation => DialogError::NoImplementation,
+            native_dialog::Error::ImplementationError(message) => {
+                DialogError::Implementation(message)
+            }
+            error => DialogError::Implementation(error.to_string()),
+        }
+    }
+}
+
+/// An entry selected using
+/// [ImNativeFileDialog::show_open_multiple_entry()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectedEntry {
+    File(PathBuf),
+    Dir(PathBuf),
 }
 
 /// A wrapper around [FileDialog] for use with immediate mode gui
 /// libraries. The `show*()` methods create a [FileDialog] in a new
 /// thread, and the result is returned to this object via
-/// [crossbeam_channel], ready to be polled by the ui using
+/// a [Sender], ready to be polled by the ui using
 /// [ImNativeFileDialog::check()]
 pub struct ImNativeFileDialog<T> {
-    callback: Option<Box<dyn FnOnce(&Result<T, native_dialog::Error>) + Send>>,
-    receiver: Option<crossbeam_channel::Receiver<Result<T, native_dialog::Error>>>,
+    worker: Worker<T>,
+    stream: Option<Receiver<PathBuf>>,
 }
 
 impl<T> Default for ImNativeFileDialog<T> {
     fn default() -> Self {
-        Self { callback: None, receiver: None }
+        Self {
+            worker: Worker::default(),
+            stream: None,
+        }
+    }
+}
+
+impl<T: Send + 'static + Default> std::fmt::Debug for ImNativeFileDialog<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImNativeFileDialog")
+            .field("state", &self.state())
+            .field("streaming", &self.stream.is_some())
+            .finish_non_exhaustive()
     }
 }
 
@@ -33,20 +243,197 @@ impl ImNativeFileDialog<Vec<PathBuf>> {
     /// Shows a dialog that let users to open multiple files using [FileDialog::show_open_multiple_file()].
     pub fn show_open_multiple_file(
         &mut self,
-        location: Option<PathBuf>,
-    ) -> Result<(), ImNativeDialogError> {
-        self.show(|sender, dialog, callback| {
-            let dialog = match &location {
-                Some(location) => dialog.set_location(location),
-                None => dialog,
-            };
-            let result = dialog.show_open_multiple_file();
-            callback(&result);
-            sender
-                .send(result)
-                .expect("error sending show_open_multiple_file result to ui");
-            drop(location)
-        })
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenMultipleFile,
+            Some(options.clone()),
+            move || options.show_open_multiple_file(),
+        )
+    }
+
+    /// Shows a dialog that let users to open multiple files using
+    /// [FileDialog::show_open_multiple_file()], where the selected
+    /// paths are streamed to [ImNativeFileDialog::check_stream()]
+    /// instead of being returned all at once. The result returned by
+    /// [ImNativeFileDialog::check()] is always empty, and marks the end
+    /// of the stream.
+    pub fn show_open_multiple_file_stream(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        self.show_open_multiple_file_stream_with(options, |_| Ok(()))
+    }
+
+    /// The same as [ImNativeFileDialog::show_open_multiple_file_stream()],
+    /// but calls `process` with each selected path in the dialog thread
+    /// before it is streamed, for example to read its metadata. Any
+    /// error returned by `process` is returned via
+    /// [ImNativeFileDialog::check()], and ends the stream.
+    pub fn show_open_multiple_file_stream_with<P>(
+        &mut self,
+        options: impl Into<DialogOptions>,
+        mut process: P,
+    ) -> Result<RequestToken, ImNativeDialogError>
+    where
+        P: FnMut(&Path) -> std::io::Result<()> + Send + 'static,
+    {
+        let options = self.worker.options(options.into());
+        let (sender, receiver) = channel::unbounded();
+        let token = self.worker.spawn_map(
+            DialogKind::OpenMultipleFile,
+            Some(options.clone()),
+            move || -> Result<_, DialogError> {
+                let paths = options.show_open_multiple_file()?;
+                let count = paths.len();
+                for path in paths {
+                    process(&path)?;
+                    // The ui may have stopped checking the stream.
+                    let _ = sender.send(path);
+                }
+                Ok(count)
+            },
+            |_| Vec::new(),
+        )?;
+        self.stream = Some(receiver);
+        Ok(token)
+    }
+
+    /// Returns the paths streamed since the previous call, from a
+    /// dialog shown using
+    /// [ImNativeFileDialog::show_open_multiple_file_stream()], or `None`
+    /// if there are none. Call this every frame alongside
+    /// [ImNativeFileDialog::check()] to process a large selection in
+    /// batches.
+    pub fn check_stream(&mut self) -> Option<Vec<PathBuf>> {
+        let stream = self.stream.as_ref()?;
+        let mut batch = Vec::new();
+        loop {
+            match stream.try_recv() {
+                Ok(path) => batch.push(path),
+                Err(channel::TryRecvError::Empty) => break,
+                Err(channel::TryRecvError::Disconnected) => {
+                    self.stream = None;
+                    break;
+                }
+            }
+        }
+
+        if batch.is_empty() {
+            None
+        } else {
+            Some(batch)
+        }
+    }
+
+    /// Shows a dialog that let users to open multiple directories.
+    /// [FileDialog] has no native support for this, so
+    /// [FileDialog::show_open_single_dir()] is shown repeatedly, each
+    /// time starting in the parent of the previously selected
+    /// directory, until the user cancels.
+    pub fn show_open_multiple_dir(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenMultipleDir,
+            Some(options.clone()),
+            move || open_multiple_dir(&options),
+        )
+    }
+
+    /// Shows a dialog that let users to choose a directory to export
+    /// `file_names` to using [FileDialog::show_open_single_dir()], and
+    /// then asks them to confirm the list of files using
+    /// [native_dialog::MessageDialog::show_confirm()], marking the
+    /// files which already exist. The result is the paths of the files
+    /// in the chosen directory, or empty if the user cancelled either
+    /// dialog.
+    pub fn show_export(
+        &mut self,
+        options: impl Into<DialogOptions>,
+        file_names: Vec<String>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::Export,
+            Some(options.clone()),
+            move || -> Result<_, DialogError> {
+                let dir = match options.show_open_single_dir()? {
+                    Some(dir) => dir,
+                    None => return Ok(Vec::new()),
+                };
+                let paths: Vec<PathBuf> = file_names.iter().map(|name| dir.join(name)).collect();
+
+                let mut text = format!("{}:", dir.display());
+                for (name, path) in file_names.iter().zip(&paths) {
+                    text.push('\n');
+                    text.push_str(name);
+                    if path.exists() {
+                        text.push_str(&format!(" ({})", i18n::text(i18n::Text::Exists)));
+                    }
+                }
+                let confirmed = native_dialog::MessageDialog::new()
+                    .set_title(options.title.as_deref().unwrap_or_default())
+                    .set_text(&text)
+                    .set_type(MessageType::Info)
+                    .show_confirm()?;
+
+                Ok(if confirmed { paths } else { Vec::new() })
+            },
+        )
+    }
+}
+
+impl ImNativeFileDialog<FilteredSelection> {
+    /// Shows a dialog that let users to open multiple files using
+    /// [FileDialog::show_open_multiple_file()], and then applies
+    /// `filter` to the selected files in the dialog thread, returning
+    /// the accepted files along with the rejected ones and why they
+    /// were rejected.
+    pub fn show_open_multiple_file_filtered(
+        &mut self,
+        options: impl Into<DialogOptions>,
+        filter: SelectionFilter,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenMultipleFile,
+            Some(options.clone()),
+            move || -> Result<_, DialogError> {
+                let (paths, excess) = options.show_open_multiple_file_limited()?;
+                Ok(filter.apply(paths, excess))
+            },
+        )
+    }
+}
+
+impl ImNativeFileDialog<Vec<SelectedEntry>> {
+    /// Shows a dialog that let users to open a mix of files and
+    /// directories. [FileDialog] has no native support for this, so
+    /// [FileDialog::show_open_multiple_file()] is shown first,
+    /// followed by the same directory selection as
+    /// [ImNativeFileDialog::show_open_multiple_dir()].
+    pub fn show_open_multiple_entry(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenMultipleEntry,
+            Some(options.clone()),
+            move || -> Result<_, DialogError> {
+                let files = options.show_open_multiple_file()?;
+                let dirs = open_multiple_dir(&options)?;
+                Ok(files
+                    .into_iter()
+                    .map(SelectedEntry::File)
+                    .chain(dirs.into_iter().map(SelectedEntry::Dir))
+                    .collect())
+            },
+        )
     }
 }
 
@@ -54,69 +441,697 @@ impl ImNativeFileDialog<Option<PathBuf>> {
     /// Shows a dialog that let users to open one directory using [FileDialog::show_open_single_dir()].
     pub fn open_single_dir(
         &mut self,
-        location: Option<PathBuf>,
-    ) -> Result<(), ImNativeDialogError> {
-        self.show(|sender, dialog, callback| {
-            let dialog = match &location {
-                Some(location) => dialog.set_location(location),
-                None => dialog,
-            };
-            let result = dialog.show_open_single_dir();
-            callback(&result);
-            sender
-                .send(result)
-                .expect("error sending open_single_dir result to ui");
-            drop(location)
-        })
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenSingleDir,
+            Some(options.clone()),
+            move || options.show_open_single_dir(),
+        )
     }
 
     /// Shows a dialog that let users to open one file using [FileDialog::show_open_single_file()].
     pub fn open_single_file(
         &mut self,
-        location: Option<PathBuf>,
-    ) -> Result<(), ImNativeDialogError> {
-        self.show(|sender, dialog, callback| {
-            let dialog = match &location {
-                Some(location) => dialog.set_location(location),
-                None => dialog,
-            };
-            let result = dialog.show_open_single_file();
-            callback(&result);
-            sender
-                .send(result)
-                .expect("error sending open_single_file result to ui");
-            drop(location)
-        })
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenSingleFile,
+            Some(options.clone()),
+            move || options.show_open_single_file(),
+        )
+    }
+
+    /// Reads a path from the system clipboard in the dialog thread
+    /// instead of showing a dialog, returning it like
+    /// [ImNativeFileDialog::open_single_file()]. This suits users who
+    /// already have the path at hand, and remote sessions where dialogs
+    /// are awkward. The result is `None` if the clipboard doesn't
+    /// contain the path of an existing file or directory. The
+    /// clipboard is read using `pbpaste` on macOS, PowerShell on
+    /// Windows, and `wl-paste`, `xclip` or `xsel` on GNU/Linux and BSD
+    /// Unix, otherwise [DialogError::NoImplementation] is returned.
+    pub fn paste_path(&mut self) -> Result<RequestToken, ImNativeDialogError> {
+        self.worker
+            .spawn(DialogKind::Clipboard, None, clipboard::path)
     }
 
     /// Shows a dialog that let users to save one file using [FileDialog::show_save_single_file()].
     pub fn show_save_single_file(
         &mut self,
-        location: Option<PathBuf>,
-    ) -> Result<(), ImNativeDialogError> {
-        self.show(|sender, dialog, callback| {
-            let dialog = match &location {
-                Some(location) => dialog.set_location(location),
-                None => dialog,
-            };
-            let result = dialog.show_save_single_file();
-            callback(&result);
-            sender
-                .send(result)
-                .expect("error sending show_save_single_file result to ui");
-            drop(location)
-        })
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::SaveSingleFile,
+            Some(options.clone()),
+            move || options.show_save(),
+        )
+    }
+
+    /// Shows a dialog that let users to save one file using
+    /// [FileDialog::show_save_single_file()], and then writes `bytes`
+    /// to the chosen path in the dialog thread. Any error writing the
+    /// file is returned via [ImNativeFileDialog::check()].
+    pub fn show_save_and_write(
+        &mut self,
+        options: impl Into<DialogOptions>,
+        bytes: Vec<u8>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        self.show_save_and_write_with(options, move |path| progress::write(path, &bytes))
+    }
+
+    /// Shows a dialog that let users to save one file using
+    /// [FileDialog::show_save_single_file()], and then calls `write`
+    /// with the chosen path in the dialog thread. Any error returned
+    /// by `write` is returned via [ImNativeFileDialog::check()].
+    pub fn show_save_and_write_with<W>(
+        &mut self,
+        options: impl Into<DialogOptions>,
+        write: W,
+    ) -> Result<RequestToken, ImNativeDialogError>
+    where
+        W: FnOnce(&Path) -> std::io::Result<()> + Send + 'static,
+    {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::SaveSingleFile,
+            Some(options.clone()),
+            move || -> Result<_, DialogError> {
+                let path = options.show_save_unnotified()?;
+                if let Some(path) = &path {
+                    write(path)?;
+                    options.notify_saved();
+                }
+                Ok(path)
+            },
+        )
+    }
+}
+
+impl ImNativeFileDialog<Option<CheckedPath>> {
+    /// Shows a dialog that let users to open one file using
+    /// [FileDialog::show_open_single_file()], returning the chosen path
+    /// along with its [PathChecks].
+    pub fn open_single_file_checked(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenSingleFile,
+            Some(options.clone()),
+            move || {
+                // The links are resolved here to keep the original.
+                let resolve_symlinks = options.resolve_symlinks;
+                let options = options.resolve_symlinks(false);
+                options
+                    .show_open_single_file()
+                    .map(|path| path.map(|path| CheckedPath::new(path, resolve_symlinks)))
+            },
+        )
+    }
+
+    /// Shows a dialog that let users to save one file using
+    /// [FileDialog::show_save_single_file()], returning the chosen path
+    /// along with its [PathChecks], for example to warn that the file
+    /// is read-only or the volume is nearly full.
+    pub fn show_save_single_file_checked(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::SaveSingleFile,
+            Some(options.clone()),
+            move || {
+                let resolve_symlinks = options.resolve_symlinks;
+                let options = options.resolve_symlinks(false);
+                options
+                    .show_save()
+                    .map(|path| path.map(|path| CheckedPath::new(path, resolve_symlinks)))
+            },
+        )
+    }
+}
+
+impl ImNativeFileDialog<Option<DisplayPath>> {
+    /// Shows a dialog that let users to open one file using
+    /// [FileDialog::show_open_single_file()], returning the chosen path
+    /// along with a [DisplayPath::display] string for the ui.
+    pub fn open_single_file_display(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenSingleFile,
+            Some(options.clone()),
+            move || {
+                options
+                    .show_open_single_file()
+                    .map(|path| path.map(DisplayPath::from))
+            },
+        )
+    }
+
+    /// Shows a dialog that let users to open one directory using
+    /// [FileDialog::show_open_single_dir()], returning the chosen path
+    /// along with a [DisplayPath::display] string for the ui.
+    pub fn open_single_dir_display(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenSingleDir,
+            Some(options.clone()),
+            move || {
+                options
+                    .show_open_single_dir()
+                    .map(|path| path.map(DisplayPath::from))
+            },
+        )
+    }
+
+    /// Shows a dialog that let users to save one file using
+    /// [FileDialog::show_save_single_file()], returning the chosen path
+    /// along with a [DisplayPath::display] string for the ui.
+    pub fn show_save_single_file_display(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::SaveSingleFile,
+            Some(options.clone()),
+            move || options.show_save().map(|path| path.map(DisplayPath::from)),
+        )
+    }
+}
+
+impl ImNativeFileDialog<Vec<DisplayPath>> {
+    /// Shows a dialog that let users to open multiple files using
+    /// [FileDialog::show_open_multiple_file()], returning the chosen
+    /// paths along with a [DisplayPath::display] string for the ui.
+    pub fn show_open_multiple_file_display(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenMultipleFile,
+            Some(options.clone()),
+            move || {
+                options
+                    .show_open_multiple_file()
+                    .map(|paths| paths.into_iter().map(DisplayPath::from).collect())
+            },
+        )
+    }
+}
+
+impl ImNativeFileDialog<DirListing> {
+    /// Reads the entries of the directory at `path` in the dialog
+    /// thread without showing a dialog, polled using
+    /// [ImNativeFileDialog::check()] like the result of a dialog, for
+    /// applications which show their own picker. Any error reading the
+    /// directory is returned via [ImNativeFileDialog::check()].
+    pub fn list_dir(
+        &mut self,
+        path: impl Into<PathBuf>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let path = path.into();
+        self.worker
+            .spawn(DialogKind::ListDir, None, move || DirListing::read(path))
+    }
+}
+
+#[cfg(feature = "hash")]
+impl ImNativeFileDialog<Option<HashedPath>> {
+    /// Shows a dialog that let users to open one file using
+    /// [FileDialog::show_open_single_file()], returning the chosen path
+    /// along with the hash of its contents using `algorithm`, computed
+    /// in the dialog thread. [DialogEvent::Progress] is emitted while
+    /// hashing large files, and any error reading the file is returned
+    /// via [ImNativeFileDialog::check()].
+    pub fn open_single_file_hashed(
+        &mut self,
+        options: impl Into<DialogOptions>,
+        algorithm: HashAlgorithm,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenSingleFile,
+            Some(options.clone()),
+            move || -> Result<_, DialogError> {
+                match options.show_open_single_file()? {
+                    Some(path) => Ok(Some(HashedPath::new(path, algorithm)?)),
+                    None => Ok(None),
+                }
+            },
+        )
+    }
+}
+
+#[cfg(feature = "hash")]
+impl ImNativeFileDialog<Vec<HashedPath>> {
+    /// Shows a dialog that let users to open multiple files using
+    /// [FileDialog::show_open_multiple_file()], returning the chosen
+    /// paths along with the hashes of their contents using
+    /// `algorithm`, computed in the dialog thread. [DialogEvent::Progress]
+    /// is emitted while hashing large files, and any error reading the
+    /// files is returned via [ImNativeFileDialog::check()].
+    pub fn show_open_multiple_file_hashed(
+        &mut self,
+        options: impl Into<DialogOptions>,
+        algorithm: HashAlgorithm,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenMultipleFile,
+            Some(options.clone()),
+            move || -> Result<_, DialogError> {
+                options
+                    .show_open_multiple_file()?
+                    .into_iter()
+                    .map(|path| Ok(HashedPath::new(path, algorithm)?))
+                    .collect()
+            },
+        )
+    }
+}
+
+impl ImNativeFileDialog<Option<ThumbnailPath>> {
+    /// Shows a dialog that let users to open one file using
+    /// [FileDialog::show_open_single_file()], returning the chosen path
+    /// along with a thumbnail of the image, decoded in the dialog
+    /// thread and scaled down to fit within `max_size` pixels, see
+    /// [set_image_decoder()].
+    pub fn open_single_file_thumbnail(
+        &mut self,
+        options: impl Into<DialogOptions>,
+        max_size: u32,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenSingleFile,
+            Some(options.clone()),
+            move || {
+                options
+                    .show_open_single_file()
+                    .map(|path| path.map(|path| ThumbnailPath::new(path, max_size)))
+            },
+        )
+    }
+}
+
+impl ImNativeFileDialog<Vec<ThumbnailPath>> {
+    /// Shows a dialog that let users to open multiple files using
+    /// [FileDialog::show_open_multiple_file()], returning the chosen
+    /// paths along with thumbnails of the images, decoded in the dialog
+    /// thread and scaled down to fit within `max_size` pixels, see
+    /// [set_image_decoder()].
+    pub fn show_open_multiple_file_thumbnails(
+        &mut self,
+        options: impl Into<DialogOptions>,
+        max_size: u32,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenMultipleFile,
+            Some(options.clone()),
+            move || {
+                options.show_open_multiple_file().map(|paths| {
+                    paths
+                        .into_iter()
+                        .map(|path| ThumbnailPath::new(path, max_size))
+                        .collect()
+                })
+            },
+        )
+    }
+}
+
+impl ImNativeFileDialog<Option<ChoicePath>> {
+    /// Shows a dialog that let users to open one file using
+    /// [FileDialog::show_open_single_file()], returning the chosen path
+    /// along with the values of the controls added using
+    /// [DialogOptions::checkbox()] and [DialogOptions::combo()].
+    pub fn open_single_file_choices(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into()).with_choice_values();
+        self.worker.spawn(
+            DialogKind::OpenSingleFile,
+            Some(options.clone()),
+            move || {
+                options.show_open_single_file().map(|path| {
+                    path.map(|path| ChoicePath {
+                        path,
+                        choices: options.choice_values(),
+                    })
+                })
+            },
+        )
+    }
+
+    /// Shows a dialog that let users to save one file using
+    /// [FileDialog::show_save_single_file()], returning the chosen path
+    /// along with the values of the controls added using
+    /// [DialogOptions::checkbox()] and [DialogOptions::combo()].
+    pub fn show_save_single_file_choices(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into()).with_choice_values();
+        self.worker.spawn(
+            DialogKind::SaveSingleFile,
+            Some(options.clone()),
+            move || {
+                options.show_save().map(|path| {
+                    path.map(|path| ChoicePath {
+                        path,
+                        choices: options.choice_values(),
+                    })
+                })
+            },
+        )
+    }
+}
+
+impl ImNativeFileDialog<Option<FilterPath>> {
+    /// Shows a dialog that let users to open one file using
+    /// [FileDialog::show_open_single_file()], returning the chosen path
+    /// along with the filter which was selected.
+    pub fn open_single_file_filter(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into()).with_selected_filter();
+        self.worker.spawn(
+            DialogKind::OpenSingleFile,
+            Some(options.clone()),
+            move || {
+                options.show_open_single_file().map(|path| {
+                    path.map(|path| FilterPath {
+                        path,
+                        filter: options.selected_filter(),
+                    })
+                })
+            },
+        )
+    }
+
+    /// Shows a dialog that let users to save one file using
+    /// [FileDialog::show_save_single_file()], returning the chosen path
+    /// along with the filter which was selected, for example to choose
+    /// the format to save the file in.
+    pub fn show_save_single_file_filter(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into()).with_selected_filter();
+        self.worker.spawn(
+            DialogKind::SaveSingleFile,
+            Some(options.clone()),
+            move || {
+                options.show_save().map(|path| {
+                    path.map(|path| FilterPath {
+                        path,
+                        filter: options.selected_filter(),
+                    })
+                })
+            },
+        )
+    }
+}
+
+impl ImNativeFileDialog<Option<Bookmark>> {
+    /// Shows a dialog that let users to open one file using
+    /// [FileDialog::show_open_single_file()], and creates a
+    /// [Bookmark] for the chosen file, so the application can access
+    /// it again after a restart when sandboxed on macOS.
+    pub fn open_single_file_bookmark(
+        &mut self,
+        options: impl Into<DialogOptions>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let options = self.worker.options(options.into());
+        self.worker.spawn(
+            DialogKind::OpenSingleFile,
+            Some(options.clone()),
+            move || -> Result<_, DialogError> {
+                let path = options.show_open_single_file()?;
+                path.map(Bookmark::create).transpose()
+            },
+        )
+    }
+}
+
+/// Repeatedly shows [FileDialog::show_open_single_dir()], each time
+/// starting in the parent of the previously selected directory, until
+/// the user cancels.
+fn open_multiple_dir(options: &DialogOptions) -> Result<Vec<PathBuf>, native_dialog::Error> {
+    let mut options = options.clone();
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    loop {
+        match options.show_open_single_dir()? {
+            Some(dir) => {
+                options.location = dir.parent().map(Path::to_path_buf);
+                if !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+            None => return Ok(dirs),
+        }
+    }
+}
+
+impl<S: Send + 'static> ImNativeFileDialog<Option<S>> {
+    /// Shows the dialogs of `flow` one after another in the dialog
+    /// thread, so the whole flow is polled using
+    /// [ImNativeFileDialog::check()]. The result is the state after the
+    /// last step, or `None` if the user cancelled any of them. The
+    /// default options fill in the options of each dialog.
+    pub fn show_flow(&mut self, flow: DialogFlow<S>) -> Result<RequestToken, ImNativeDialogError> {
+        let defaults = self.worker.options(DialogOptions::new());
+        self.worker
+            .spawn_with(DialogKind::Flow, None, move |sender, callback| {
+                let result = flow.run(&defaults);
+                callback(&result);
+                let _ = sender.send(result);
+            })
+    }
+
+    /// Shows the dialog defined by `request` in the dialog thread, with
+    /// `options` filled in from the defaults of this object, see
+    /// [DialogRequest].
+    pub fn show_request<R>(
+        &mut self,
+        request: R,
+        options: DialogOptions,
+    ) -> Result<RequestToken, ImNativeDialogError>
+    where
+        R: DialogRequest<Output = S>,
+    {
+        let options = self.worker.options(options);
+        self.worker.spawn_map(
+            DialogKind::Request,
+            Some(options.clone()),
+            move || request.show(options).map(Requested::<R>),
+            |requested| requested.0,
+        )
     }
 }
 
 impl<T: Send + 'static + Default> ImNativeFileDialog<T> {
+    /// Returns a builder to set the configuration which applies to
+    /// every dialog shown by the new object in one place, including
+    /// default options and thread options.
+    pub fn builder() -> ImNativeFileDialogBuilder<T> {
+        ImNativeFileDialogBuilder::new()
+    }
+
     /// Set a callback to use for this dialog which will be called
     /// immediately upon dialog close in the dialog monitoring thread.
     pub fn with_callback<C>(&mut self, callback: C) -> &mut Self
     where
-        C: FnOnce(&Result<T, native_dialog::Error>) + Send + 'static
+        C: FnOnce(&Result<T, DialogError>) + Send + 'static,
+    {
+        self.worker.set_callback(Box::new(callback));
+        self
+    }
+
+    /// Set a token to use for the next dialog, which cancels the
+    /// dialog when [CancelToken::cancel()] is called. The dialog is
+    /// then abandoned, and [ImNativeFileDialog::check()] returns the
+    /// same empty result as when the user cancels it. [FileDialog]
+    /// can't close the native dialog, so it stays visible until the
+    /// user closes it, and its result is discarded.
+    pub fn with_cancel_token(&mut self, token: CancelToken) -> &mut Self {
+        self.worker.set_cancel_token(token);
+        self
+    }
+
+    /// Attach `context` to the next dialog, such as the document or
+    /// row the chosen path applies to, which is returned along with
+    /// the result by [ImNativeFileDialog::check_context()].
+    pub fn with_context<C: Send + 'static>(&mut self, context: C) -> &mut Self {
+        self.worker.set_context(Box::new(context));
+        self
+    }
+
+    /// Set the priority of the next dialog in the queue of its guard,
+    /// see [GuardPolicy::Queue], where dialogs with a higher priority
+    /// are shown first, such as a dialog to save before recovering from
+    /// a crash ahead of a queued file picker. The default is 0.
+    pub fn with_priority(&mut self, priority: i32) -> &mut Self {
+        self.worker.set_priority(priority);
+        self
+    }
+
+    /// Set a window after showing a dialog, such as 300ms, in which
+    /// the `show*()` methods do nothing and return `Ok` with the token
+    /// of the dialog which was shown, instead of showing another dialog
+    /// or returning [ImNativeDialogError::AlreadyOpen]. This coalesces
+    /// repeated requests from a double clicked button or key repeat
+    /// into a single dialog. [ImNativeFileDialog::show_if_closed()]
+    /// returns `false` for debounced requests.
+    pub fn with_debounce(&mut self, window: Duration) -> &mut Self {
+        self.worker.set_debounce(window);
+        self
+    }
+
+    /// Report dialogs shown by this object which stay open for longer
+    /// than `threshold`, such as 10 minutes, with
+    /// [DialogEvent::MaybeHung] and [ImNativeFileDialog::is_stale()],
+    /// so the application can warn users or offer to cancel when the
+    /// backend silently failed to show the dialog. This uses an extra
+    /// thread for each dialog.
+    pub fn with_watchdog(&mut self, threshold: Duration) -> &mut Self {
+        self.worker.set_watchdog(threshold);
+        self
+    }
+
+    /// Discard the results of dialogs shown by this object which
+    /// aren't checked in time after the dialog closed, see
+    /// [ExpiryPolicy].
+    pub fn with_expiry(&mut self, expiry: ExpiryPolicy) -> &mut Self {
+        self.worker.set_expiry(expiry);
+        self
+    }
+
+    /// Assign this object to the group called `name`, where only one
+    /// member can be open at a time, using `policy` for the others. For
+    /// example all the project file pickers in an application can
+    /// share a group, so they don't stack on top of each other.
+    pub fn with_group(&mut self, name: impl Into<String>, policy: GuardPolicy) -> &mut Self {
+        self.worker.set_group(guard::Group {
+            name: name.into(),
+            policy,
+        });
+        self
+    }
+
+    /// Watch the paths selected in every dialog shown by this object
+    /// from now on for changes, which are then returned by
+    /// [ImNativeFileDialog::check_changes()]. Each selection replaces
+    /// the previous watch, and cancelling keeps it. Available with the
+    /// `watch` feature.
+    #[cfg(feature = "watch")]
+    pub fn with_watcher(&mut self) -> &mut Self {
+        self.worker.enable_watcher();
+        self
+    }
+
+    /// Returns the next change to the paths selected in the last
+    /// dialog, see [ImNativeFileDialog::with_watcher()], or `None` if
+    /// there are no changes. Call this repeatedly every frame until it
+    /// returns `None`.
+    #[cfg(feature = "watch")]
+    pub fn check_changes(&self) -> Option<notify::Result<notify::Event>> {
+        self.worker
+            .watcher()?
+            .lock()
+            .expect("watcher lock poisoned")
+            .as_ref()?
+            .check()
+    }
+
+    /// Stop watching the paths selected in the last dialog, returning
+    /// the watcher so the application can keep it.
+    #[cfg(feature = "watch")]
+    pub fn take_watcher(&mut self) -> Option<PathWatcher> {
+        self.worker
+            .watcher()?
+            .lock()
+            .expect("watcher lock poisoned")
+            .take()
+    }
+
+    /// Send the event returned by `event` to the winit event loop of
+    /// `proxy` once the result of every dialog shown by this object is
+    /// ready, so applications using `ControlFlow::Wait` wake up to
+    /// check it. Available with the `winit` feature.
+    #[cfg(feature = "winit")]
+    pub fn with_event_loop_proxy<E, F>(
+        &mut self,
+        proxy: winit::event_loop::EventLoopProxy<E>,
+        event: F,
+    ) -> &mut Self
+    where
+        E: Send + 'static,
+        F: Fn() -> E + Send + Sync + 'static,
+    {
+        let proxy = std::sync::Mutex::new(proxy);
+        self.with_waker(move || {
+            // The event loop may have exited.
+            let _ = proxy
+                .lock()
+                .expect("proxy lock poisoned")
+                .send_event(event());
+        })
+    }
+
+    /// Set a function to call in the dialog thread once the result of
+    /// every dialog shown by this object has been sent, to wake the ui
+    /// so it calls [ImNativeFileDialog::check()], for example by
+    /// requesting an egui repaint or notifying a condition variable.
+    pub fn with_waker<W>(&mut self, waker: W) -> &mut Self
+    where
+        W: Fn() + Send + Sync + 'static,
     {
-        self.callback = Some(Box::new(callback));
+        self.worker.set_wake(Arc::new(waker));
+        self
+    }
+
+    /// Forward the result of every dialog shown by this object from now
+    /// on to `sender`, converted using `map` in the dialog thread, such
+    /// as into a message of the application's command bus, instead of
+    /// returning it from [ImNativeFileDialog::check()]. The dialog
+    /// closes once the result is forwarded. A dialog cancelled using
+    /// [ImNativeFileDialog::cancel()] still returns its empty result
+    /// from [ImNativeFileDialog::check()].
+    pub fn route_to<M, F>(&mut self, sender: Sender<M>, map: F) -> &mut Self
+    where
+        M: Send + 'static,
+        F: Fn(Result<T, DialogError>) -> M + Send + Sync + 'static,
+    {
+        self.worker.set_route(Arc::new(move |result| {
+            // The application stops receiving results by dropping the
+            // receiver.
+            let _ = sender.send(map(result));
+        }));
+        self
+    }
+
+    /// Set an observer to use for every dialog shown by this object,
+    /// in addition to the observer set using [set_observer()].
+    pub fn with_observer<O: DialogObserver + 'static>(&mut self, observer: O) -> &mut Self {
+        self.worker.set_observer(Arc::new(observer));
         self
     }
 
@@ -124,55 +1139,270 @@ impl<T: Send + 'static + Default> ImNativeFileDialog<T> {
     /// closure to customize the dialog and show the dialog. This
     /// closure runs in its own thread.
     pub fn show<
-        F: FnOnce(crossbeam_channel::Sender<Result<T, native_dialog::Error>>, FileDialog, Box<dyn FnOnce(&Result<T, native_dialog::Error>)>)
-            + Send
+        F: FnOnce(
+                Sender<Result<T, DialogError>>,
+                FileDialog,
+                Box<dyn FnOnce(&Result<T, DialogError>)>,
+            ) + Send
             + 'static,
     >(
         &mut self,
         run: F,
-    ) -> Result<(), ImNativeDialogError> {
-        if self.receiver.is_some() {
-            return Err(ImNativeDialogError::AlreadyOpen);
-        }
-
-        let (sender, receiver) = crossbeam_channel::bounded(1);
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        self.worker
+            .spawn_with(DialogKind::Custom, None, move |sender, callback| {
+                let dialog = FileDialog::new();
+                run(sender, dialog, callback)
+            })
+    }
 
-        let callback = self.callback.take().unwrap_or_else(|| Box::new(|_| {}));
-        std::thread::spawn(move || {
-            let dialog = FileDialog::new();
-            run(sender, dialog, callback)
-        });
+    /// Calls `show` to show a dialog if this dialog isn't already open,
+    /// returning `true` if a dialog was shown, or `false` instead of
+    /// [ImNativeDialogError::AlreadyOpen] or
+    /// [ImNativeDialogError::Busy] or
+    /// [ImNativeDialogError::RateLimited], or when the request is
+    /// debounced, see [ImNativeFileDialog::with_debounce()]. This suits
+    /// button handlers where clicking again while the dialog is open is
+    /// expected, for example
+    /// `dialog.show_if_closed(|dialog| dialog.open_single_file(None))`.
+    /// Other errors are returned.
+    pub fn show_if_closed<F, R>(&mut self, show: F) -> Result<bool, ImNativeDialogError>
+    where
+        F: FnOnce(&mut Self) -> Result<R, ImNativeDialogError>,
+    {
+        self.worker.take_coalesced();
+        match show(self) {
+            Ok(_) => Ok(!self.worker.take_coalesced()),
+            Err(
+                ImNativeDialogError::AlreadyOpen
+                | ImNativeDialogError::Busy
+                | ImNativeDialogError::RateLimited,
+            ) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
 
-        self.receiver = Some(receiver);
+    /// Calls `show` to show a dialog, and blocks until it closes,
+    /// returning its result. While waiting `pump` is called repeatedly,
+    /// for example to draw a "waiting" frame or keep the window's event
+    /// loop responsive, for flows which need modal behaviour without
+    /// freezing the window, for example
+    /// `dialog.show_modal_with(|dialog| dialog.open_single_file(None), || redraw())`.
+    /// Returns the default result if no dialog was shown, because
+    /// `show` was debounced.
+    pub fn show_modal_with<F, R, P>(
+        &mut self,
+        show: F,
+        mut pump: P,
+    ) -> Result<Result<T, DialogError>, ImNativeDialogError>
+    where
+        F: FnOnce(&mut Self) -> Result<R, ImNativeDialogError>,
+        P: FnMut(),
+    {
+        show(self)?;
+        loop {
+            if let Some(result) = self.check() {
+                return Ok(result);
+            }
+            if !self.is_open() {
+                return Ok(Ok(T::default()));
+            }
+            pump();
+            std::thread::sleep(MODAL_POLL_INTERVAL);
+        }
+    }
 
-        Ok(())
+    /// Delivers `result` through [ImNativeFileDialog::check()] as if
+    /// the user had selected it, for example the paths of files dropped
+    /// onto the window, so the application handles both in one place.
+    /// If a dialog is open, `result` is returned first and the dialog's
+    /// own result is returned by a later call.
+    pub fn inject_result(&mut self, result: T) -> RequestToken {
+        self.worker.inject(result)
     }
 
     /// Check if the dialog is complete. If it is complete it will
     /// return `Some` with the result of the dialog, otherwise will
     /// return `None`. This will update the status of
     /// [ImNativeFileDialog::is_open()].
-    pub fn check(&mut self) -> Option<Result<T, native_dialog::Error>> {
-        match self.receiver.take() {
-            Some(receiver) => match receiver.try_recv() {
-                Ok(result) => Some(result),
-                Err(crossbeam_channel::TryRecvError::Disconnected) => {
-                    log::warn!("OpenDialog channel disconnected");
-                    Some(Ok(T::default()))
-                }
-                Err(crossbeam_channel::TryRecvError::Empty) => {
-                    self.receiver = Some(receiver);
-                    None
-                }
-            },
-            None => None,
-        }
+    pub fn check(&mut self) -> Option<Result<T, DialogError>> {
+        self.worker.check()
+    }
+
+    /// Like [ImNativeFileDialog::check()], but tells a dialog the user
+    /// cancelled apart from one which failed or whose thread exited
+    /// without a result, so the ui can tell users their selection was
+    /// cancelled instead of silently doing nothing.
+    pub fn check_result(&mut self) -> Option<DialogResult<T>> {
+        self.worker.check_result()
+    }
+
+    /// Returns the latest progress of the work done in the dialog
+    /// thread after the dialog closed, such as hashing the chosen files
+    /// using `ImNativeFileDialog::open_single_file_hashed()`, or `None`
+    /// if there has been none since the previous call. Call this every
+    /// frame alongside [ImNativeFileDialog::check()] to draw a progress
+    /// bar until the result arrives.
+    pub fn check_progress(&self) -> Option<Progress> {
+        self.worker.check_progress()
+    }
+
+    /// Like [ImNativeFileDialog::check()], but also returns the
+    /// [Request] the result belongs to, with the [RequestToken]
+    /// returned by the `show*()` method and the options used to show
+    /// the dialog. Use this to discard results for requests which no
+    /// longer apply, for example because the ui changed while the
+    /// dialog was open.
+    pub fn check_request(&mut self) -> Option<(Request, Result<T, DialogError>)> {
+        self.worker.check_request()
+    }
+
+    /// Like [ImNativeFileDialog::check()], but also returns the context
+    /// attached to the dialog using
+    /// [ImNativeFileDialog::with_context()], or `None` if there was no
+    /// context or it isn't a `C`.
+    pub fn check_context<C: 'static>(&mut self) -> Option<(Option<C>, Result<T, DialogError>)> {
+        self.worker
+            .check_context()
+            .map(|(_request, context, result)| {
+                let context = context
+                    .and_then(|context| context.downcast().ok())
+                    .map(|context| *context);
+                (context, result)
+            })
     }
 
     /// Returns `true` if the dialog is currently open, otherwise
     /// returns `false`. Requires a previous call of
     /// [ImNativeFileDialog::check()] to update the current status.
     pub fn is_open(&self) -> bool {
-        self.receiver.is_some()
+        self.worker.is_open()
+    }
+
+    /// Returns the kind of the open or queued dialog, or `None` if no
+    /// dialog is open or its result has been checked, so generic ui
+    /// such as a status bar can describe what the user is being asked.
+    pub fn kind(&self) -> Option<DialogKind> {
+        self.worker.kind()
+    }
+
+    /// Returns a short description of the open or queued dialog for a
+    /// status bar or tooltip, such as "Waiting for you to choose a
+    /// file… (12s)", or `None` if no dialog is open. The description is
+    /// translated using the [Translator] set using [set_translator()].
+    pub fn status(&self) -> Option<String> {
+        self.worker.status()
+    }
+
+    /// Returns the options the open or queued dialog was shown with,
+    /// after filling in the defaults, or `None` if no dialog is open.
+    /// They can be inspected by converting them to an
+    /// [OptionsSnapshot], or cloned to show the dialog again with the
+    /// same options. Once the result has been checked, the options are
+    /// returned along with it by [ImNativeFileDialog::check_request()].
+    pub fn pending_options(&self) -> Option<&DialogOptions> {
+        self.worker.pending_options()
+    }
+
+    /// Moves the currently open dialog into a [DialogHandle] which is
+    /// polled separately, so this object can show another dialog
+    /// while it is open, for example
+    /// `dialog.open_single_file(None)?; let handle = dialog.detach();`.
+    /// Returns `None` if no dialog is open. The options set on this
+    /// object, such as [ImNativeFileDialog::with_observer()], keep
+    /// applying to the dialogs it shows next.
+    pub fn detach(&mut self) -> Option<DialogHandle<T>> {
+        self.worker.detach().map(DialogHandle::new)
+    }
+
+    /// Cancels the open or queued dialog, so the next
+    /// [ImNativeFileDialog::check()] returns the same empty result as
+    /// when the user cancels it, see
+    /// [ImNativeFileDialog::with_cancel_token()]. Returns `true` if a
+    /// dialog was open.
+    pub fn cancel(&mut self) -> bool {
+        self.worker.cancel()
+    }
+
+    /// Moves the queued dialog to the place in the queue for
+    /// `priority`, see [ImNativeFileDialog::with_priority()], returning
+    /// `false` if no dialog is queued. A queued dialog is removed from
+    /// the queue using [ImNativeFileDialog::cancel()].
+    pub fn reprioritize(&mut self, priority: i32) -> bool {
+        self.worker.reprioritize(priority)
+    }
+
+    /// Discards the open or queued dialog and any results not yet
+    /// returned by [ImNativeFileDialog::check()], returning this object
+    /// to the closed state, for example when the document the dialog
+    /// was shown for is closed. Returns `true` if anything was
+    /// discarded. The native dialog stays visible until the user closes
+    /// it, and its result is dropped.
+    pub fn reset(&mut self) -> bool {
+        self.worker.reset()
+    }
+
+    /// Returns when the currently open dialog was shown, or `None` if
+    /// the dialog is not open. Requires a previous call of
+    /// [ImNativeFileDialog::check()] to update the current status.
+    pub fn opened_at(&self) -> Option<Instant> {
+        self.worker.opened_at()
+    }
+
+    /// Returns the [DialogId] of the currently open dialog, as sent in
+    /// the [DialogEvent]s for it, or `None` if the dialog is not open.
+    /// Requires a previous call of [ImNativeFileDialog::check()] to
+    /// update the current status.
+    pub fn id(&self) -> Option<DialogId> {
+        self.worker.id()
+    }
+
+    /// Returns how long the currently open dialog has been open, or
+    /// `None` if the dialog is not open. Requires a previous call of
+    /// [ImNativeFileDialog::check()] to update the current status.
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.worker.elapsed()
+    }
+
+    /// Returns the [DialogState] of this dialog, including the kind of
+    /// dialog requested and when it was shown. Unlike
+    /// [ImNativeFileDialog::is_open()], this doesn't require a previous
+    /// call of [ImNativeFileDialog::check()], so a completed dialog is
+    /// reported as [DialogState::Completed] until its result is
+    /// checked.
+    pub fn state(&self) -> DialogState {
+        self.worker.state()
+    }
+
+    /// Returns `true` if the currently open dialog has been open for
+    /// longer than the threshold set using
+    /// [ImNativeFileDialog::with_watchdog()]. Requires a previous call
+    /// of [ImNativeFileDialog::check()] to update the current status.
+    pub fn is_stale(&self) -> bool {
+        self.worker.is_stale()
+    }
+}
+
+impl<T: Clone + Send + 'static + Default> ImNativeFileDialog<T> {
+    /// Returns an additional receiver which gets a clone of the result
+    /// of every successful dialog shown by this object from now on, so
+    /// that more than one part of the application can react to the
+    /// user's selection. Errors are only returned via
+    /// [ImNativeFileDialog::check()]. Dropping the receiver
+    /// unsubscribes it.
+    pub fn subscribe(&mut self) -> Receiver<T> {
+        let (sender, receiver) = channel::unbounded();
+        self.worker.add_subscriber(Box::new(move |value: &T| {
+            sender.send(value.clone()).is_ok()
+        }));
+        receiver
     }
 }
+
+/// Serializes the tests which change the state shared by every dialog
+/// in the process, such as the rate limit, the guards and the clock.
+#[cfg(test)]
+pub(crate) fn serial_test() -> std::sync::MutexGuard<'static, ()> {
+    static SERIAL: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    SERIAL.lock().unwrap_or_else(|error| error.into_inner())
+}
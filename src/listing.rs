@@ -0,0 +1,118 @@
+use std::{io, path::PathBuf, time::SystemTime};
+
+use crate::worker::Selection;
+#[cfg(feature = "replay")]
+use crate::{DialogError, DialogOutcome};
+
+/// What a [DirEntryInfo] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EntryType {
+    File,
+    Dir,
+    /// A symbolic link, which isn't followed.
+    Symlink,
+    /// Anything else, such as a socket or device.
+    Other,
+}
+
+/// An entry of a [DirListing].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntryInfo {
+    pub path: PathBuf,
+    /// The file name, converted lossily for display.
+    pub name: String,
+    pub entry_type: EntryType,
+    /// The size in bytes, which is 0 for directories.
+    pub size: u64,
+    /// When the entry was last modified, or `None` if the platform or
+    /// file system doesn't record it.
+    pub modified: Option<SystemTime>,
+}
+
+impl DirEntryInfo {
+    /// Reads the metadata of `path` without following symbolic links.
+    /// Entries whose metadata can't be read are [EntryType::Other].
+    fn new(path: PathBuf) -> Self {
+        let metadata = path.symlink_metadata().ok();
+        let entry_type = match &metadata {
+            Some(metadata) if metadata.is_dir() => EntryType::Dir,
+            Some(metadata) if metadata.is_file() => EntryType::File,
+            Some(metadata) if metadata.file_type().is_symlink() => EntryType::Symlink,
+            _ => EntryType::Other,
+        };
+        let size = match entry_type {
+            EntryType::Dir => 0,
+            _ => metadata.as_ref().map_or(0, |metadata| metadata.len()),
+        };
+        Self {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            entry_type,
+            size,
+            modified: metadata.and_then(|metadata| metadata.modified().ok()),
+            path,
+        }
+    }
+}
+
+/// The entries of a directory read in the dialog thread using
+/// [crate::ImNativeFileDialog::list_dir()], sorted with directories
+/// first and then by name, so applications can build their own pickers
+/// without blocking the ui on [std::fs::read_dir()].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirListing {
+    pub path: PathBuf,
+    pub entries: Vec<DirEntryInfo>,
+}
+
+impl DirListing {
+    pub(crate) fn read(path: PathBuf) -> io::Result<Self> {
+        let mut entries = std::fs::read_dir(&path)?
+            .map(|entry| entry.map(|entry| DirEntryInfo::new(entry.path())))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by(|a, b| {
+            (a.entry_type != EntryType::Dir)
+                .cmp(&(b.entry_type != EntryType::Dir))
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        Ok(Self { path, entries })
+    }
+}
+
+impl Selection for DirListing {
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        self.entries
+            .iter()
+            .map(|entry| entry.path.clone())
+            .collect()
+    }
+
+    /// The entries are recorded as the paths of the outcome.
+    #[cfg(feature = "replay")]
+    fn replay(outcome: &DialogOutcome) -> Option<Result<Self, DialogError>> {
+        if let Some(error) = &outcome.error {
+            return Some(Err(DialogError::Implementation(error.clone())));
+        }
+        Some(Ok(Self {
+            path: outcome
+                .paths
+                .first()
+                .and_then(|path| path.parent())
+                .map(|parent| parent.to_path_buf())
+                .unwrap_or_default(),
+            entries: outcome
+                .paths
+                .iter()
+                .cloned()
+                .map(DirEntryInfo::new)
+                .collect(),
+        }))
+    }
+}
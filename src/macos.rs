@@ -0,0 +1,357 @@
+//! macOS specific options for the file dialogs, which are shown using
+//! `NSOpenPanel` and `NSSavePanel` directly when any of them are set,
+//! because [native_dialog] doesn't expose them.
+
+use std::{ffi::CStr, os::raw::c_char, path::PathBuf};
+
+use native_dialog::Error;
+use objc::{
+    class, msg_send,
+    runtime::{Object, BOOL, NO, YES},
+    sel, sel_impl,
+};
+use raw_window_handle::RawWindowHandle;
+
+use crate::{
+    choices::Choice,
+    options::{OwnerHandle, Prompt},
+    ChoiceValue, DialogOptions, ScreenRect, Theme,
+};
+
+pub(crate) type Id = *mut Object;
+
+const NIL: Id = std::ptr::null_mut();
+const UTF8_STRING_ENCODING: usize = 4;
+const MODAL_RESPONSE_OK: isize = 1;
+const ACTIVATION_POLICY_ACCESSORY: isize = 1;
+const ACTIVATION_POLICY_PROHIBITED: isize = 2;
+const BUTTON_TYPE_SWITCH: usize = 3;
+const CONTROL_STATE_ON: isize = 1;
+const ORIENTATION_VERTICAL: isize = 1;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Size {
+    width: f64,
+    height: f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Rect {
+    origin: Point,
+    size: Size,
+}
+
+/// The options set using [DialogOptionsExtMacOs].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MacOsOptions {
+    message: Option<String>,
+    prompt: Option<String>,
+    name_field_label: Option<String>,
+}
+
+/// macOS specific [DialogOptions], available on macOS only. Setting any
+/// of them shows the dialog using `NSOpenPanel` or `NSSavePanel`
+/// directly.
+pub trait DialogOptionsExtMacOs {
+    /// Sets the message shown at the top of the panel, such as "Choose
+    /// where to export the report".
+    fn message(self, message: impl Into<String>) -> Self;
+
+    /// Sets the label of the default button, such as "Export", in place
+    /// of [DialogOptions::accept_label()].
+    fn prompt(self, prompt: impl Into<String>) -> Self;
+
+    /// Sets the label of the file name field of save panels, such as
+    /// "Export As:".
+    fn name_field_label(self, label: impl Into<String>) -> Self;
+}
+
+impl DialogOptionsExtMacOs for DialogOptions {
+    fn message(mut self, message: impl Into<String>) -> Self {
+        self.macos.get_or_insert_with(MacOsOptions::default).message = Some(message.into());
+        self
+    }
+
+    fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.macos.get_or_insert_with(MacOsOptions::default).prompt = Some(prompt.into());
+        self
+    }
+
+    fn name_field_label(mut self, label: impl Into<String>) -> Self {
+        self.macos
+            .get_or_insert_with(MacOsOptions::default)
+            .name_field_label = Some(label.into());
+        self
+    }
+}
+
+/// Calls `f` inside an autorelease pool.
+pub(crate) fn autoreleased<R>(f: impl FnOnce() -> R) -> R {
+    unsafe {
+        let pool: Id = msg_send![class!(NSAutoreleasePool), new];
+        let result = f();
+        let _: () = msg_send![pool, drain];
+        result
+    }
+}
+
+/// Returns an autoreleased `NSString` with the contents of `value`.
+pub(crate) unsafe fn string(value: &str) -> Id {
+    let string: Id = msg_send![class!(NSString), alloc];
+    let string: Id = msg_send![string,
+        initWithBytes: value.as_ptr()
+        length: value.len()
+        encoding: UTF8_STRING_ENCODING];
+    msg_send![string, autorelease]
+}
+
+unsafe fn url_path(url: Id) -> PathBuf {
+    let url: Id = msg_send![url, absoluteURL];
+    let path: Id = msg_send![url, path];
+    let bytes: *const c_char = msg_send![path, UTF8String];
+    PathBuf::from(CStr::from_ptr(bytes).to_string_lossy().into_owned())
+}
+
+fn objc_bool(value: bool) -> BOOL {
+    if value {
+        YES
+    } else {
+        NO
+    }
+}
+
+/// Returns an autoreleased `NSArray` holding `objects`.
+unsafe fn array(objects: &[Id]) -> Id {
+    msg_send![class!(NSArray), arrayWithObjects: objects.as_ptr() count: objects.len()]
+}
+
+/// Returns an autoreleased accessory view holding a control for each
+/// of `choices`, along with the controls.
+unsafe fn accessory_view(choices: &[Choice]) -> (Id, Vec<Id>) {
+    let mut rows = Vec::with_capacity(choices.len());
+    let mut controls = Vec::with_capacity(choices.len());
+    for choice in choices {
+        match choice {
+            Choice::Checkbox { label, checked } => {
+                let button: Id = msg_send![class!(NSButton), new];
+                let button: Id = msg_send![button, autorelease];
+                let _: () = msg_send![button, setButtonType: BUTTON_TYPE_SWITCH];
+                let _: () = msg_send![button, setTitle: string(label)];
+                let _: () = msg_send![button, setState: *checked as isize];
+                rows.push(button);
+                controls.push(button);
+            }
+            Choice::Combo {
+                label,
+                items,
+                selected,
+            } => {
+                let popup: Id = msg_send![class!(NSPopUpButton), new];
+                let popup: Id = msg_send![popup, autorelease];
+                for item in items {
+                    let _: () = msg_send![popup, addItemWithTitle: string(item)];
+                }
+                let _: () = msg_send![popup, selectItemAtIndex: *selected as isize];
+                let label: Id = msg_send![class!(NSTextField), labelWithString: string(label)];
+                let row: Id =
+                    msg_send![class!(NSStackView), stackViewWithViews: array(&[label, popup])];
+                rows.push(row);
+                controls.push(popup);
+            }
+        }
+    }
+    let view: Id = msg_send![class!(NSStackView), stackViewWithViews: array(&rows)];
+    let _: () = msg_send![view, setOrientation: ORIENTATION_VERTICAL];
+    (view, controls)
+}
+
+/// Reads the values of the `controls` returned by [accessory_view()].
+unsafe fn choice_values(choices: &[Choice], controls: &[Id]) -> Vec<ChoiceValue> {
+    choices
+        .iter()
+        .zip(controls)
+        .map(|(choice, &control)| match choice {
+            Choice::Checkbox { .. } => {
+                let state: isize = msg_send![control, state];
+                ChoiceValue::Checked(state == CONTROL_STATE_ON)
+            }
+            Choice::Combo { .. } => {
+                let index: isize = msg_send![control, indexOfSelectedItem];
+                ChoiceValue::Selected(index.max(0) as usize)
+            }
+        })
+        .collect()
+}
+
+/// Runs `panel`, as a sheet of `owner` if it isn't nil, and returns
+/// the response.
+unsafe fn run(panel: Id, owner: Id) -> isize {
+    if !owner.is_null() {
+        let _: () = msg_send![owner, beginSheet: panel completionHandler: NIL];
+        let response: isize = msg_send![panel, runModal];
+        let _: () = msg_send![owner, endSheet: panel returnCode: response];
+        return response;
+    }
+
+    // Applications without a user interface, such as command line
+    // tools, can't show panels.
+    let app: Id = msg_send![class!(NSApplication), sharedApplication];
+    let policy: isize = msg_send![app, activationPolicy];
+    if policy == ACTIVATION_POLICY_PROHIBITED {
+        let _: () = msg_send![app, setActivationPolicy: ACTIVATION_POLICY_ACCESSORY];
+    }
+    let response: isize = msg_send![panel, runModal];
+    if policy == ACTIVATION_POLICY_PROHIBITED {
+        let _: () = msg_send![app, setActivationPolicy: policy];
+    }
+    response
+}
+
+/// Centers `panel` on `rect`, whose origin is the top left of the
+/// primary screen, while the origin of AppKit is its bottom left.
+unsafe fn center_on(panel: Id, rect: ScreenRect) {
+    let screens: Id = msg_send![class!(NSScreen), screens];
+    let count: usize = msg_send![screens, count];
+    if count == 0 {
+        return;
+    }
+    let primary: Id = msg_send![screens, objectAtIndex: 0usize];
+    let screen: Rect = msg_send![primary, frame];
+    let frame: Rect = msg_send![panel, frame];
+    let (center_x, center_y) = rect.center();
+    let origin = Point {
+        x: center_x as f64 - frame.size.width / 2.0,
+        y: screen.size.height - center_y as f64 - frame.size.height / 2.0,
+    };
+    let _: () = msg_send![panel, setFrameOrigin: origin];
+}
+
+/// Shows the dialog described by `options` and `prompt` using
+/// `NSOpenPanel` or `NSSavePanel`, returning the chosen paths, which
+/// are empty if the user cancelled the dialog.
+pub(crate) fn show(
+    options: &DialogOptions,
+    prompt: Prompt,
+    multiple: bool,
+) -> Result<Vec<PathBuf>, Error> {
+    let default_macos = MacOsOptions::default();
+    let macos = options.macos.as_ref().unwrap_or(&default_macos);
+    // Safety: the panel and strings are autoreleased objects, which
+    // stay valid until the pool is drained.
+    autoreleased(|| unsafe {
+        let panel: Id = match prompt {
+            Prompt::Save => msg_send![class!(NSSavePanel), savePanel],
+            Prompt::File | Prompt::Dir => msg_send![class!(NSOpenPanel), openPanel],
+        };
+        if panel.is_null() {
+            return Err(Error::ImplementationError(
+                "failed to create the panel".to_owned(),
+            ));
+        }
+
+        if let Some(title) = &options.title {
+            let _: () = msg_send![panel, setTitle: string(title)];
+        }
+        if let Some(message) = &macos.message {
+            let _: () = msg_send![panel, setMessage: string(message)];
+        }
+        if let Some(prompt) = macos.prompt.as_ref().or(options.accept_label.as_ref()) {
+            let _: () = msg_send![panel, setPrompt: string(prompt)];
+        }
+        if let Some(label) = &macos.name_field_label {
+            let _: () = msg_send![panel, setNameFieldLabel: string(label)];
+        }
+        if options.show_hidden {
+            let _: () = msg_send![panel, setShowsHiddenFiles: YES];
+        }
+        if let Some(can_create_dirs) = options.can_create_dirs {
+            let _: () = msg_send![panel, setCanCreateDirectories: objc_bool(can_create_dirs)];
+        }
+        if prompt != Prompt::Save {
+            let _: () = msg_send![panel, setCanChooseFiles: objc_bool(prompt == Prompt::File)];
+            let _: () = msg_send![panel, setCanChooseDirectories: objc_bool(prompt == Prompt::Dir)];
+            let _: () = msg_send![panel, setAllowsMultipleSelection: objc_bool(multiple)];
+        }
+        // Open panels select the file when the directory URL points at
+        // it, while save panels fill in the name field.
+        let location = match (options.preselect.first(), prompt) {
+            (Some(path), Prompt::File | Prompt::Dir) => Some(path.as_path()),
+            (Some(path), Prompt::Save) => path.parent(),
+            (None, _) => options.location.as_deref(),
+        };
+        if let Some(location) = location.and_then(|path| path.to_str()) {
+            let url: Id = msg_send![class!(NSURL), fileURLWithPath: string(location)];
+            let _: () = msg_send![panel, setDirectoryURL: url];
+        }
+        if let (Some((_dir, name)), Prompt::Save) = (options.preselection(false), prompt) {
+            let _: () = msg_send![panel, setNameFieldStringValue: string(&name)];
+        }
+        if prompt != Prompt::Dir && !options.filters.is_empty() {
+            let extensions: Id = msg_send![class!(NSMutableArray), array];
+            for extension in options.filters.iter().flat_map(|filter| &filter.extensions) {
+                let _: () = msg_send![extensions, addObject: string(extension)];
+            }
+            let _: () = msg_send![panel, setAllowedFileTypes: extensions];
+        }
+
+        let mut controls = Vec::new();
+        if !options.choices.is_empty() {
+            let (view, choice_controls) = accessory_view(&options.choices);
+            let _: () = msg_send![panel, setAccessoryView: view];
+            if prompt != Prompt::Save {
+                let _: () = msg_send![panel, setAccessoryViewDisclosed: YES];
+            }
+            controls = choice_controls;
+        }
+
+        if let Some(theme) = options.theme {
+            let name = match theme {
+                Theme::Light => "NSAppearanceNameAqua",
+                Theme::Dark => "NSAppearanceNameDarkAqua",
+            };
+            let appearance: Id = msg_send![class!(NSAppearance), appearanceNamed: string(name)];
+            let _: () = msg_send![panel, setAppearance: appearance];
+        }
+
+        let owner = match options.owner {
+            Some(OwnerHandle(RawWindowHandle::AppKit(handle))) => handle.ns_window as Id,
+            _ => NIL,
+        };
+        // Sheets are always attached to their owner.
+        if let (Some(rect), true) = (options.near, owner.is_null()) {
+            center_on(panel, rect);
+        }
+        if run(panel, owner) != MODAL_RESPONSE_OK {
+            return Ok(Vec::new());
+        }
+        if !options.choices.is_empty() {
+            options.store_choices(choice_values(&options.choices, &controls));
+        }
+
+        match prompt {
+            Prompt::Save => {
+                let url: Id = msg_send![panel, URL];
+                Ok(vec![url_path(url)])
+            }
+            Prompt::File | Prompt::Dir => {
+                let urls: Id = msg_send![panel, URLs];
+                let count: usize = msg_send![urls, count];
+                Ok((0..count)
+                    .map(|index| {
+                        let url: Id = msg_send![urls, objectAtIndex: index];
+                        url_path(url)
+                    })
+                    .collect())
+            }
+        }
+    })
+}
@@ -0,0 +1,215 @@
+use std::{collections::HashMap, hash::Hash, sync::Arc, time::Duration};
+
+use crate::{
+    shutdown,
+    worker::{Route, Wake},
+    DialogError, DialogState, DiscardedRequest, ImNativeFileDialog, Sender, ShutdownReport,
+};
+
+/// Creates the route of the dialog for a key, see
+/// [DialogManager::route_to()].
+type KeyedRoute<K, T> = Arc<dyn Fn(&K) -> Route<T> + Send + Sync>;
+
+/// A set of [ImNativeFileDialog]s identified by a key, for
+/// applications which show several dialogs concurrently, such as one
+/// per document. The results of all the dialogs are polled together
+/// using [DialogManager::check()].
+///
+/// With the `futures` feature, the manager is also a
+/// `futures::Stream` of `(key, result)` items, which ends when none of
+/// the dialogs are open.
+pub struct DialogManager<K, T> {
+    dialogs: HashMap<K, ImNativeFileDialog<T>>,
+    wake: Option<Wake>,
+    route: Option<KeyedRoute<K, T>>,
+}
+
+/// The results returned by [DialogManager::poll_ready()].
+#[derive(Debug)]
+pub struct ReadyBatch<K, T> {
+    /// The key and result of each dialog checked.
+    pub results: Vec<(K, Result<T, DialogError>)>,
+    /// How many other dialogs have completed, whose results are left
+    /// for the next call.
+    pub remaining: usize,
+}
+
+impl<K, T> Default for DialogManager<K, T> {
+    fn default() -> Self {
+        Self {
+            dialogs: HashMap::new(),
+            wake: None,
+            route: None,
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, T: Send + 'static + Default> DialogManager<K, T> {
+    /// Create an empty manager.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a function to call in the dialog thread once the result of
+    /// any of the dialogs has been sent, see
+    /// [ImNativeFileDialog::with_waker()].
+    pub fn with_waker<W>(&mut self, waker: W) -> &mut Self
+    where
+        W: Fn() + Send + Sync + 'static,
+    {
+        let wake: Wake = Arc::new(waker);
+        for dialog in self.dialogs.values_mut() {
+            dialog.worker.set_wake(wake.clone());
+        }
+        self.wake = Some(wake);
+        self
+    }
+
+    /// Forward the results of all the dialogs from now on to `sender`,
+    /// converted using `map` along with the key of the dialog, see
+    /// [ImNativeFileDialog::route_to()]. Their results are no longer
+    /// returned by [DialogManager::check()].
+    pub fn route_to<M, F>(&mut self, sender: Sender<M>, map: F) -> &mut Self
+    where
+        K: Send + Sync + 'static,
+        M: Send + 'static,
+        F: Fn(K, Result<T, DialogError>) -> M + Send + Sync + 'static,
+    {
+        let map = Arc::new(map);
+        let route: KeyedRoute<K, T> = Arc::new(move |key: &K| -> Route<T> {
+            let (key, sender, map) = (key.clone(), sender.clone(), map.clone());
+            Arc::new(move |result| {
+                let _ = sender.send(map(key.clone(), result));
+            })
+        });
+        for (key, dialog) in &mut self.dialogs {
+            dialog.worker.set_route(route(key));
+        }
+        self.route = Some(route);
+        self
+    }
+
+    /// The dialog for `key`, created if it doesn't exist yet.
+    pub fn dialog(&mut self, key: K) -> &mut ImNativeFileDialog<T> {
+        let (wake, route) = (&self.wake, &self.route);
+        self.dialogs.entry(key).or_insert_with_key(|key| {
+            let mut dialog = ImNativeFileDialog::default();
+            if let Some(wake) = wake {
+                dialog.worker.set_wake(wake.clone());
+            }
+            if let Some(route) = route {
+                dialog.worker.set_route(route(key));
+            }
+            dialog
+        })
+    }
+
+    /// The dialog for `key`, if it exists.
+    pub fn get(&self, key: &K) -> Option<&ImNativeFileDialog<T>> {
+        self.dialogs.get(key)
+    }
+
+    /// Remove the dialog for `key`. If the dialog is open its result
+    /// is discarded.
+    pub fn remove(&mut self, key: &K) -> Option<ImNativeFileDialog<T>> {
+        self.dialogs.remove(key)
+    }
+
+    /// Returns `true` if any of the dialogs is currently open. Requires
+    /// a previous call of [DialogManager::check()] to update the
+    /// current status.
+    pub fn is_open(&self) -> bool {
+        self.dialogs.values().any(ImNativeFileDialog::is_open)
+    }
+
+    /// Check all the dialogs, returning the key and result of each
+    /// dialog which has completed since the previous call.
+    pub fn check(&mut self) -> Vec<(K, Result<T, DialogError>)> {
+        self.dialogs
+            .iter_mut()
+            .filter_map(|(key, dialog)| dialog.check().map(|result| (key.clone(), result)))
+            .collect()
+    }
+
+    /// Like [DialogManager::check()], returning at most `max_items`
+    /// results, along with how many other dialogs have completed and
+    /// are left for the next call, so handling the results of many
+    /// dialogs fits in the time budget of a frame.
+    pub fn poll_ready(&mut self, max_items: usize) -> ReadyBatch<K, T> {
+        let mut results = Vec::new();
+        let mut remaining = 0;
+        for (key, dialog) in &mut self.dialogs {
+            if results.len() < max_items {
+                if let Some(result) = dialog.check() {
+                    results.push((key.clone(), result));
+                }
+            } else if matches!(dialog.state(), DialogState::Completed { .. }) {
+                remaining += 1;
+            }
+        }
+        ReadyBatch { results, remaining }
+    }
+
+    /// Discards the queued and open dialogs and the results not yet
+    /// checked, like [ImNativeFileDialog::reset()], and waits up to
+    /// `timeout` for the dialog threads to finish, like
+    /// [crate::shutdown()] for only the dialogs of this manager.
+    pub fn shutdown(&mut self, timeout: Duration) -> ShutdownReport {
+        let mut discarded = Vec::new();
+        for dialog in self.dialogs.values_mut() {
+            let (token, kind, shown) = match dialog.state() {
+                DialogState::Queued { kind, token } => (token, kind, false),
+                DialogState::Pending { kind, token, .. }
+                | DialogState::Completed { kind, token, .. } => (token, kind, true),
+                _ => continue,
+            };
+            shutdown::abandon(token);
+            dialog.reset();
+            discarded.push(DiscardedRequest {
+                token,
+                kind,
+                shown,
+                finished: true,
+            });
+        }
+        let tokens: Vec<_> = discarded.iter().map(|request| request.token).collect();
+        let unfinished = shutdown::wait(&tokens, timeout);
+        for request in &mut discarded {
+            request.finished = !unfinished.contains(&request.token);
+        }
+        ShutdownReport { discarded }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<K, T> futures::Stream for DialogManager<K, T>
+where
+    K: Eq + Hash + Clone,
+    T: Send + 'static + Default,
+    Self: Unpin,
+{
+    type Item = (K, Result<T, DialogError>);
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let manager = self.get_mut();
+        let mut open = false;
+        for (key, dialog) in &mut manager.dialogs {
+            // Register before checking, so a result sent in between
+            // still wakes the task.
+            dialog.worker.register_waker(cx.waker());
+            match dialog.check() {
+                Some(result) => return std::task::Poll::Ready(Some((key.clone(), result))),
+                None => open |= dialog.is_open(),
+            }
+        }
+
+        if open {
+            std::task::Poll::Pending
+        } else {
+            std::task::Poll::Ready(None)
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use native_dialog::{Error, MessageDialog, MessageType};
+
+use crate::{
+    worker::Worker, DialogError, DialogKind, DialogResult, ImNativeDialogError, RequestToken,
+};
+
+/// The button chosen in
+/// [ImNativeMessageDialog::show_yes_no_cancel()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MessageChoice {
+    Yes,
+    No,
+    #[default]
+    Cancel,
+}
+
+/// A wrapper around [MessageDialog] for use with immediate mode gui
+/// libraries, following the same pattern as
+/// [crate::ImNativeFileDialog]. The `show*()` methods show the message
+/// in a new thread, and the result is returned to this object ready
+/// to be polled by the ui using [ImNativeMessageDialog::check()].
+pub struct ImNativeMessageDialog<T> {
+    worker: Worker<T>,
+}
+
+impl<T> Default for ImNativeMessageDialog<T> {
+    fn default() -> Self {
+        Self {
+            worker: Worker::default(),
+        }
+    }
+}
+
+impl ImNativeMessageDialog<()> {
+    /// Shows a dialog that alert users with some message using
+    /// [MessageDialog::show_alert()].
+    pub fn show_alert(
+        &mut self,
+        title: impl Into<String>,
+        text: impl Into<String>,
+        typ: MessageType,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let title = title.into();
+        let text = text.into();
+        self.worker.spawn(DialogKind::Alert, None, move || {
+            MessageDialog::new()
+                .set_title(&title)
+                .set_text(&text)
+                .set_type(typ)
+                .show_alert()
+        })
+    }
+}
+
+impl ImNativeMessageDialog<bool> {
+    /// Shows a dialog that let users to choose Yes/No using
+    /// [MessageDialog::show_confirm()].
+    pub fn show_confirm(
+        &mut self,
+        title: impl Into<String>,
+        text: impl Into<String>,
+        typ: MessageType,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let title = title.into();
+        let text = text.into();
+        self.worker.spawn(DialogKind::Confirm, None, move || {
+            MessageDialog::new()
+                .set_title(&title)
+                .set_text(&text)
+                .set_type(typ)
+                .show_confirm()
+        })
+    }
+}
+
+#[cfg(feature = "trash")]
+impl ImNativeMessageDialog<bool> {
+    /// Shows a dialog that let users to choose Yes/No using
+    /// [MessageDialog::show_confirm()], and if they choose Yes moves
+    /// `paths` to the trash in the dialog thread, available with the
+    /// `trash` feature. The result is `true` if the paths were moved to
+    /// the trash, and any error moving them is returned via
+    /// [ImNativeMessageDialog::check()].
+    pub fn show_confirm_trash(
+        &mut self,
+        title: impl Into<String>,
+        text: impl Into<String>,
+        paths: Vec<std::path::PathBuf>,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let title = title.into();
+        let text = text.into();
+        self.worker.spawn(
+            DialogKind::Confirm,
+            None,
+            move || -> Result<_, DialogError> {
+                let confirmed = MessageDialog::new()
+                    .set_title(&title)
+                    .set_text(&text)
+                    .set_type(MessageType::Warning)
+                    .show_confirm()?;
+                if confirmed {
+                    trash::delete_all(&paths).map_err(std::io::Error::other)?;
+                }
+                Ok(confirmed)
+            },
+        )
+    }
+}
+
+impl ImNativeMessageDialog<MessageChoice> {
+    /// Shows a dialog that let users to choose Yes/No/Cancel.
+    /// [MessageDialog] has no support for this, so the platform's
+    /// dialog program is used where available, otherwise it is
+    /// emulated using [MessageDialog::show_confirm()], in which case
+    /// users can't choose [MessageChoice::Cancel].
+    pub fn show_yes_no_cancel(
+        &mut self,
+        title: impl Into<String>,
+        text: impl Into<String>,
+        typ: MessageType,
+    ) -> Result<RequestToken, ImNativeDialogError> {
+        let title = title.into();
+        let text = text.into();
+        self.worker
+            .spawn(
+                DialogKind::YesNoCancel,
+                None,
+                move || match yes_no_cancel(&title, &text, typ)? {
+                    Some(choice) => Ok(choice),
+                    None => MessageDialog::new()
+                        .set_title(&title)
+                        .set_text(&text)
+                        .set_type(typ)
+                        .show_confirm()
+                        .map(|yes| {
+                            if yes {
+                                MessageChoice::Yes
+                            } else {
+                                MessageChoice::No
+                            }
+                        }),
+                },
+            )
+    }
+}
+
+impl<T: Send + 'static + Default> ImNativeMessageDialog<T> {
+    /// Set a callback to use for this dialog which will be called
+    /// immediately upon dialog close in the dialog monitoring thread.
+    pub fn with_callback<C>(&mut self, callback: C) -> &mut Self
+    where
+        C: FnOnce(&Result<T, DialogError>) + Send + 'static,
+    {
+        self.worker.set_callback(Box::new(callback));
+        self
+    }
+
+    /// Check if the dialog is complete. If it is complete it will
+    /// return `Some` with the result of the dialog, otherwise will
+    /// return `None`. This will update the status of
+    /// [ImNativeMessageDialog::is_open()].
+    pub fn check(&mut self) -> Option<Result<T, DialogError>> {
+        self.worker.check()
+    }
+
+    /// Like [ImNativeMessageDialog::check()], but tells cancellation apart
+    /// from failures, see [DialogResult].
+    pub fn check_result(&mut self) -> Option<DialogResult<T>> {
+        self.worker.check_result()
+    }
+
+    /// Returns `true` if the dialog is currently open, otherwise
+    /// returns `false`. Requires a previous call of
+    /// [ImNativeMessageDialog::check()] to update the current status.
+    pub fn is_open(&self) -> bool {
+        self.worker.is_open()
+    }
+
+    /// Returns the kind of the open dialog, or `None` if it isn't open,
+    /// see [crate::ImNativeFileDialog::kind()].
+    pub fn kind(&self) -> Option<DialogKind> {
+        self.worker.kind()
+    }
+
+    /// Returns a short description of the open dialog, or `None` if it
+    /// isn't open, see [crate::ImNativeFileDialog::status()].
+    pub fn status(&self) -> Option<String> {
+        self.worker.status()
+    }
+}
+
+/// Shows a Yes/No/Cancel dialog using the platform's dialog program,
+/// returning `None` if there is none available.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn yes_no_cancel(
+    title: &str,
+    text: &str,
+    _typ: MessageType,
+) -> Result<Option<MessageChoice>, Error> {
+    use crate::{
+        command::{self, GnuProgram},
+        i18n::{self, Text},
+    };
+    use std::process::Command;
+
+    match command::gnu_program() {
+        Some(GnuProgram::KDialog) => {
+            let status = Command::new("kdialog")
+                .arg("--title")
+                .arg(title)
+                .arg("--yesnocancel")
+                .arg(text)
+                .status()?;
+            match status.code() {
+                Some(0) => Ok(Some(MessageChoice::Yes)),
+                Some(1) => Ok(Some(MessageChoice::No)),
+                Some(2) => Ok(Some(MessageChoice::Cancel)),
+                _ => Err(Error::UnexpectedOutput("kdialog")),
+            }
+        }
+        Some(GnuProgram::Zenity) => {
+            // The extra button prints its label and exits with 1, like
+            // the cancel button, which is also how zenity exits when the
+            // dialog is dismissed, so dismissing it cancels.
+            let no = i18n::text(Text::No);
+            let output = Command::new("zenity")
+                .arg("--question")
+                .arg(format!("--title={}", title))
+                .arg(format!("--text={}", text))
+                .arg(format!("--ok-label={}", i18n::text(Text::Yes)))
+                .arg(format!("--cancel-label={}", i18n::text(Text::Cancel)))
+                .arg(format!("--extra-button={}", no))
+                .output()?;
+            match output.status.code() {
+                Some(0) => Ok(Some(MessageChoice::Yes)),
+                Some(1) if String::from_utf8_lossy(&output.stdout).trim_end() == no => {
+                    Ok(Some(MessageChoice::No))
+                }
+                Some(1) => Ok(Some(MessageChoice::Cancel)),
+                _ => Err(Error::UnexpectedOutput("zenity")),
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn yes_no_cancel(
+    title: &str,
+    text: &str,
+    typ: MessageType,
+) -> Result<Option<MessageChoice>, Error> {
+    use crate::{
+        command::{applescript_string, osascript},
+        i18n::{self, Text},
+    };
+
+    let (yes, no, cancel) = (
+        i18n::text(Text::Yes),
+        i18n::text(Text::No),
+        i18n::text(Text::Cancel),
+    );
+    let icon = match typ {
+        MessageType::Info => "note",
+        MessageType::Warning => "caution",
+        MessageType::Error => "stop",
+    };
+    let output = osascript(&format!(
+        "button returned of (display dialog {} with title {} \
+        buttons {{{cancel}, {no}, {yes}}} default button {yes} \
+        cancel button {cancel} with icon {})",
+        applescript_string(text),
+        applescript_string(title),
+        icon,
+        yes = applescript_string(&yes),
+        no = applescript_string(&no),
+        cancel = applescript_string(&cancel),
+    ))?;
+    match output {
+        Some(output) if output == yes => Ok(Some(MessageChoice::Yes)),
+        Some(output) if output == no => Ok(Some(MessageChoice::No)),
+        Some(_) => Err(Error::UnexpectedOutput("osascript")),
+        None => Ok(Some(MessageChoice::Cancel)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn yes_no_cancel(
+    title: &str,
+    text: &str,
+    typ: MessageType,
+) -> Result<Option<MessageChoice>, Error> {
+    use crate::command::{powershell, powershell_string};
+
+    let icon = match typ {
+        MessageType::Info => "Information",
+        MessageType::Warning => "Warning",
+        MessageType::Error => "Error",
+    };
+    let output = powershell(&format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+        [System.Windows.Forms.MessageBox]::Show({}, {}, 'YesNoCancel', '{}')",
+        powershell_string(text),
+        powershell_string(title),
+        icon,
+    ))?;
+    match output.as_deref() {
+        Some("Yes") => Ok(Some(MessageChoice::Yes)),
+        Some("No") => Ok(Some(MessageChoice::No)),
+        Some("Cancel") => Ok(Some(MessageChoice::Cancel)),
+        _ => Err(Error::UnexpectedOutput("powershell")),
+    }
+}
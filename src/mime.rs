@@ -0,0 +1,131 @@
+//! The MIME types understood by [crate::DialogOptions::mime_filter()],
+//! and their file extensions.
+
+const MIME_TYPES: &[(&str, &[&str])] = &[
+    ("application/gzip", &["gz"]),
+    ("application/json", &["json"]),
+    ("application/msword", &["doc"]),
+    ("application/pdf", &["pdf"]),
+    ("application/rtf", &["rtf"]),
+    ("application/toml", &["toml"]),
+    ("application/vnd.oasis.opendocument.presentation", &["odp"]),
+    ("application/vnd.oasis.opendocument.spreadsheet", &["ods"]),
+    ("application/vnd.oasis.opendocument.text", &["odt"]),
+    (
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        &["pptx"],
+    ),
+    (
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        &["xlsx"],
+    ),
+    (
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        &["docx"],
+    ),
+    ("application/wasm", &["wasm"]),
+    ("application/x-7z-compressed", &["7z"]),
+    ("application/x-tar", &["tar"]),
+    ("application/xml", &["xml"]),
+    ("application/yaml", &["yaml", "yml"]),
+    ("application/zip", &["zip"]),
+    ("audio/aac", &["aac"]),
+    ("audio/flac", &["flac"]),
+    ("audio/midi", &["mid", "midi"]),
+    ("audio/mp4", &["m4a"]),
+    ("audio/mpeg", &["mp3"]),
+    ("audio/ogg", &["ogg", "oga", "opus"]),
+    ("audio/wav", &["wav"]),
+    ("audio/webm", &["weba"]),
+    ("font/otf", &["otf"]),
+    ("font/ttf", &["ttf"]),
+    ("font/woff", &["woff"]),
+    ("font/woff2", &["woff2"]),
+    ("image/avif", &["avif"]),
+    ("image/bmp", &["bmp"]),
+    ("image/gif", &["gif"]),
+    ("image/heic", &["heic"]),
+    ("image/jpeg", &["jpg", "jpeg"]),
+    ("image/png", &["png"]),
+    ("image/svg+xml", &["svg"]),
+    ("image/tiff", &["tif", "tiff"]),
+    ("image/vnd.microsoft.icon", &["ico"]),
+    ("image/webp", &["webp"]),
+    ("text/css", &["css"]),
+    ("text/csv", &["csv"]),
+    ("text/html", &["html", "htm"]),
+    ("text/javascript", &["js", "mjs"]),
+    ("text/markdown", &["md", "markdown"]),
+    ("text/plain", &["txt"]),
+    ("video/mp4", &["mp4", "m4v"]),
+    ("video/mpeg", &["mpeg", "mpg"]),
+    ("video/ogg", &["ogv"]),
+    ("video/quicktime", &["mov"]),
+    ("video/webm", &["webm"]),
+    ("video/x-matroska", &["mkv"]),
+    ("video/x-msvideo", &["avi"]),
+];
+
+/// The file extensions for `mime_type`, which may be a wildcard such as
+/// `image/*`, compared ignoring case. Unknown types have no
+/// extensions.
+pub(crate) fn extensions(mime_type: &str) -> impl Iterator<Item = &'static str> + '_ {
+    let prefix = mime_type.strip_suffix('*');
+    MIME_TYPES
+        .iter()
+        .filter(move |(name, _)| match prefix {
+            Some(prefix) => name
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix)),
+            None => name.eq_ignore_ascii_case(mime_type),
+        })
+        .flat_map(|(_, extensions)| extensions.iter().copied())
+}
+
+/// The MIME types of files with the extension `extension`.
+#[cfg_attr(not(all(unix, not(target_os = "macos"))), allow(dead_code))]
+pub(crate) fn mime_types(extension: &str) -> impl Iterator<Item = &'static str> + '_ {
+    MIME_TYPES
+        .iter()
+        .filter(move |(_, extensions)| {
+            extensions
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(extension))
+        })
+        .map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extensions_of(mime_type: &str) -> Vec<&'static str> {
+        extensions(mime_type).collect()
+    }
+
+    #[test]
+    fn finds_extensions_of_exact_types() {
+        assert_eq!(extensions_of("image/jpeg"), ["jpg", "jpeg"]);
+        assert_eq!(extensions_of("IMAGE/JPEG"), ["jpg", "jpeg"]);
+        assert!(extensions_of("image/x-unknown").is_empty());
+        // A prefix of a type isn't a match without the wildcard.
+        assert!(extensions_of("image/jp").is_empty());
+    }
+
+    #[test]
+    fn finds_extensions_of_wildcards_ignoring_case() {
+        let audio = extensions_of("audio/*");
+        assert_eq!(extensions_of("Audio/*"), audio);
+        assert!(audio.contains(&"mp3"));
+        assert!(audio.contains(&"flac"));
+        assert!(!audio.contains(&"mp4"));
+        assert!(extensions_of("chemical/*").is_empty());
+    }
+
+    #[test]
+    fn finds_types_of_extensions() {
+        assert_eq!(mime_types("yml").collect::<Vec<_>>(), ["application/yaml"]);
+        assert_eq!(mime_types("PNG").collect::<Vec<_>>(), ["image/png"]);
+        assert_eq!(mime_types("unknown").count(), 0);
+    }
+}
@@ -0,0 +1,57 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use crate::{DialogError, DialogKind, DialogOutcome};
+
+/// Observes the dialogs shown by this crate, for example to record
+/// how often they are used and how long they stay open. Register an
+/// observer for every dialog using [set_observer()], or for a single
+/// dialog using [crate::ImNativeFileDialog::with_observer()].
+///
+/// Only [DialogObserver::on_open()] and [DialogObserver::on_close()]
+/// are called for dialogs shown using
+/// [crate::ImNativeFileDialog::show()], because the result is sent by
+/// the caller.
+pub trait DialogObserver: Send + Sync {
+    /// Called when a dialog is shown.
+    fn on_open(&self, _kind: DialogKind) {}
+
+    /// Called in the dialog thread when a dialog completes, with
+    /// `cancelled` set if the user didn't select anything, and the
+    /// `duration` the dialog was open.
+    fn on_complete(&self, _kind: DialogKind, _cancelled: bool, _duration: Duration) {}
+
+    /// Called in the dialog thread when a dialog fails, with the
+    /// `duration` the dialog was open.
+    fn on_error(&self, _kind: DialogKind, _error: &DialogError, _duration: Duration) {}
+
+    /// Called in the dialog thread after [DialogObserver::on_complete()]
+    /// or [DialogObserver::on_error()], with a record of the dialog,
+    /// for example to log it.
+    fn on_outcome(&self, _outcome: &DialogOutcome) {}
+
+    /// Called in the dialog thread when the native dialog has closed,
+    /// after the result was delivered, for example to focus the main
+    /// window again.
+    fn on_close(&self, _kind: DialogKind) {}
+}
+
+static OBSERVER: RwLock<Option<Arc<dyn DialogObserver>>> = RwLock::new(None);
+
+/// Set the observer for every dialog shown by this crate, replacing
+/// any previous observer.
+pub fn set_observer<O: DialogObserver + 'static>(observer: O) {
+    *OBSERVER.write().expect("observer lock poisoned") = Some(Arc::new(observer));
+}
+
+/// Remove the observer set using [set_observer()].
+pub fn clear_observer() {
+    *OBSERVER.write().expect("observer lock poisoned") = None;
+}
+
+/// The observer set using [set_observer()].
+pub(crate) fn global_observer() -> Option<Arc<dyn DialogObserver>> {
+    OBSERVER.read().expect("observer lock poisoned").clone()
+}
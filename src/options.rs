@@ -0,0 +1,1315 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    choices::{Choice, ChoiceValues},
+    filter_path::FilterSlot,
+    i18n, paths, template, Backend, ChoiceValue, DialogError, DialogKind, LongPathPrefix,
+    SelectedFilter, Text,
+};
+
+use native_dialog::{FileDialog, MessageDialog, MessageType};
+use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+
+/// A [RawWindowHandle] which can be sent to the dialog thread.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OwnerHandle(pub(crate) RawWindowHandle);
+
+// The handle is only ever used by the backend to parent the dialog,
+// which the platforms support from a thread other than the owner's.
+unsafe impl Send for OwnerHandle {}
+
+/// A file filter, see [DialogOptions::filter()].
+#[derive(Debug, Clone)]
+pub(crate) struct Filter {
+    pub(crate) description: String,
+    pub(crate) extensions: Vec<String>,
+}
+
+/// The order of the files selected in multiple file dialogs, see
+/// [DialogOptions::sort()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    /// Sorted by file name, and then by the rest of the path.
+    Name,
+    /// Sorted by the time the files were last modified, oldest first.
+    /// Files whose modification time can't be read come last.
+    Modified,
+}
+
+/// What multiple file dialogs do when the user selects more files
+/// than the limit set using [DialogOptions::max_files()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LimitPolicy {
+    /// Keep the first files up to the limit, after sorting. The other
+    /// files are reported as [crate::RejectReason::TooMany] by
+    /// [crate::ImNativeFileDialog::show_open_multiple_file_filtered()].
+    Truncate,
+    /// Tell the user about the limit and show the dialog again.
+    Reprompt,
+}
+
+/// The appearance of a dialog, see [DialogOptions::theme()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+/// A rectangle on the screen, in the desktop coordinates of the
+/// platform, whose origin is the top left of the primary monitor, see
+/// [DialogOptions::near()]. These are physical pixels on Windows and
+/// points on macOS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScreenRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScreenRect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The center of the rectangle.
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    pub(crate) fn center(&self) -> (i32, i32) {
+        (
+            self.x.saturating_add((self.width / 2) as i32),
+            self.y.saturating_add((self.height / 2) as i32),
+        )
+    }
+}
+
+/// A comparator set using [DialogOptions::sort_by()].
+type Compare = Arc<dyn Fn(&Path, &Path) -> Ordering + Send + Sync>;
+
+/// How the files selected in multiple file dialogs are sorted.
+#[derive(Clone)]
+pub(crate) enum Sort {
+    Order(SortOrder),
+    By(Compare),
+}
+
+impl fmt::Debug for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sort::Order(order) => f.debug_tuple("Order").field(order).finish(),
+            Sort::By(_) => f.write_str("By(..)"),
+        }
+    }
+}
+
+/// A function set using [DialogOptions::on_backend_failure()].
+#[derive(Clone)]
+pub(crate) struct Fallback(Arc<dyn Fn(&DialogError) + Send + Sync>);
+
+impl fmt::Debug for Fallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Fallback(..)")
+    }
+}
+
+/// What a dialog asks the user for, used to prompt on the terminal
+/// instead with the `terminal` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Prompt {
+    File,
+    Dir,
+    Save,
+}
+
+/// Options for the dialogs shown by the convenience methods of
+/// [crate::ImNativeFileDialog]. This can be created from an
+/// `Option<PathBuf>` location.
+#[derive(Debug, Clone, Default)]
+pub struct DialogOptions {
+    pub(crate) location: Option<PathBuf>,
+    pub(crate) title: Option<String>,
+    pub(crate) accept_label: Option<String>,
+    pub(crate) owner: Option<OwnerHandle>,
+    pub(crate) bring_to_front: bool,
+    pub(crate) show_hidden: bool,
+    pub(crate) can_create_dirs: Option<bool>,
+    pub(crate) filters: Vec<Filter>,
+    pub(crate) create_parent_dirs: bool,
+    pub(crate) retries: u32,
+    pub(crate) dedupe: bool,
+    pub(crate) sort: Option<Sort>,
+    pub(crate) max_files: Option<(usize, LimitPolicy)>,
+    pub(crate) min_files: Option<usize>,
+    pub(crate) dir_must_exist: bool,
+    pub(crate) dir_must_be_writable: bool,
+    pub(crate) dir_must_be_empty: bool,
+    pub(crate) increment_pattern: Option<String>,
+    pub(crate) resolve_symlinks: bool,
+    pub(crate) long_path_prefix: Option<LongPathPrefix>,
+    pub(crate) normalize_unicode: bool,
+    pub(crate) choices: Vec<Choice>,
+    pub(crate) choice_values: Option<ChoiceValues>,
+    pub(crate) selected_filter: Option<FilterSlot>,
+    pub(crate) preselect: Vec<PathBuf>,
+    pub(crate) notify: Option<String>,
+    pub(crate) backend: Option<Backend>,
+    pub(crate) locale: Option<String>,
+    pub(crate) theme: Option<Theme>,
+    pub(crate) near: Option<ScreenRect>,
+    pub(crate) root: Option<PathBuf>,
+    pub(crate) reject_network: bool,
+    pub(crate) reject_placeholders: bool,
+    pub(crate) on_backend_failure: Option<Fallback>,
+    pub(crate) filename_template: Option<String>,
+    #[cfg(target_os = "windows")]
+    pub(crate) windows: Option<crate::windows::WindowsOptions>,
+    #[cfg(target_os = "macos")]
+    pub(crate) macos: Option<crate::macos::MacOsOptions>,
+}
+
+impl DialogOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the default location that the dialog shows at open.
+    pub fn location(mut self, location: impl Into<PathBuf>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Sets the window title for the dialog using
+    /// [FileDialog::set_title()].
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the label of the button which accepts the dialog, such as
+    /// "Import", "Export" or "Select Folder", instead of "Open" or
+    /// "Save". This is shown by the Windows and macOS dialogs and the
+    /// xdg-desktop-portal file chooser. The dialogs shown using
+    /// [native_dialog], zenity or kdialog can't relabel the button, so
+    /// on GNU/Linux and BSD Unix outside a sandbox this is a no-op.
+    pub fn accept_label(mut self, label: impl Into<String>) -> Self {
+        self.accept_label = Some(label.into());
+        self
+    }
+
+    /// Sets the owner of the dialog using [FileDialog::set_owner()].
+    /// On Unix and GNU/Linux, this is a no-op.
+    ///
+    /// `window` implements `HasRawWindowHandle` from raw-window-handle
+    /// 0.5, which winit 0.30 windows only do with winit's `rwh_05`
+    /// feature enabled.
+    ///
+    /// # Safety
+    ///
+    /// The handle of `window` is used by the dialog thread after this
+    /// borrow has ended, so it's the caller's responsibility to ensure
+    /// the window outlives the dialog, as for
+    /// [DialogOptions::owner_handle()].
+    pub unsafe fn owner<W: HasRawWindowHandle>(mut self, window: &W) -> Self {
+        self.owner = Some(OwnerHandle(window.raw_window_handle()));
+        self
+    }
+
+    /// Sets the owner of the dialog by raw handle using
+    /// [FileDialog::set_owner_handle()]. On Unix and GNU/Linux, this
+    /// is a no-op.
+    ///
+    /// # Safety
+    ///
+    /// It's the caller's responsibility to ensure the handle is valid
+    /// until the dialog is closed.
+    pub unsafe fn owner_handle(mut self, handle: RawWindowHandle) -> Self {
+        self.owner = Some(OwnerHandle(handle));
+        self
+    }
+
+    /// Adds a file filter using [FileDialog::add_filter()], for
+    /// example `.filter("PNG Image", &["png"])`. Filters with no
+    /// extensions are ignored. Directory dialogs don't use filters.
+    pub fn filter(mut self, description: impl Into<String>, extensions: &[&str]) -> Self {
+        if !extensions.is_empty() {
+            self.filters.push(Filter {
+                description: description.into(),
+                extensions: extensions
+                    .iter()
+                    .map(|extension| extension.to_string())
+                    .collect(),
+            });
+        }
+        self
+    }
+
+    /// Adds a file filter for files with the MIME types `mime_types`,
+    /// which may be wildcards such as `image/*`. [FileDialog] only
+    /// supports extensions, so each type is expanded to its extensions
+    /// using a bundled table of common types, and unknown types are
+    /// ignored.
+    pub fn mime_filter(self, description: impl Into<String>, mime_types: &[&str]) -> Self {
+        let mut extensions: Vec<&str> = Vec::new();
+        for extension in mime_types
+            .iter()
+            .flat_map(|mime_type| crate::mime::extensions(mime_type))
+        {
+            if !extensions.contains(&extension) {
+                extensions.push(extension);
+            }
+        }
+        self.filter(description, &extensions)
+    }
+
+    /// Sets whether to bring the dialog to the foreground once it is
+    /// shown, when it has no owner. On Windows the dialog often opens
+    /// behind the main window otherwise. On other platforms, this is a
+    /// no-op.
+    pub fn bring_to_front(mut self, bring_to_front: bool) -> Self {
+        self.bring_to_front = bring_to_front;
+        self
+    }
+
+    /// Sets whether file dialogs show hidden files, such as dotfiles, for
+    /// tools which edit them. This is honored by the Windows and macOS
+    /// dialogs. The xdg-desktop-portal file chooser and the dialogs shown
+    /// using [native_dialog], zenity or kdialog have no option for it, so
+    /// on GNU/Linux and BSD Unix this is a no-op, and users can show them
+    /// from the dialog instead.
+    pub fn show_hidden(mut self, show_hidden: bool) -> Self {
+        self.show_hidden = show_hidden;
+        self
+    }
+
+    /// Sets whether users can create new folders from save and directory
+    /// dialogs, for applications which must restrict users to existing
+    /// locations. This is honored by the macOS panels. The Windows
+    /// dialogs, the xdg-desktop-portal file chooser and the dialogs shown
+    /// using [native_dialog], zenity or kdialog always allow it.
+    pub fn can_create_dirs(mut self, can_create_dirs: bool) -> Self {
+        self.can_create_dirs = Some(can_create_dirs);
+        self
+    }
+
+    /// Sets whether save dialogs create the missing parent directories
+    /// of the chosen path in the dialog thread, before returning the
+    /// path. Some backends let users type a path in a directory which
+    /// doesn't exist. Any error creating the directories is returned
+    /// via [crate::ImNativeFileDialog::check()].
+    pub fn create_parent_dirs(mut self, create_parent_dirs: bool) -> Self {
+        self.create_parent_dirs = create_parent_dirs;
+        self
+    }
+
+    /// Sets how many times to show the dialog again in the dialog
+    /// thread when the backend fails with a transient error, such as
+    /// an I/O failure running the dialog program, before the error is
+    /// returned via [crate::ImNativeFileDialog::check()]. Defaults to
+    /// `0`.
+    pub fn retry(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets whether multiple file dialogs remove duplicate files from
+    /// the selection in the dialog thread, comparing the canonical
+    /// paths, so the same file selected through a symbolic link or a
+    /// relative path is only returned once. The first path selected for
+    /// each file is kept.
+    pub fn dedupe(mut self, dedupe: bool) -> Self {
+        self.dedupe = dedupe;
+        self
+    }
+
+    /// Sets the order multiple file dialogs sort the selection in, in
+    /// the dialog thread. By default the files are returned in the
+    /// order the backend returns them.
+    pub fn sort(mut self, order: SortOrder) -> Self {
+        self.sort = Some(Sort::Order(order));
+        self
+    }
+
+    /// Sets a comparator multiple file dialogs sort the selection with,
+    /// in the dialog thread, see [DialogOptions::sort()].
+    pub fn sort_by<F>(mut self, compare: F) -> Self
+    where
+        F: Fn(&Path, &Path) -> Ordering + Send + Sync + 'static,
+    {
+        self.sort = Some(Sort::By(Arc::new(compare)));
+        self
+    }
+
+    /// Sets the most files users can select in multiple file dialogs,
+    /// using `policy` when they select more, for example "attach up
+    /// to 10 files".
+    pub fn max_files(mut self, max: usize, policy: LimitPolicy) -> Self {
+        self.max_files = Some((max, policy));
+        self
+    }
+
+    /// Sets the fewest files users must select in multiple file
+    /// dialogs, for example "pick at least 2 files to compare". If they
+    /// select fewer, they are told about the limit and the dialog is
+    /// shown again. Cancelling the dialog still returns no files.
+    pub fn min_files(mut self, min: usize) -> Self {
+        self.min_files = Some(min);
+        self
+    }
+
+    /// Sets whether the chosen paths are resolved to the targets of any
+    /// symbolic links in them in the dialog thread, for example when
+    /// an asset pipeline needs the real location. By default the paths
+    /// are returned as chosen. The original path is also returned in
+    /// [crate::CheckedPath::link] by checked methods such as
+    /// [crate::ImNativeFileDialog::open_single_file_checked()].
+    pub fn resolve_symlinks(mut self, resolve_symlinks: bool) -> Self {
+        self.resolve_symlinks = resolve_symlinks;
+        self
+    }
+
+    /// Sets what happens to the `\\?\` extended-length prefix of the
+    /// chosen paths on Windows, which backends return for some long
+    /// paths. By default the paths are returned as chosen. On other
+    /// platforms, this is a no-op.
+    pub fn long_path_prefix(mut self, prefix: LongPathPrefix) -> Self {
+        self.long_path_prefix = Some(prefix);
+        self
+    }
+
+    /// Sets whether the chosen paths are converted to Unicode
+    /// normalization form C on macOS, where the file system returns
+    /// decomposed file names which don't compare equal to the composed
+    /// strings usually found elsewhere, such as in configuration files.
+    /// On other platforms, this is a no-op.
+    pub fn normalize_unicode(mut self, normalize_unicode: bool) -> Self {
+        self.normalize_unicode = normalize_unicode;
+        self
+    }
+
+    /// Adds a checkbox labelled `label` to file dialogs, such as "Open
+    /// read-only", whose value is returned by
+    /// [crate::ImNativeFileDialog::open_single_file_choices()] and
+    /// [crate::ImNativeFileDialog::show_save_single_file_choices()].
+    /// Only some backends show controls, see
+    /// [crate::Capabilities::choices].
+    pub fn checkbox(mut self, label: impl Into<String>, checked: bool) -> Self {
+        self.choices.push(Choice::Checkbox {
+            label: label.into(),
+            checked,
+        });
+        self
+    }
+
+    /// Adds a combo box labelled `label` with `items` to file dialogs,
+    /// such as "Import as" with "Copy" and "Link", with the item at
+    /// index `selected` initially selected, see
+    /// [DialogOptions::checkbox()].
+    pub fn combo(mut self, label: impl Into<String>, items: &[&str], selected: usize) -> Self {
+        self.choices.push(Choice::Combo {
+            label: label.into(),
+            items: items.iter().map(|item| item.to_string()).collect(),
+            selected,
+        });
+        self
+    }
+
+    /// Selects `path` when the dialog opens, to let users adjust an
+    /// existing choice, and shows the directory containing it instead
+    /// of the location. Call repeatedly to select several files in
+    /// multiple file dialogs, which must be in the same directory.
+    /// Windows selects every file, other backends only the first, and
+    /// the `terminal` feature fills in the prompt with each path.
+    pub fn preselect(mut self, path: impl Into<PathBuf>) -> Self {
+        self.preselect.push(path.into());
+        self
+    }
+
+    /// The directory to show and the contents of the file name field
+    /// which select the paths set using [DialogOptions::preselect()].
+    /// Only the name of the first path is used unless `quoted`, which
+    /// joins the names of all of them in quotes like the Windows
+    /// dialogs accept.
+    pub(crate) fn preselection(&self, quoted: bool) -> Option<(&Path, String)> {
+        let first = self.preselect.first()?;
+        let dir = first.parent()?;
+        let mut names = self
+            .preselect
+            .iter()
+            .filter(|path| path.parent() == Some(dir))
+            .filter_map(|path| path.file_name()?.to_str());
+        if !quoted {
+            return names.next().map(|name| (dir, name.to_owned()));
+        }
+        let names: Vec<String> = names.map(|name| format!("\"{}\"", name)).collect();
+        Some((dir, names.join(" ")))
+    }
+
+    /// Returns these options with a new place for the backend to store
+    /// the values of the controls in.
+    pub(crate) fn with_choice_values(mut self) -> Self {
+        self.choice_values = Some(ChoiceValues::default());
+        self
+    }
+
+    /// Stores the values of the controls when the dialog is confirmed.
+    #[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+    pub(crate) fn store_choices(&self, values: Vec<ChoiceValue>) {
+        if let Some(slot) = &self.choice_values {
+            *slot.lock().expect("choices lock poisoned") = Some(values);
+        }
+    }
+
+    /// The values of the controls stored by the backend, or their
+    /// initial values if the backend doesn't show controls.
+    pub(crate) fn choice_values(&self) -> Vec<ChoiceValue> {
+        self.choice_values
+            .as_ref()
+            .and_then(|slot| slot.lock().expect("choices lock poisoned").clone())
+            .unwrap_or_else(|| self.choices.iter().map(Choice::initial).collect())
+    }
+
+    /// Returns these options with a new place for the backend to store
+    /// the index of the selected filter in.
+    pub(crate) fn with_selected_filter(mut self) -> Self {
+        self.selected_filter = Some(FilterSlot::default());
+        self
+    }
+
+    /// Stores the index of the selected filter when the dialog is
+    /// confirmed.
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub(crate) fn store_selected_filter(&self, index: usize) {
+        if let Some(slot) = &self.selected_filter {
+            *slot.lock().expect("selected filter lock poisoned") = Some(index);
+        }
+    }
+
+    /// The filter stored by the backend, if it reports it.
+    pub(crate) fn selected_filter(&self) -> Option<SelectedFilter> {
+        let index = (*self
+            .selected_filter
+            .as_ref()?
+            .lock()
+            .expect("selected filter lock poisoned"))?;
+        self.filters.get(index).map(|filter| SelectedFilter {
+            index,
+            description: filter.description.clone(),
+        })
+    }
+
+    /// Fills in the options which aren't set from `defaults`. Flags
+    /// enabled in either are enabled, and filters are only taken from
+    /// `defaults` if there are none.
+    pub(crate) fn or_defaults(mut self, defaults: &DialogOptions) -> Self {
+        let DialogOptions {
+            location,
+            title,
+            accept_label,
+            owner,
+            bring_to_front,
+            show_hidden,
+            can_create_dirs,
+            filters,
+            create_parent_dirs,
+            retries,
+            dedupe,
+            sort,
+            max_files,
+            min_files,
+            dir_must_exist,
+            dir_must_be_writable,
+            dir_must_be_empty,
+            increment_pattern,
+            resolve_symlinks,
+            long_path_prefix,
+            normalize_unicode,
+            choices,
+            choice_values: _,
+            selected_filter: _,
+            preselect,
+            notify,
+            backend,
+            locale,
+            theme,
+            near,
+            root,
+            reject_network,
+            reject_placeholders,
+            on_backend_failure,
+            filename_template,
+            #[cfg(target_os = "windows")]
+            windows,
+            #[cfg(target_os = "macos")]
+            macos,
+        } = defaults.clone();
+        self.location = self.location.or(location);
+        self.title = self.title.or(title);
+        self.accept_label = self.accept_label.or(accept_label);
+        self.owner = self.owner.or(owner);
+        self.bring_to_front |= bring_to_front;
+        self.show_hidden |= show_hidden;
+        self.can_create_dirs = self.can_create_dirs.or(can_create_dirs);
+        if self.filters.is_empty() {
+            self.filters = filters;
+        }
+        self.create_parent_dirs |= create_parent_dirs;
+        self.retries = self.retries.max(retries);
+        self.dedupe |= dedupe;
+        self.sort = self.sort.or(sort);
+        self.max_files = self.max_files.or(max_files);
+        self.min_files = self.min_files.or(min_files);
+        self.dir_must_exist |= dir_must_exist;
+        self.dir_must_be_writable |= dir_must_be_writable;
+        self.dir_must_be_empty |= dir_must_be_empty;
+        self.increment_pattern = self.increment_pattern.or(increment_pattern);
+        self.resolve_symlinks |= resolve_symlinks;
+        self.long_path_prefix = self.long_path_prefix.or(long_path_prefix);
+        self.normalize_unicode |= normalize_unicode;
+        if self.choices.is_empty() {
+            self.choices = choices;
+        }
+        if self.preselect.is_empty() {
+            self.preselect = preselect;
+        }
+        self.notify = self.notify.or(notify);
+        self.backend = self.backend.or(backend);
+        self.locale = self.locale.or(locale);
+        self.theme = self.theme.or(theme);
+        self.near = self.near.or(near);
+        self.root = self.root.or(root);
+        self.reject_network |= reject_network;
+        self.reject_placeholders |= reject_placeholders;
+        self.on_backend_failure = self.on_backend_failure.or(on_backend_failure);
+        self.filename_template = self.filename_template.or(filename_template);
+        #[cfg(target_os = "windows")]
+        {
+            self.windows = self.windows.or(windows);
+        }
+        #[cfg(target_os = "macos")]
+        {
+            self.macos = self.macos.or(macos);
+        }
+        self
+    }
+
+    /// Applies the conversions set in these options to a path returned
+    /// by the backend.
+    fn convert(&self, mut path: PathBuf) -> PathBuf {
+        if self.resolve_symlinks {
+            if let Some(resolved) = paths::resolve_symlinks(&path) {
+                path = resolved;
+            }
+        }
+        #[cfg(target_os = "windows")]
+        if let Some(prefix) = self.long_path_prefix {
+            path = prefix.apply(path);
+        }
+        #[cfg(target_os = "macos")]
+        if self.normalize_unicode {
+            path = paths::nfc(path);
+        }
+        path
+    }
+
+    /// Sets whether save dialogs rename the chosen path in the dialog
+    /// thread when it already exists, to the first path which doesn't
+    /// such as `name (1).ext`, like browsers do for downloads. Use
+    /// [DialogOptions::increment_pattern()] to change the names.
+    pub fn auto_increment(mut self, auto_increment: bool) -> Self {
+        self.increment_pattern = if auto_increment {
+            Some("{stem} ({n})".to_owned())
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Sets the pattern save dialogs rename the chosen path with when
+    /// it already exists, see [DialogOptions::auto_increment()], where
+    /// `{stem}` is replaced by the file name without its extension and
+    /// `{n}` by the counter, for example `"{stem}-{n}"`. The extension
+    /// is kept.
+    pub fn increment_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.increment_pattern = Some(pattern.into());
+        self
+    }
+
+    /// Fills in the file name of save dialogs from `template`, such as
+    /// `"export-{date}-{counter}.csv"`, when no path is preselected.
+    /// `{date}` is replaced by the local date like `2024-01-31`,
+    /// `{time}` by the local time like `14-05-09`, and `{counter}` by a
+    /// number which counts up each time the user saves using the
+    /// template, skipping names which exist in the location, see
+    /// [crate::TemplateCounters].
+    pub fn filename_template(mut self, template: impl Into<String>) -> Self {
+        self.filename_template = Some(template.into());
+        self
+    }
+
+    /// Shows an information dialog with `message`, such as "The report
+    /// was exported", once a save dialog has delivered the chosen path,
+    /// or once the file has been written by
+    /// [crate::ImNativeFileDialog::show_save_and_write()]. The
+    /// information dialog doesn't delay the result, and isn't shown if
+    /// the user cancelled or writing failed. Other dialogs ignore this.
+    pub fn then_notify(mut self, message: impl Into<String>) -> Self {
+        self.notify = Some(message.into());
+        self
+    }
+
+    /// Shows file dialogs using `backend` instead of the one chosen
+    /// for the platform and session, for example
+    /// [Backend::Portal] for user documents and [Backend::Zenity] for
+    /// paths the portal restricts. Backends which aren't available on
+    /// this platform, or without the feature they need, are ignored,
+    /// and [Backend::Unavailable] fails with
+    /// [crate::DialogError::NoImplementation]. Message dialogs always
+    /// use the default backend.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Asks the backend to show file dialogs in the language of
+    /// `locale`, such as `de_DE`, so they match the application rather
+    /// than the system. This is honored by zenity and kdialog, if the
+    /// locale is installed, and ignored by the other backends, which
+    /// use the system language.
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Asks the backend to show file dialogs with a light or dark
+    /// appearance, so they match the application rather than the
+    /// system. This is honored by the macOS panels and zenity, and
+    /// ignored by the other backends.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Asks the backend to show file dialogs centered on `rect`, such
+    /// as the button which opened them or the monitor the application
+    /// is on, so they don't open on another monitor. This is honored by
+    /// the Windows dialogs, which are moved once they appear, and by the
+    /// macOS panels unless they are shown as a sheet of their owner,
+    /// and ignored by the other backends, which let the window manager
+    /// place the dialog.
+    pub fn near(mut self, rect: ScreenRect) -> Self {
+        self.near = Some(rect);
+        self
+    }
+
+    /// Calls `fallback` in the dialog thread as soon as a file dialog
+    /// fails to open, after any retries set using
+    /// [DialogOptions::retry()], for example to let users type the
+    /// path instead, rather than waiting for
+    /// [crate::ImNativeFileDialog::check()] to return the error, which
+    /// it still does.
+    pub fn on_backend_failure<F>(mut self, fallback: F) -> Self
+    where
+        F: Fn(&DialogError) + Send + Sync + 'static,
+    {
+        self.on_backend_failure = Some(Fallback(Arc::new(fallback)));
+        self
+    }
+
+    /// Shows the information dialog set using
+    /// [DialogOptions::then_notify()], without waiting for it to close.
+    pub(crate) fn notify_saved(&self) {
+        let message = match &self.notify {
+            Some(message) => message.clone(),
+            None => return,
+        };
+        let title = self.title.clone().unwrap_or_default();
+        let show = move || {
+            let result = crate::worker::run_dialog(DialogKind::Alert, || {
+                MessageDialog::new()
+                    .set_title(&title)
+                    .set_text(&message)
+                    .set_type(MessageType::Info)
+                    .show_alert()
+            });
+            if let Err(_error) = result {
+                #[cfg(feature = "log")]
+                log::warn!("Error while showing the save notification: {}", _error);
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_error, "error while showing the save notification");
+            }
+        };
+        #[cfg(feature = "inline")]
+        show();
+        // The notification is best effort, so failing to spawn its
+        // thread is ignored.
+        #[cfg(not(feature = "inline"))]
+        let _ = std::thread::Builder::new().spawn(show);
+    }
+
+    /// The first path based on `path` using
+    /// [DialogOptions::increment_pattern()] which doesn't exist.
+    fn increment(&self, path: PathBuf) -> PathBuf {
+        let pattern = match &self.increment_pattern {
+            Some(pattern) if path.exists() => pattern,
+            _ => return path,
+        };
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let extension = path.extension().map(|extension| extension.to_owned());
+        (1..)
+            .map(|n| {
+                let mut candidate = path.with_file_name(
+                    pattern
+                        .replace("{stem}", &stem)
+                        .replace("{n}", &n.to_string()),
+                );
+                if let Some(extension) = &extension {
+                    candidate.set_extension(extension);
+                }
+                candidate
+            })
+            .find(|candidate| !candidate.exists())
+            .expect("unbounded counter")
+    }
+
+    /// Sets whether directory dialogs require the chosen directory to
+    /// exist, when the backend lets users type a path. Otherwise the
+    /// user is told why and the dialog is shown again.
+    pub fn must_exist(mut self, must_exist: bool) -> Self {
+        self.dir_must_exist = must_exist;
+        self
+    }
+
+    /// Sets whether directory dialogs require the chosen directory to
+    /// be writable, which is checked by creating a file in it in the
+    /// dialog thread, for example to choose an output directory.
+    /// Otherwise the user is told why and the dialog is shown again.
+    pub fn must_be_writable(mut self, must_be_writable: bool) -> Self {
+        self.dir_must_be_writable = must_be_writable;
+        self
+    }
+
+    /// Sets whether directory dialogs require the chosen directory to
+    /// be empty, or not exist yet. Otherwise the user is told why and
+    /// the dialog is shown again.
+    pub fn must_be_empty(mut self, must_be_empty: bool) -> Self {
+        self.dir_must_be_empty = must_be_empty;
+        self
+    }
+
+    /// Sets whether file and directory dialogs only accept paths inside
+    /// `root`, such as the workspace of an editor, once symlinks and
+    /// `..` are resolved. Otherwise the user is told why and the dialog
+    /// is shown again. Dialogs start in `root` unless their location is
+    /// inside it, but native dialogs can't be kept from navigating
+    /// above it.
+    pub fn restrict_to(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Sets whether file and directory dialogs reject paths on network
+    /// shares, such as UNC paths and mapped drives on Windows, or NFS
+    /// and SMB mounts, which can block for a long time when they are
+    /// read. Otherwise the user is told why and the dialog is shown
+    /// again. To warn about them instead, check
+    /// [crate::PathChecks::network].
+    pub fn reject_network_paths(mut self, reject: bool) -> Self {
+        self.reject_network = reject;
+        self
+    }
+
+    /// Sets whether file dialogs reject files whose contents are only
+    /// stored online by a cloud sync client, such as OneDrive and
+    /// Dropbox files which aren't kept on this computer on Windows, or
+    /// evicted iCloud Drive files on macOS, which are downloaded when
+    /// they are read. Otherwise the user is told why and the dialog is
+    /// shown again. To warn about them instead, check
+    /// [crate::PathChecks::placeholder].
+    pub fn reject_cloud_placeholders(mut self, reject: bool) -> Self {
+        self.reject_placeholders = reject;
+        self
+    }
+
+    /// The reason why `path` is rejected by
+    /// [DialogOptions::restrict_to()],
+    /// [DialogOptions::reject_network_paths()] or
+    /// [DialogOptions::reject_cloud_placeholders()], followed by what
+    /// to show after it.
+    fn reject(&self, path: &Path) -> Option<(Text, String)> {
+        if let Some(root) = &self.root {
+            if !paths::is_within(path, root) {
+                return Some((Text::OutsideRoot, root.display().to_string()));
+            }
+        }
+        if self.reject_network && crate::remote::is_network(path) {
+            return Some((Text::NetworkPath, path.display().to_string()));
+        }
+        if self.reject_placeholders && crate::remote::is_placeholder(path) {
+            return Some((Text::CloudPlaceholder, path.display().to_string()));
+        }
+        None
+    }
+
+    /// Checks the directory chosen in a directory dialog as set using
+    /// [DialogOptions::must_exist()], [DialogOptions::must_be_writable()]
+    /// and [DialogOptions::must_be_empty()], returning why it was
+    /// rejected.
+    fn check_dir(&self, dir: &Path) -> Option<Text> {
+        let exists = dir.is_dir();
+        if !exists && (self.dir_must_exist || self.dir_must_be_writable) {
+            return Some(Text::DirMissing);
+        }
+        if self.dir_must_be_empty && exists {
+            let empty = std::fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_none());
+            if !empty {
+                return Some(Text::DirNotEmpty);
+            }
+        }
+        if self.dir_must_be_writable {
+            let probe = dir.join(format!(".im-native-dialog-{}", std::process::id()));
+            let writable = std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&probe)
+                .is_ok();
+            if !writable {
+                return Some(Text::DirNotWritable);
+            }
+            let _ = std::fs::remove_file(&probe);
+        }
+        None
+    }
+
+    /// Dedupes and sorts the files selected in a multiple file dialog
+    /// as set using [DialogOptions::dedupe()] and
+    /// [DialogOptions::sort()].
+    fn arrange(&self, mut paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        if self.dedupe {
+            let mut seen = Vec::with_capacity(paths.len());
+            paths.retain(|path| {
+                let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+                if seen.contains(&canonical) {
+                    false
+                } else {
+                    seen.push(canonical);
+                    true
+                }
+            });
+        }
+
+        match &self.sort {
+            Some(Sort::Order(SortOrder::Name)) => {
+                paths.sort_by(|a, b| a.file_name().cmp(&b.file_name()).then_with(|| a.cmp(b)))
+            }
+            Some(Sort::Order(SortOrder::Modified)) => paths.sort_by_cached_key(|path| {
+                let modified = path.metadata().and_then(|metadata| metadata.modified());
+                (modified.is_err(), modified.ok())
+            }),
+            Some(Sort::By(compare)) => paths.sort_by(|a, b| compare(a, b)),
+            None => {}
+        }
+        paths
+    }
+
+    /// Shows a save dialog using [FileDialog::show_save_single_file()],
+    /// renaming the chosen path if [DialogOptions::auto_increment()]
+    /// was set, and creating its parent directories if
+    /// [DialogOptions::create_parent_dirs()] was set, and then shows
+    /// the information dialog set using [DialogOptions::then_notify()].
+    pub(crate) fn show_save(&self) -> Result<Option<PathBuf>, native_dialog::Error> {
+        let path = self.show_save_unnotified()?;
+        if path.is_some() {
+            self.notify_saved();
+        }
+        Ok(path)
+    }
+
+    /// Like [DialogOptions::show_save()], for callers which show the
+    /// information dialog themselves once the file has been written.
+    pub(crate) fn show_save_unnotified(&self) -> Result<Option<PathBuf>, native_dialog::Error> {
+        if let (Some(template), true) = (&self.filename_template, self.preselect.is_empty()) {
+            let dir = match &self.location {
+                Some(location) => location.clone(),
+                None => std::env::current_dir()?,
+            };
+            let expanded = template::Expanded::new(template, &dir);
+            let mut options = self.clone();
+            options.filename_template = None;
+            options.preselect = vec![dir.join(&expanded.name)];
+            let path = options.show_save_unnotified()?;
+            expanded.saved(path.as_ref());
+            return Ok(path);
+        }
+
+        let path = self.show_or_prompt(Prompt::Save, false, |dialog| {
+            dialog
+                .show_save_single_file()
+                .map(|path| path.into_iter().collect())
+        })?;
+        let path = path.into_iter().next().map(|path| self.increment(path));
+        if self.create_parent_dirs {
+            if let Some(parent) = path.as_deref().and_then(Path::parent) {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        Ok(path)
+    }
+
+    /// Shows an open dialog using [FileDialog::show_open_single_file()].
+    pub(crate) fn show_open_single_file(&self) -> Result<Option<PathBuf>, native_dialog::Error> {
+        let paths = self.show_or_prompt(Prompt::File, false, |dialog| {
+            dialog
+                .show_open_single_file()
+                .map(|path| path.into_iter().collect())
+        })?;
+        Ok(paths.into_iter().next())
+    }
+
+    /// Shows an open dialog using
+    /// [FileDialog::show_open_multiple_file()].
+    pub(crate) fn show_open_multiple_file(&self) -> Result<Vec<PathBuf>, native_dialog::Error> {
+        self.show_open_multiple_file_limited()
+            .map(|(paths, _excess)| paths)
+    }
+
+    /// Shows an open dialog using
+    /// [FileDialog::show_open_multiple_file()], applying
+    /// [DialogOptions::min_files()] and [DialogOptions::max_files()],
+    /// and also returning the files beyond the limit which were
+    /// truncated.
+    pub(crate) fn show_open_multiple_file_limited(
+        &self,
+    ) -> Result<(Vec<PathBuf>, Vec<PathBuf>), native_dialog::Error> {
+        loop {
+            let paths = self.show_or_prompt(Prompt::File, true, |dialog| {
+                dialog.show_open_multiple_file()
+            })?;
+            match self.limit(self.arrange(paths)) {
+                Ok(limited) => return Ok(limited),
+                Err(reason) => self.explain(&reason)?,
+            }
+        }
+    }
+
+    /// Applies [DialogOptions::min_files()] and
+    /// [DialogOptions::max_files()] to `paths`, returning the accepted
+    /// files and the files beyond the limit which were truncated, or
+    /// why the dialog is shown again.
+    fn limit(&self, mut paths: Vec<PathBuf>) -> Result<(Vec<PathBuf>, Vec<PathBuf>), String> {
+        if let Some(min) = self.min_files {
+            if !paths.is_empty() && paths.len() < min {
+                return Err(format!("{} {}.", i18n::text(Text::TooFewFiles), min));
+            }
+        }
+        match self.max_files {
+            Some((max, LimitPolicy::Truncate)) if paths.len() > max => {
+                let excess = paths.split_off(max);
+                Ok((paths, excess))
+            }
+            Some((max, LimitPolicy::Reprompt)) if paths.len() > max => {
+                Err(format!("{} {}.", i18n::text(Text::TooManyFiles), max))
+            }
+            _ => Ok((paths, Vec::new())),
+        }
+    }
+
+    /// Tells the user why the dialog is shown again, on the terminal if
+    /// prompting there, otherwise using
+    /// [MessageDialog::show_alert()].
+    fn explain(&self, text: &str) -> Result<(), native_dialog::Error> {
+        #[cfg(all(feature = "terminal", unix, not(target_os = "macos")))]
+        if crate::terminal::is_available() {
+            eprintln!("{}", text);
+            return Ok(());
+        }
+
+        MessageDialog::new()
+            .set_title(self.title.as_deref().unwrap_or_default())
+            .set_text(text)
+            .set_type(MessageType::Warning)
+            .show_alert()
+    }
+
+    /// Shows an open dialog using [FileDialog::show_open_single_dir()],
+    /// showing it again while the chosen directory fails the checks set
+    /// using [DialogOptions::must_be_writable()] and the like.
+    pub(crate) fn show_open_single_dir(&self) -> Result<Option<PathBuf>, native_dialog::Error> {
+        loop {
+            let paths = self.show_or_prompt(Prompt::Dir, false, |dialog| {
+                dialog
+                    .show_open_single_dir()
+                    .map(|path| path.into_iter().collect())
+            })?;
+            let dir = paths.into_iter().next();
+            match dir.as_deref().and_then(|dir| self.check_dir(dir)) {
+                Some(reason) => self.explain(&format!(
+                    "{}: {}",
+                    i18n::text(reason),
+                    dir.unwrap_or_default().display()
+                ))?,
+                None => return Ok(dir),
+            }
+        }
+    }
+
+    /// Like [DialogOptions::show_anywhere()], showing the dialog again
+    /// while any of the chosen paths is rejected by
+    /// [DialogOptions::restrict_to()] and the like, which are checked
+    /// in the dialog thread.
+    fn show_or_prompt(
+        &self,
+        prompt: Prompt,
+        multiple: bool,
+        show: impl Fn(FileDialog<'_>) -> Result<Vec<PathBuf>, native_dialog::Error>,
+    ) -> Result<Vec<PathBuf>, native_dialog::Error> {
+        let mut options = self.clone();
+        if let Some(root) = &self.root {
+            let inside = options
+                .location
+                .as_deref()
+                .is_some_and(|location| paths::is_within(location, root));
+            if !inside {
+                options.location = Some(root.clone());
+            }
+            options
+                .preselect
+                .retain(|path| paths::is_within(path, root));
+        }
+        loop {
+            let paths = options.show_anywhere(prompt, multiple, &show)?;
+            match paths.iter().find_map(|path| self.reject(path)) {
+                Some((reason, detail)) => {
+                    self.explain(&format!("{}: {}", i18n::text(reason), detail))?
+                }
+                None => return Ok(paths),
+            }
+        }
+    }
+
+    /// Calls `show` with a [FileDialog] using these options, retrying
+    /// transient errors, or with the `terminal` feature prompts on the
+    /// terminal instead when there is no display to show the dialog
+    /// on.
+    fn show_anywhere(
+        &self,
+        prompt: Prompt,
+        multiple: bool,
+        show: &impl Fn(FileDialog<'_>) -> Result<Vec<PathBuf>, native_dialog::Error>,
+    ) -> Result<Vec<PathBuf>, native_dialog::Error> {
+        #[cfg(all(feature = "terminal", unix, not(target_os = "macos")))]
+        if self.backend == Some(Backend::Terminal)
+            || (self.backend.is_none() && crate::terminal::is_available())
+        {
+            return crate::terminal::prompt(self, prompt, multiple)
+                .map(|paths| paths.into_iter().map(|path| self.convert(path)).collect());
+        }
+        #[cfg(not(all(feature = "terminal", unix, not(target_os = "macos"))))]
+        let _ = (prompt, multiple);
+
+        let show_once = || {
+            match self.backend {
+                #[cfg(all(unix, not(target_os = "macos")))]
+                Some(Backend::Portal) => return crate::portal::show(self, prompt, multiple),
+                #[cfg(all(unix, not(target_os = "macos")))]
+                Some(Backend::KDialog) => {
+                    return crate::command::show_file_dialog(
+                        crate::command::GnuProgram::KDialog,
+                        self,
+                        prompt,
+                        multiple,
+                    )
+                }
+                #[cfg(all(unix, not(target_os = "macos")))]
+                Some(Backend::Zenity) => {
+                    return crate::command::show_file_dialog(
+                        crate::command::GnuProgram::Zenity,
+                        self,
+                        prompt,
+                        multiple,
+                    )
+                }
+                #[cfg(target_os = "windows")]
+                Some(Backend::Windows) => return crate::windows::show(self, prompt, multiple),
+                #[cfg(target_os = "macos")]
+                Some(Backend::MacOs) => return crate::macos::show(self, prompt, multiple),
+                Some(Backend::Unavailable) => return Err(native_dialog::Error::NoImplementation),
+                _ => {}
+            }
+            #[cfg(all(unix, not(target_os = "macos")))]
+            if crate::portal::is_sandboxed() {
+                return crate::portal::show(self, prompt, multiple);
+            }
+            #[cfg(target_os = "windows")]
+            if self.windows.is_some()
+                || self.accept_label.is_some()
+                || self.show_hidden
+                || !self.choices.is_empty()
+                || self.selected_filter.is_some()
+                || !self.preselect.is_empty()
+            {
+                return crate::windows::show(self, prompt, multiple);
+            }
+            #[cfg(target_os = "macos")]
+            if self.macos.is_some()
+                || self.accept_label.is_some()
+                || self.show_hidden
+                || self.can_create_dirs.is_some()
+                || !self.choices.is_empty()
+                || !self.preselect.is_empty()
+                || self.theme.is_some()
+                || self.near.is_some()
+            {
+                return crate::macos::show(self, prompt, multiple);
+            }
+            // [native_dialog] runs the dialog program itself, so it
+            // can't be given the environment the hints are passed in.
+            #[cfg(all(unix, not(target_os = "macos")))]
+            if self.locale.is_some() || self.theme.is_some() {
+                if let Some(program) = crate::command::gnu_program() {
+                    return crate::command::show_file_dialog(program, self, prompt, multiple);
+                }
+            }
+            self.show(show)
+        };
+
+        let mut retries = self.retries;
+        loop {
+            match show_once() {
+                Err(
+                    native_dialog::Error::IoFailure(_)
+                    | native_dialog::Error::ImplementationError(_),
+                ) if retries > 0 => {
+                    #[cfg(feature = "log")]
+                    log::warn!("Dialog failed, retrying");
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(retries, "dialog failed, retrying");
+                    retries -= 1;
+                }
+                Err(error) => {
+                    let fallback = match &self.on_backend_failure {
+                        Some(Fallback(fallback)) => fallback,
+                        None => return Err(error),
+                    };
+                    let error = DialogError::from(error);
+                    fallback(&error);
+                    return Err(match error {
+                        DialogError::Io(error) => native_dialog::Error::IoFailure(error),
+                        DialogError::NoImplementation => native_dialog::Error::NoImplementation,
+                        DialogError::Implementation(message) => {
+                            native_dialog::Error::ImplementationError(message)
+                        }
+                    });
+                }
+                Ok(paths) => return Ok(paths.into_iter().map(|path| self.convert(path)).collect()),
+            }
+        }
+    }
+
+    /// Calls `show` with a [FileDialog] using these options, which
+    /// must be shown in the current thread.
+    pub(crate) fn show<R>(&self, show: impl FnOnce(FileDialog<'_>) -> R) -> R {
+        #[cfg(target_os = "windows")]
+        if self.bring_to_front && self.owner.is_none() {
+            crate::foreground::raise_next_window();
+        }
+        #[cfg(target_os = "windows")]
+        if let Some(rect) = self.near {
+            crate::foreground::move_next_window(rect);
+        }
+
+        let dialog = FileDialog::new();
+        let preselection = self.preselection(false);
+        let dialog = match (&preselection, &self.location) {
+            (Some((dir, name)), _) => dialog.set_location(dir).set_filename(name),
+            (None, Some(location)) => dialog.set_location(location),
+            (None, None) => dialog,
+        };
+        let dialog = match &self.title {
+            Some(title) => dialog.set_title(title),
+            None => dialog,
+        };
+        let mut dialog = match self.owner {
+            // Safety: the handle is guaranteed valid until the dialog
+            // closes by the caller of `owner()` or `owner_handle()`.
+            Some(OwnerHandle(handle)) => unsafe { dialog.set_owner_handle(handle) },
+            None => dialog,
+        };
+
+        let extensions: Vec<Vec<&str>> = self
+            .filters
+            .iter()
+            .map(|filter| filter.extensions.iter().map(String::as_str).collect())
+            .collect();
+        for (filter, extensions) in self.filters.iter().zip(&extensions) {
+            dialog = dialog.add_filter(&filter.description, extensions);
+        }
+
+        show(dialog)
+    }
+}
+
+impl From<Option<PathBuf>> for DialogOptions {
+    fn from(location: Option<PathBuf>) -> Self {
+        Self {
+            location,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(count: usize) -> Vec<PathBuf> {
+        (0..count)
+            .map(|index| PathBuf::from(format!("{}.txt", index)))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_files_within_limits() {
+        let options = DialogOptions::new()
+            .min_files(2)
+            .max_files(3, LimitPolicy::Reprompt);
+        assert_eq!(options.limit(paths(2)), Ok((paths(2), Vec::new())));
+        assert_eq!(options.limit(paths(3)), Ok((paths(3), Vec::new())));
+        // Cancelling isn't too few files.
+        assert_eq!(options.limit(Vec::new()), Ok((Vec::new(), Vec::new())));
+    }
+
+    #[test]
+    fn truncates_files_beyond_max() {
+        let options = DialogOptions::new().max_files(2, LimitPolicy::Truncate);
+        let (accepted, excess) = options.limit(paths(4)).unwrap();
+        assert_eq!(accepted, paths(2));
+        assert_eq!(excess, vec![PathBuf::from("2.txt"), PathBuf::from("3.txt")]);
+    }
+
+    #[test]
+    fn reprompts_for_too_many_or_too_few_files() {
+        let options = DialogOptions::new()
+            .min_files(2)
+            .max_files(3, LimitPolicy::Reprompt);
+        assert!(options.limit(paths(4)).unwrap_err().contains('3'));
+        assert!(options.limit(paths(1)).unwrap_err().contains('2'));
+    }
+
+    #[test]
+    fn accepts_any_number_without_limits() {
+        let options = DialogOptions::new();
+        assert_eq!(options.limit(paths(100)), Ok((paths(100), Vec::new())));
+    }
+}
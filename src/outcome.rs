@@ -0,0 +1,80 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use crate::{worker::Selection, DialogError, DialogKind, DialogOptions};
+
+/// The options a dialog was shown with, in a [DialogOutcome]. The
+/// owner window is left out, because it is only valid for the running
+/// process.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionsSnapshot {
+    pub location: Option<PathBuf>,
+    pub title: Option<String>,
+    /// The description and extensions of each filter.
+    pub filters: Vec<(String, Vec<String>)>,
+}
+
+impl From<&DialogOptions> for OptionsSnapshot {
+    fn from(options: &DialogOptions) -> Self {
+        Self {
+            location: options.location.clone(),
+            title: options.title.clone(),
+            filters: options
+                .filters
+                .iter()
+                .map(|filter| (filter.description.clone(), filter.extensions.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// A record of a completed, cancelled or failed dialog, passed to
+/// [crate::DialogObserver::on_outcome()], which can be serialized with
+/// the `serde` feature to log dialog interactions or replay them in
+/// tests.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DialogOutcome {
+    pub kind: DialogKind,
+    /// The options the dialog was shown with, or `None` for dialogs
+    /// which don't take [DialogOptions].
+    pub options: Option<OptionsSnapshot>,
+    /// The paths the user selected, which is empty for dialogs which
+    /// don't select paths.
+    pub paths: Vec<PathBuf>,
+    /// `true` if the user cancelled the dialog.
+    pub cancelled: bool,
+    /// The error the dialog failed with.
+    pub error: Option<String>,
+    /// How long the dialog was open.
+    pub duration: Duration,
+    /// When the dialog was shown.
+    pub timestamp: SystemTime,
+}
+
+impl DialogOutcome {
+    pub(crate) fn new<T: Selection>(
+        kind: DialogKind,
+        options: Option<&DialogOptions>,
+        result: &Result<T, DialogError>,
+        duration: Duration,
+        timestamp: SystemTime,
+    ) -> Self {
+        let (paths, cancelled, error) = match result {
+            Ok(selection) => (selection.paths(), selection.is_cancelled(), None),
+            Err(error) => (Vec::new(), false, Some(error.to_string())),
+        };
+        Self {
+            kind,
+            options: options.map(OptionsSnapshot::from),
+            paths,
+            cancelled,
+            error,
+            duration,
+            timestamp,
+        }
+    }
+}
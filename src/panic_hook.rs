@@ -0,0 +1,82 @@
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, RwLock},
+};
+
+use crate::{DialogKind, DialogOptions};
+
+/// A panic in a dialog thread, passed to the hook set using
+/// [set_panic_hook()].
+#[derive(Debug, Clone)]
+pub struct DialogPanic {
+    pub kind: DialogKind,
+    /// The options the dialog was shown with, or `None` for dialogs
+    /// which don't take [DialogOptions].
+    pub options: Option<DialogOptions>,
+    /// The message the thread panicked with.
+    pub message: String,
+}
+
+type PanicHook = Arc<dyn Fn(&DialogPanic) + Send + Sync>;
+
+static PANIC_HOOK: RwLock<Option<PanicHook>> = RwLock::new(None);
+
+/// Set a hook called in the dialog thread when it panics, replacing
+/// any previous hook, for example to report the panic to a crash
+/// reporter. The panic is caught after calling the hook, and the
+/// dialog returns the default result, as if it had been cancelled.
+pub fn set_panic_hook<F>(hook: F)
+where
+    F: Fn(&DialogPanic) + Send + Sync + 'static,
+{
+    *PANIC_HOOK.write().expect("panic hook lock poisoned") = Some(Arc::new(hook));
+}
+
+/// Remove the hook set using [set_panic_hook()].
+pub fn clear_panic_hook() {
+    *PANIC_HOOK.write().expect("panic hook lock poisoned") = None;
+}
+
+/// Runs `f`, calling the panic hook and returning `None` if it
+/// panics.
+pub(crate) fn catch<R>(
+    kind: DialogKind,
+    options: Option<&DialogOptions>,
+    f: impl FnOnce() -> R,
+) -> Option<R> {
+    let payload = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => return Some(value),
+        Err(payload) => payload,
+    };
+
+    let dialog_panic = DialogPanic {
+        kind,
+        options: options.cloned(),
+        message: message(&*payload),
+    };
+    #[cfg(feature = "log")]
+    log::error!(
+        "{:?} dialog thread panicked: {}",
+        kind,
+        dialog_panic.message
+    );
+    #[cfg(feature = "tracing")]
+    tracing::error!(message = %dialog_panic.message, "dialog thread panicked");
+
+    let hook = PANIC_HOOK.read().expect("panic hook lock poisoned").clone();
+    if let Some(hook) = hook {
+        hook(&dialog_panic);
+    }
+    None
+}
+
+fn message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_owned()
+    }
+}
@@ -0,0 +1,272 @@
+//! Conversions applied to the paths returned by the dialogs, see
+//! [crate::DialogOptions::resolve_symlinks()],
+//! [crate::DialogOptions::long_path_prefix()] and
+//! [crate::DialogOptions::normalize_unicode()].
+
+use std::path::{Path, PathBuf};
+
+/// What happens to the `\\?\` extended-length prefix of the paths
+/// returned on Windows, see [crate::DialogOptions::long_path_prefix()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LongPathPrefix {
+    /// Remove the prefix, for paths shown to users, see
+    /// [strip_long_path_prefix()].
+    Strip,
+    /// Add the prefix, for paths which may be longer than `MAX_PATH`,
+    /// see [add_long_path_prefix()].
+    Add,
+}
+
+impl LongPathPrefix {
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub(crate) fn apply(self, path: PathBuf) -> PathBuf {
+        match self {
+            LongPathPrefix::Strip => strip_long_path_prefix(&path),
+            LongPathPrefix::Add => add_long_path_prefix(&path),
+        }
+    }
+}
+
+/// Removes the `\\?\` extended-length prefix from a Windows path,
+/// such as `\\?\C:\file` to `C:\file` or `\\?\UNC\server\share`
+/// to `\\server\share`. Other paths are returned unchanged, so this
+/// can be applied to any path on any platform.
+pub fn strip_long_path_prefix(path: &Path) -> PathBuf {
+    let text = match path.to_str() {
+        Some(text) => text,
+        None => return path.to_path_buf(),
+    };
+    if let Some(unc) = text.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", unc))
+    } else if let Some(local) = text.strip_prefix(r"\\?\") {
+        PathBuf::from(local)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Adds the `\\?\` extended-length prefix to an absolute Windows
+/// path, such as `C:\file` to `\\?\C:\file` or `\\server\share` to
+/// `\\?\UNC\server\share`, so it can be longer than `MAX_PATH`.
+/// Relative paths, paths which already have the prefix and paths which
+/// aren't Windows paths are returned unchanged.
+pub fn add_long_path_prefix(path: &Path) -> PathBuf {
+    let text = match path.to_str() {
+        Some(text) => text,
+        None => return path.to_path_buf(),
+    };
+    if text.starts_with(r"\\?\") || text.starts_with(r"\\.\") {
+        path.to_path_buf()
+    } else if let Some(unc) = text.strip_prefix(r"\\") {
+        PathBuf::from(format!(r"\\?\UNC\{}", unc))
+    } else if text.as_bytes().get(1) == Some(&b':') && text.as_bytes().get(2) == Some(&b'\\') {
+        PathBuf::from(format!(r"\\?\{}", text))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Resolves the symbolic links in `path`, returning `None` if it has
+/// none or can't be resolved, for example because it doesn't exist
+/// yet.
+pub(crate) fn resolve_symlinks(path: &Path) -> Option<PathBuf> {
+    let resolved = strip_verbatim(path.canonicalize().ok()?);
+    if resolved == path {
+        None
+    } else {
+        Some(resolved)
+    }
+}
+
+/// Returns `true` if `path` is `root` or inside it, once symlinks and
+/// `..` are resolved, where `path` may not exist yet, such as the path
+/// chosen in a save dialog, see [crate::DialogOptions::restrict_to()].
+pub(crate) fn is_within(path: &Path, root: &Path) -> bool {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    // Resolves the closest ancestor which exists, and appends the rest.
+    let mut missing = Vec::new();
+    let mut existing = path;
+    let resolved = loop {
+        match existing.canonicalize() {
+            Ok(resolved) => break resolved,
+            Err(_) => match (existing.parent(), existing.file_name()) {
+                (Some(parent), Some(name)) => {
+                    missing.push(name);
+                    existing = parent;
+                }
+                _ => return false,
+            },
+        }
+    };
+    let resolved = missing
+        .iter()
+        .rev()
+        .fold(resolved, |path, name| path.join(name));
+    resolved.starts_with(root)
+}
+
+/// Removes the `\\?\` prefix which [Path::canonicalize()] adds on
+/// Windows, when the path is valid without it.
+#[cfg(target_os = "windows")]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    let stripped = strip_long_path_prefix(&path);
+    if stripped.as_os_str().len() < 260 {
+        stripped
+    } else {
+        path
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    path
+}
+
+/// Converts `path` to Unicode normalization form C, if it is valid
+/// Unicode.
+#[cfg(target_os = "macos")]
+pub(crate) fn nfc(path: PathBuf) -> PathBuf {
+    use unicode_normalization::UnicodeNormalization;
+
+    match path.to_str() {
+        Some(text) => PathBuf::from(text.nfc().collect::<String>()),
+        None => path,
+    }
+}
+
+/// A chosen path along with a string to show it in the ui, returned by
+/// [crate::ImNativeFileDialog::open_single_file_display()] and the
+/// other `*_display()` methods. Paths which aren't valid Unicode can't
+/// be edited as text without changing them, so check
+/// [DisplayPath::lossy] before creating a path from the edited text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DisplayPath {
+    pub path: PathBuf,
+    /// The path with invalid Unicode replaced by `U+FFFD`.
+    pub display: String,
+    /// `true` if [DisplayPath::display] differs from the path, because
+    /// the path isn't valid Unicode.
+    pub lossy: bool,
+}
+
+impl From<PathBuf> for DisplayPath {
+    fn from(path: PathBuf) -> Self {
+        let (display, lossy) = match path.to_str() {
+            Some(display) => (display.to_owned(), false),
+            None => (path.to_string_lossy().into_owned(), true),
+        };
+        Self {
+            path,
+            display,
+            lossy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_long_path_prefix() {
+        assert_eq!(
+            strip_long_path_prefix(Path::new(r"\\?\C:\dir\file.txt")),
+            PathBuf::from(r"C:\dir\file.txt")
+        );
+        assert_eq!(
+            strip_long_path_prefix(Path::new(r"\\?\UNC\server\share\file.txt")),
+            PathBuf::from(r"\\server\share\file.txt")
+        );
+        for unchanged in [
+            r"C:\file.txt",
+            r"\\server\share",
+            "/home/me/file.txt",
+            "file.txt",
+        ] {
+            assert_eq!(
+                strip_long_path_prefix(Path::new(unchanged)),
+                PathBuf::from(unchanged)
+            );
+        }
+    }
+
+    #[test]
+    fn adds_long_path_prefix() {
+        assert_eq!(
+            add_long_path_prefix(Path::new(r"C:\dir\file.txt")),
+            PathBuf::from(r"\\?\C:\dir\file.txt")
+        );
+        assert_eq!(
+            add_long_path_prefix(Path::new(r"\\server\share\file.txt")),
+            PathBuf::from(r"\\?\UNC\server\share\file.txt")
+        );
+        for unchanged in [
+            r"\\?\C:\file.txt",
+            r"\\.\pipe\name",
+            r"C:file.txt",
+            r"dir\file.txt",
+            "/home/me/file.txt",
+        ] {
+            assert_eq!(
+                add_long_path_prefix(Path::new(unchanged)),
+                PathBuf::from(unchanged)
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips_long_path_prefix() {
+        for path in [r"C:\dir\file.txt", r"\\server\share\file.txt"] {
+            let added = LongPathPrefix::Add.apply(PathBuf::from(path));
+            assert_eq!(LongPathPrefix::Strip.apply(added), PathBuf::from(path));
+        }
+    }
+
+    /// A directory for `name` under the temporary directory, created
+    /// empty.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "im-native-dialog-paths-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn accepts_paths_within_root() {
+        let root = temp_dir("within");
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/file.txt"), b"").unwrap();
+        assert!(is_within(&root, &root));
+        assert!(is_within(&root.join("sub/file.txt"), &root));
+        // Save dialogs return paths which don't exist yet.
+        assert!(is_within(&root.join("sub/new/file.txt"), &root));
+        assert!(is_within(&root.join("sub/../file.txt"), &root));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn rejects_paths_outside_root() {
+        let root = temp_dir("outside");
+        let sibling = temp_dir("outside-sibling");
+        assert!(!is_within(&sibling.join("file.txt"), &root));
+        assert!(!is_within(&root.join("../file.txt"), &root));
+        assert!(!is_within(root.parent().unwrap(), &root));
+        std::fs::remove_dir_all(&root).unwrap();
+        std::fs::remove_dir_all(&sibling).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolves_symlinks_out_of_root() {
+        let root = temp_dir("symlink");
+        let outside = temp_dir("symlink-target");
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        assert!(!is_within(&root.join("link/file.txt"), &root));
+        std::fs::remove_dir_all(&root).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
+}
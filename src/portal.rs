@@ -0,0 +1,513 @@
+use std::{
+    fs::File,
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+/// Returns `true` if the application is running inside a Flatpak
+/// sandbox.
+pub fn is_flatpak() -> bool {
+    Path::new("/.flatpak-info").exists()
+}
+
+/// Returns `true` if the application is running inside a Snap
+/// sandbox.
+pub fn is_snap() -> bool {
+    std::env::var_os("SNAP").is_some() && std::env::var_os("SNAP_NAME").is_some()
+}
+
+/// Returns `true` if the application is running inside a Flatpak or
+/// Snap sandbox, where the dialog programs can't see the user's files,
+/// so file dialogs on GNU/Linux and BSD Unix are always shown using the
+/// xdg-desktop-portal file chooser instead.
+pub fn is_sandboxed() -> bool {
+    is_flatpak() || is_snap()
+}
+
+/// A file exported to a sandboxed application by the Flatpak document
+/// portal, which is how files chosen using the portal's file chooser
+/// are returned. The host path of the file is often not accessible from
+/// inside the sandbox, so it should be accessed using
+/// [PortalDocument::path()], [PortalDocument::open()] or
+/// [PortalDocument::read()].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PortalDocument {
+    id: String,
+    path: PathBuf,
+}
+
+impl PortalDocument {
+    /// The document for `path`, if it is inside the document portal's
+    /// mount at `/run/user/<uid>/doc/<id>/`, otherwise `None`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let mut components = path.components();
+        let prefix = [
+            Component::RootDir,
+            Component::Normal("run".as_ref()),
+            Component::Normal("user".as_ref()),
+        ];
+        if !prefix
+            .iter()
+            .all(|expected| components.next() == Some(*expected))
+        {
+            return None;
+        }
+
+        match (components.next(), components.next(), components.next()) {
+            (
+                Some(Component::Normal(_uid)),
+                Some(Component::Normal(doc)),
+                Some(Component::Normal(id)),
+            ) if doc == "doc" => Some(Self {
+                id: id.to_str()?.to_owned(),
+                path: path.to_path_buf(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// The id of the document in the document portal.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The path of the document inside the sandbox.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The `file://` URI of the document inside the sandbox.
+    pub fn uri(&self) -> String {
+        file_uri(&self.path)
+    }
+
+    /// Open the document for reading.
+    pub fn open(&self) -> io::Result<File> {
+        File::open(&self.path)
+    }
+
+    /// Read the contents of the document.
+    pub fn read(&self) -> io::Result<Vec<u8>> {
+        std::fs::read(&self.path)
+    }
+}
+
+/// The `file://` URI of `path`.
+pub(crate) fn file_uri(path: &Path) -> String {
+    // Paths on Unix are bytes, which aren't always UTF-8.
+    #[cfg(unix)]
+    let bytes = std::os::unix::ffi::OsStrExt::as_bytes(path.as_os_str());
+    #[cfg(not(unix))]
+    let path = path.to_string_lossy();
+    #[cfg(not(unix))]
+    let bytes = path.as_bytes();
+
+    let mut uri = String::from("file://");
+    for &byte in bytes {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Decodes the percent encoded bytes in `uri`, which must be UTF-8.
+pub(crate) fn percent_decode(uri: &str) -> Option<String> {
+    String::from_utf8(percent_decode_bytes(uri)?).ok()
+}
+
+/// Decodes the percent encoded bytes in `uri`.
+fn percent_decode_bytes(uri: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(uri.len());
+    let mut rest = uri.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+const PORTAL: &str = "org.freedesktop.portal.Desktop";
+
+/// Distinguishes the requests made by this process.
+#[cfg(all(unix, not(target_os = "macos")))]
+static TOKENS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Kills the child process when dropped.
+#[cfg(all(unix, not(target_os = "macos")))]
+struct KillOnDrop(std::process::Child);
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn unavailable(reason: &str) -> native_dialog::Error {
+    native_dialog::Error::ImplementationError(format!(
+        "file dialogs inside a Flatpak or Snap sandbox require the xdg-desktop-portal file \
+        chooser, which isn't available: {}",
+        reason
+    ))
+}
+
+/// Quotes `value` as a GVariant string literal.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn variant_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Parses the GVariant string literals at the start of `list`, up to
+/// the end of the array.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn parse_strings(list: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = list.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ']' => break,
+            '\'' | '"' => {
+                let quote = c;
+                let mut string = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => string.extend(chars.next()),
+                        c if c == quote => break,
+                        c => string.push(c),
+                    }
+                }
+                strings.push(string);
+            }
+            _ => {}
+        }
+    }
+    strings
+}
+
+/// Parses the arguments of the `Response` signal of a file chooser
+/// request, as printed by `gdbus monitor`.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn parse_response(response: &str) -> Result<Vec<PathBuf>, native_dialog::Error> {
+    use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+    let code = response
+        .strip_prefix("(uint32 ")
+        .and_then(|rest| rest.split(',').next())
+        .and_then(|code| code.parse::<u32>().ok());
+    match code {
+        Some(0) => {}
+        Some(1) => return Ok(Vec::new()),
+        _ => {
+            return Err(native_dialog::Error::ImplementationError(format!(
+                "the xdg-desktop-portal file chooser failed: {}",
+                response
+            )))
+        }
+    }
+
+    const URIS: &str = "'uris': <[";
+    let uris = match response.find(URIS) {
+        Some(index) => &response[index + URIS.len()..],
+        None => return Ok(Vec::new()),
+    };
+    Ok(parse_strings(uris)
+        .iter()
+        .filter_map(|uri| {
+            let bytes = percent_decode_bytes(uri.strip_prefix("file://")?)?;
+            Some(PathBuf::from(OsString::from_vec(bytes)))
+        })
+        .collect())
+}
+
+/// Returns the version of the xdg-desktop-portal file chooser
+/// interface, or `None` if `gdbus` or the portal isn't available.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) fn file_chooser_version() -> Option<u32> {
+    use std::process::Command;
+
+    which::which("gdbus").ok()?;
+    let output = Command::new("gdbus")
+        .args(["call", "--session", "--timeout", "2", "--dest", PORTAL])
+        .args(["--object-path", "/org/freedesktop/portal/desktop"])
+        .args(["--method", "org.freedesktop.DBus.Properties.Get"])
+        .args(["org.freedesktop.portal.FileChooser", "version"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // The output is like `(<uint32 3>,)`.
+    let output = String::from_utf8_lossy(&output.stdout);
+    let version = output.split("uint32").nth(1)?;
+    version
+        .trim_start()
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Shows the dialog described by `options` and `prompt` using the
+/// xdg-desktop-portal file chooser, returning the chosen paths, which
+/// are empty if the user cancelled the dialog. The portal is called
+/// and its response received using `gdbus`.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub(crate) fn show(
+    options: &crate::DialogOptions,
+    prompt: crate::options::Prompt,
+    multiple: bool,
+) -> Result<Vec<PathBuf>, native_dialog::Error> {
+    use std::{
+        io::{BufRead, BufReader},
+        process::{Command, Stdio},
+        sync::atomic::Ordering,
+    };
+
+    use crate::options::Prompt;
+
+    if which::which("gdbus").is_err() {
+        return Err(unavailable("gdbus isn't installed"));
+    }
+    let token = format!(
+        "im_native_dialog_{}_{}",
+        std::process::id(),
+        TOKENS.fetch_add(1, Ordering::Relaxed)
+    );
+
+    // The response is a signal on the request object, so the monitor
+    // has to be listening before the request is made.
+    let mut monitor = KillOnDrop(
+        Command::new("gdbus")
+            .args(["monitor", "--session", "--dest", PORTAL])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?,
+    );
+    let stdout = monitor.0.stdout.take().expect("monitor stdout is piped");
+    let mut lines = BufReader::new(stdout).lines();
+    // gdbus prints a line once it is monitoring.
+    if lines.next().transpose()?.is_none() {
+        return Err(unavailable("gdbus monitor exited"));
+    }
+
+    let mut entries = vec![format!("'handle_token': <{}>", variant_string(&token))];
+    let method = match prompt {
+        Prompt::Save => "SaveFile",
+        Prompt::File | Prompt::Dir => {
+            entries.push(format!("'multiple': <{}>", multiple));
+            entries.push(format!("'directory': <{}>", prompt == Prompt::Dir));
+            "OpenFile"
+        }
+    };
+    if prompt != Prompt::Dir && !options.filters.is_empty() {
+        let filters: Vec<String> = options
+            .filters
+            .iter()
+            .map(|filter| {
+                let patterns: Vec<String> = filter
+                    .extensions
+                    .iter()
+                    .map(|extension| {
+                        format!(
+                            "(uint32 0, {})",
+                            variant_string(&format!("*.{}", extension))
+                        )
+                    })
+                    .collect();
+                format!(
+                    "({}, [{}])",
+                    variant_string(&filter.description),
+                    patterns.join(", ")
+                )
+            })
+            .collect();
+        entries.push(format!("'filters': <[{}]>", filters.join(", ")));
+    }
+    let preselection = options.preselection(false);
+    let folder = match &preselection {
+        Some((dir, _name)) => Some(*dir),
+        None => options.location.as_deref(),
+    };
+    if let Some(folder) = folder.and_then(Path::to_str) {
+        // The folder is a bytestring.
+        entries.push(format!("'current_folder': <b{}>", variant_string(folder)));
+    }
+    if let (Prompt::Save, Some((_dir, name))) = (prompt, &preselection) {
+        entries.push(format!("'current_name': <{}>", variant_string(name)));
+    }
+    if let Some(label) = &options.accept_label {
+        entries.push(format!("'accept_label': <{}>", variant_string(label)));
+    }
+
+    let output = Command::new("gdbus")
+        .args(["call", "--session", "--dest", PORTAL])
+        .args(["--object-path", "/org/freedesktop/portal/desktop"])
+        .arg("--method")
+        .arg(format!("org.freedesktop.portal.FileChooser.{}", method))
+        .arg("''")
+        .arg(variant_string(options.title.as_deref().unwrap_or_default()))
+        .arg(format!("{{{}}}", entries.join(", ")))
+        .output()?;
+    if !output.status.success() {
+        return Err(unavailable(String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    let signal = format!("/{}: org.freedesktop.portal.Request.Response ", token);
+    for line in lines {
+        let line = line?;
+        if let Some(index) = line.find(&signal) {
+            return parse_response(&line[index + signal.len()..]);
+        }
+    }
+    Err(unavailable("gdbus monitor exited before the response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_file_uris() {
+        assert_eq!(
+            file_uri(Path::new("/home/me/My Files/café.txt")),
+            "file:///home/me/My%20Files/caf%C3%A9.txt"
+        );
+        assert_eq!(file_uri(Path::new("/a-b_c.~d")), "file:///a-b_c.~d");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn encodes_non_utf8_paths_in_file_uris() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let path = Path::new(OsStr::from_bytes(b"/tmp/\xFFname"));
+        assert_eq!(file_uri(path), "file:///tmp/%FFname");
+    }
+
+    #[test]
+    fn decodes_percent_encoding() {
+        assert_eq!(percent_decode("a%20b").as_deref(), Some("a b"));
+        assert_eq!(percent_decode("caf%C3%A9").as_deref(), Some("café"));
+        assert_eq!(percent_decode("caf%c3%a9").as_deref(), Some("café"));
+        assert_eq!(percent_decode("plain").as_deref(), Some("plain"));
+    }
+
+    #[test]
+    fn rejects_invalid_percent_encoding() {
+        assert_eq!(percent_decode("a%2"), None);
+        assert_eq!(percent_decode("a%"), None);
+        assert_eq!(percent_decode("a%zz"), None);
+        // The escape is split inside a multibyte character.
+        assert_eq!(percent_decode("a%C3%"), None);
+        assert_eq!(percent_decode("%FF"), None);
+    }
+
+    #[test]
+    fn finds_portal_documents() {
+        let path = Path::new("/run/user/1000/doc/3a4b5c/report.pdf");
+        let document = PortalDocument::from_path(path).unwrap();
+        assert_eq!(document.id(), "3a4b5c");
+        assert_eq!(document.path(), path);
+        assert_eq!(
+            PortalDocument::from_path(Path::new("/home/me/report.pdf")),
+            None
+        );
+        assert_eq!(
+            PortalDocument::from_path(Path::new("/run/user/1000/doc")),
+            None
+        );
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    mod gdbus {
+        use super::*;
+
+        #[test]
+        fn quotes_variant_strings() {
+            assert_eq!(variant_string("plain"), "'plain'");
+            assert_eq!(variant_string("it's"), "'it\\'s'");
+            assert_eq!(variant_string("a\\b"), "'a\\\\b'");
+            assert_eq!(variant_string("\\'"), "'\\\\\\''");
+        }
+
+        #[test]
+        fn parses_quoted_strings() {
+            assert_eq!(
+                parse_strings("'a', \"it's\", 'say \\'hi\\'', 'back\\\\slash']"),
+                vec!["a", "it's", "say 'hi'", "back\\slash"]
+            );
+            assert_eq!(parse_strings("'a'], 'b']"), vec!["a"]);
+            assert_eq!(parse_strings("]"), Vec::<String>::new());
+        }
+
+        #[test]
+        fn round_trips_variant_strings() {
+            for value in ["it's", "a\\b", "\\'", "\"quoted\""] {
+                let list = format!("{}]", variant_string(value));
+                assert_eq!(parse_strings(&list), vec![value]);
+            }
+        }
+
+        #[test]
+        fn parses_chosen_files() {
+            let response = "(uint32 0, {'uris': <['file:///home/me/a%20b.txt', \
+                'file:///home/me/caf%C3%A9.txt']>, 'choices': <@a(ss) []>})";
+            assert_eq!(
+                parse_response(response).unwrap(),
+                vec![
+                    PathBuf::from("/home/me/a b.txt"),
+                    PathBuf::from("/home/me/café.txt")
+                ]
+            );
+        }
+
+        #[test]
+        fn parses_non_utf8_files() {
+            use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+            let response = "(uint32 0, {'uris': <['file:///tmp/%FFname']>})";
+            assert_eq!(
+                parse_response(response).unwrap(),
+                vec![PathBuf::from(OsStr::from_bytes(b"/tmp/\xFFname"))]
+            );
+        }
+
+        #[test]
+        fn skips_invalid_uris() {
+            let response = "(uint32 0, {'uris': <['file:///a%2', 'https://example.com/b', \
+                'file:///c']>})";
+            assert_eq!(parse_response(response).unwrap(), vec![PathBuf::from("/c")]);
+        }
+
+        #[test]
+        fn returns_nothing_when_cancelled() {
+            assert!(parse_response("(uint32 1, @a{sv} {})").unwrap().is_empty());
+        }
+
+        #[test]
+        fn fails_with_other_response_codes() {
+            assert!(parse_response("(uint32 2, @a{sv} {})").is_err());
+            assert!(parse_response("(uint32 nope, @a{sv} {})").is_err());
+            assert!(parse_response("garbage").is_err());
+        }
+
+        #[test]
+        fn returns_nothing_without_uris() {
+            let response = "(uint32 0, {'choices': <@a(ss) []>})";
+            assert!(parse_response(response).unwrap().is_empty());
+        }
+    }
+}
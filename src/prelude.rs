@@ -0,0 +1,7 @@
+//! The types most applications need to show dialogs, for use with
+//! `use im_native_dialog::prelude::*;`.
+
+pub use crate::{
+    DialogError, DialogOptions, ImNativeColorDialog, ImNativeDialogError, ImNativeFileDialog,
+    ImNativeFontDialog, ImNativeInputDialog, ImNativeMessageDialog, MessageChoice, MessageType,
+};
@@ -0,0 +1,101 @@
+use crate::{channel, DialogError, ImNativeDialogError, Receiver};
+
+/// Performs the expensive part of showing the first dialog in the
+/// background, so the first click of a "Browse" button doesn't wait
+/// for it. Call this once during startup. This runs in a new thread,
+/// and the result is returned through the receiver, which can be
+/// dropped without polling it.
+///
+/// On Windows this initializes COM and loads the common file dialog,
+/// on macOS this loads the AppKit panel classes, and on GNU/Linux and
+/// BSD Unix this loads the `zenity` or `kdialog` program and its
+/// libraries into the page cache.
+pub fn prewarm() -> Result<Receiver<Result<(), DialogError>>, ImNativeDialogError> {
+    let (sender, receiver) = channel::bounded(1);
+    std::thread::Builder::new()
+        .spawn(move || {
+            let result = prewarm_backend();
+            if let Err(_error) = &result {
+                #[cfg(feature = "log")]
+                log::warn!("Error while prewarming the dialog backend: {}", _error);
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_error, "error while prewarming the dialog backend");
+            }
+            let _ = sender.send(result);
+        })
+        .map_err(ImNativeDialogError::SpawnFailed)?;
+    Ok(receiver)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn prewarm_backend() -> Result<(), DialogError> {
+    use crate::command::{self, GnuProgram};
+    use std::process::{Command, Stdio};
+
+    let program = match command::gnu_program() {
+        Some(GnuProgram::KDialog) => "kdialog",
+        Some(GnuProgram::Zenity) => "zenity",
+        None => return Err(DialogError::NoImplementation),
+    };
+    Command::new(program)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn prewarm_backend() -> Result<(), DialogError> {
+    use objc::runtime::Class;
+
+    for name in ["NSOpenPanel", "NSSavePanel"] {
+        if Class::get(name).is_none() {
+            return Err(DialogError::Implementation(format!("no {} class", name)));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn prewarm_backend() -> Result<(), DialogError> {
+    use std::ptr;
+    use winapi::{
+        shared::{winerror::SUCCEEDED, wtypesbase::CLSCTX_INPROC_SERVER},
+        um::{
+            combaseapi::{CoCreateInstance, CoInitializeEx, CoUninitialize},
+            objbase::COINIT_APARTMENTTHREADED,
+            shobjidl_core::CLSID_FileOpenDialog,
+            unknwnbase::IUnknown,
+        },
+        Interface,
+    };
+
+    // Creating the dialog loads the shell libraries it uses, which
+    // stay loaded for the dialogs shown later.
+    unsafe {
+        let initialized = SUCCEEDED(CoInitializeEx(ptr::null_mut(), COINIT_APARTMENTTHREADED));
+        let mut dialog: *mut IUnknown = ptr::null_mut();
+        let result = CoCreateInstance(
+            &CLSID_FileOpenDialog,
+            ptr::null_mut(),
+            CLSCTX_INPROC_SERVER,
+            &IUnknown::uuidof(),
+            &mut dialog as *mut *mut IUnknown as *mut _,
+        );
+        if SUCCEEDED(result) {
+            (*dialog).Release();
+        }
+        if initialized {
+            CoUninitialize();
+        }
+        if !SUCCEEDED(result) {
+            return Err(DialogError::Implementation(format!(
+                "failed to create the file dialog: {:#x}",
+                result
+            )));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    channel,
+    event::{self, DialogEvent, DialogId},
+    DialogKind,
+};
+
+/// How far the dialog thread has got processing a chosen file after
+/// the dialog closed, such as while hashing it, returned by
+/// [crate::ImNativeFileDialog::check_progress()] so the ui can draw a
+/// progress bar until the result arrives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    /// The file being processed.
+    pub path: PathBuf,
+    /// The number of bytes of the file processed so far.
+    pub done: u64,
+    /// The length of the file in bytes.
+    pub total: u64,
+}
+
+impl Progress {
+    /// The fraction of the file processed so far, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        (self.done as f64 / self.total as f64).min(1.0) as f32
+    }
+}
+
+/// The dialog being shown in a thread, which progress is reported for.
+struct Current {
+    id: DialogId,
+    kind: DialogKind,
+    sender: channel::UnboundedSender<Progress>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Current>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` with the dialog `id` of `kind` as the dialog being shown
+/// in this thread, whose progress is sent to `sender`.
+pub(crate) fn with_current<R>(
+    id: DialogId,
+    kind: DialogKind,
+    sender: channel::UnboundedSender<Progress>,
+    f: impl FnOnce() -> R,
+) -> R {
+    /// Restores the previous dialog, even if `f` panics.
+    struct Restore(Option<Current>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+
+    let current = Current { id, kind, sender };
+    let _restore = Restore(CURRENT.with(|previous| previous.borrow_mut().replace(current)));
+    f()
+}
+
+/// Reports that `done` of the `total` bytes of the file at `path` have
+/// been processed, to the dialog being shown in this thread, if any,
+/// and as [DialogEvent::Progress].
+pub(crate) fn report(path: &Path, done: u64, total: u64) {
+    CURRENT.with(|current| {
+        if let Some(current) = &*current.borrow() {
+            let progress = Progress {
+                path: path.to_path_buf(),
+                done,
+                total,
+            };
+            // The receiver is dropped once the result is checked.
+            let _ = current.sender.send(progress.clone());
+            event::emit(DialogEvent::Progress {
+                id: current.id,
+                kind: current.kind,
+                path: progress.path,
+                done,
+                total,
+            });
+        }
+    });
+}
+
+/// Reports the progress of processing one file, only for files large
+/// enough for it to be worth showing.
+pub(crate) struct Reporter<'a> {
+    path: &'a Path,
+    total: u64,
+    reported: u64,
+}
+
+impl<'a> Reporter<'a> {
+    /// How many bytes are processed between reports.
+    const INTERVAL: u64 = 16 * 1024 * 1024;
+
+    pub(crate) fn new(path: &'a Path, total: u64) -> Self {
+        Self {
+            path,
+            total,
+            reported: 0,
+        }
+    }
+
+    /// Reports that `done` bytes have been processed, if the file is
+    /// larger than [Reporter::INTERVAL] and another interval has passed
+    /// since the previous report.
+    pub(crate) fn update(&mut self, done: u64) {
+        if self.total > Self::INTERVAL && done - self.reported >= Self::INTERVAL {
+            report(self.path, done, self.total);
+            self.reported = done;
+        }
+    }
+
+    /// Reports that all of the file has been processed.
+    pub(crate) fn finish(self, done: u64) {
+        if self.total > Self::INTERVAL && self.reported != done {
+            report(self.path, done, self.total);
+        }
+    }
+}
+
+/// Writes `bytes` to the file at `path`, reporting the progress for
+/// large files.
+pub(crate) fn write(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut file = std::fs::File::create(path)?;
+    let mut reporter = Reporter::new(path, bytes.len() as u64);
+    let mut done = 0;
+    for chunk in bytes.chunks(1024 * 1024) {
+        file.write_all(chunk)?;
+        done += chunk.len() as u64;
+        reporter.update(done);
+    }
+    file.flush()?;
+    reporter.finish(done);
+    Ok(())
+}
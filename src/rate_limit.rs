@@ -0,0 +1,187 @@
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{clock, DialogKind};
+
+/// What the `show*()` methods do with the dialogs over the limit set
+/// using [set_rate_limit()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitPolicy {
+    /// The `show*()` methods do nothing and return `Ok` with the token of
+    /// the previous dialog, like with
+    /// [crate::ImNativeFileDialog::with_debounce()], so the excess
+    /// requests are coalesced into the dialogs already shown. A dialog
+    /// which hasn't been shown before has nothing to coalesce into, and
+    /// returns [crate::ImNativeDialogError::RateLimited] instead.
+    Coalesce,
+    /// The `show*()` methods return
+    /// [crate::ImNativeDialogError::RateLimited].
+    Reject,
+}
+
+/// How many dialogs can be opened across the whole process within a
+/// window of time, see [set_rate_limit()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RateLimit {
+    max: usize,
+    window: Duration,
+    policy: RateLimitPolicy,
+}
+
+impl RateLimit {
+    /// Allows at most `max` dialogs to be opened within any `window`,
+    /// coalescing the others.
+    pub fn new(max: usize, window: Duration) -> Self {
+        Self {
+            max,
+            window,
+            policy: RateLimitPolicy::Coalesce,
+        }
+    }
+
+    /// Allows at most `max` dialogs to be opened per second.
+    pub fn per_second(max: usize) -> Self {
+        Self::new(max, Duration::from_secs(1))
+    }
+
+    /// Sets what happens to the dialogs over the limit.
+    pub fn policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+struct Limiter {
+    limit: RateLimit,
+    /// When the dialogs within the last window were opened, oldest
+    /// first.
+    opened: VecDeque<Instant>,
+}
+
+static LIMITER: Mutex<Option<Limiter>> = Mutex::new(None);
+
+/// Limit how frequently dialogs shown by this crate can be opened
+/// across the whole process, or remove the limit with `None`. This
+/// protects the desktop from being flooded with dialogs by a bug which
+/// calls a `show*()` method every frame. Clipboard reads and directory
+/// listings aren't limited, because they don't show a dialog.
+pub fn set_rate_limit(limit: Option<RateLimit>) {
+    *LIMITER.lock().expect("rate limit lock poisoned") = limit.map(|limit| Limiter {
+        limit,
+        opened: VecDeque::new(),
+    });
+}
+
+/// Counts a dialog of `kind` being opened, returning the policy to
+/// apply instead if it is over the limit.
+pub(crate) fn admit(kind: DialogKind) -> Result<(), RateLimitPolicy> {
+    if matches!(kind, DialogKind::Clipboard | DialogKind::ListDir) {
+        return Ok(());
+    }
+    let mut limiter = LIMITER.lock().expect("rate limit lock poisoned");
+    let limiter = match limiter.as_mut() {
+        Some(limiter) => limiter,
+        None => return Ok(()),
+    };
+    let now = clock::now();
+    let window = limiter.limit.window;
+    while limiter
+        .opened
+        .front()
+        .is_some_and(|&opened_at| now.saturating_duration_since(opened_at) >= window)
+    {
+        limiter.opened.pop_front();
+    }
+    if limiter.opened.len() >= limiter.limit.max {
+        #[cfg(feature = "log")]
+        log::warn!(
+            "{:?} dialog not shown, more than {} dialogs were opened within {:?}",
+            kind,
+            limiter.limit.max,
+            window
+        );
+        #[cfg(feature = "tracing")]
+        tracing::warn!(kind = ?kind, max = limiter.limit.max, ?window, "dialog rate limited");
+        return Err(limiter.limit.policy);
+    }
+    limiter.opened.push_back(now);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Removes the rate limit when dropped, even if the test fails.
+    struct Limited;
+
+    impl Limited {
+        fn new(limit: RateLimit) -> Self {
+            set_rate_limit(Some(limit));
+            Self
+        }
+    }
+
+    impl Drop for Limited {
+        fn drop(&mut self) {
+            set_rate_limit(None);
+        }
+    }
+
+    #[test]
+    fn admits_everything_without_limit() {
+        let _serial = crate::serial_test();
+        for _ in 0..100 {
+            assert_eq!(admit(DialogKind::OpenSingleFile), Ok(()));
+        }
+    }
+
+    #[test]
+    fn applies_policy_over_limit() {
+        let _serial = crate::serial_test();
+        let window = Duration::from_secs(3600);
+        let _limited = Limited::new(RateLimit::new(2, window));
+        assert_eq!(admit(DialogKind::OpenSingleFile), Ok(()));
+        assert_eq!(admit(DialogKind::SaveSingleFile), Ok(()));
+        assert_eq!(
+            admit(DialogKind::OpenSingleFile),
+            Err(RateLimitPolicy::Coalesce)
+        );
+
+        let _limited = Limited::new(RateLimit::new(1, window).policy(RateLimitPolicy::Reject));
+        assert_eq!(admit(DialogKind::OpenSingleFile), Ok(()));
+        assert_eq!(
+            admit(DialogKind::OpenSingleFile),
+            Err(RateLimitPolicy::Reject)
+        );
+    }
+
+    #[test]
+    fn admits_again_once_window_passes() {
+        let _serial = crate::serial_test();
+        let _limited = Limited::new(RateLimit::new(1, Duration::from_millis(20)));
+        assert_eq!(admit(DialogKind::OpenSingleFile), Ok(()));
+        assert!(admit(DialogKind::OpenSingleFile).is_err());
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(admit(DialogKind::OpenSingleFile), Ok(()));
+    }
+
+    #[test]
+    fn never_limits_dialogs_which_show_nothing() {
+        let _serial = crate::serial_test();
+        let _limited = Limited::new(RateLimit::new(0, Duration::from_secs(3600)));
+        assert_eq!(admit(DialogKind::Clipboard), Ok(()));
+        assert_eq!(admit(DialogKind::ListDir), Ok(()));
+        assert!(admit(DialogKind::OpenSingleFile).is_err());
+    }
+
+    #[test]
+    fn limits_per_second() {
+        let limit = RateLimit::per_second(3);
+        assert_eq!(limit, RateLimit::new(3, Duration::from_secs(1)));
+        assert_eq!(limit.policy, RateLimitPolicy::Coalesce);
+    }
+}
@@ -0,0 +1,162 @@
+//! Detects paths whose contents aren't stored on this computer, see
+//! [crate::DialogOptions::reject_network_paths()] and
+//! [crate::DialogOptions::reject_cloud_placeholders()].
+
+use std::path::Path;
+
+/// Returns `true` if `path`, or its nearest existing ancestor for a
+/// path which doesn't exist yet, is on a network share, such as a UNC
+/// path or a mapped drive on Windows, or an NFS or SMB mount.
+pub(crate) fn is_network(path: &Path) -> bool {
+    imp::is_network(path)
+}
+
+/// Returns `true` if `path` is a file whose contents are only stored
+/// online by a cloud sync client, such as a OneDrive, Dropbox or
+/// iCloud file which isn't kept on this computer, so reading it blocks
+/// until it is downloaded.
+pub(crate) fn is_placeholder(path: &Path) -> bool {
+    imp::is_placeholder(path)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn existing_ancestor(path: &Path) -> Option<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let existing = path.ancestors().find(|ancestor| ancestor.exists())?;
+    std::ffi::CString::new(existing.as_os_str().as_bytes()).ok()
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::path::Path;
+
+    /// The `f_type` of the network file systems, from `statfs(2)`.
+    const NETWORK_MAGIC: &[u32] = &[
+        0x6969,      // NFS
+        0x517b,      // SMB
+        0xff53_4d42, // CIFS
+        0xfe53_4d42, // SMB2
+        0x564c,      // NCP
+        0x5346_414f, // AFS
+        0x7375_7245, // Coda
+    ];
+
+    pub(super) fn is_network(path: &Path) -> bool {
+        let path = match super::existing_ancestor(path) {
+            Some(path) => path,
+            None => return false,
+        };
+        // Safety: `path` is a valid C string, and `stats` is only read
+        // once statfs has filled it in.
+        let kind = unsafe {
+            let mut stats: libc::statfs = std::mem::zeroed();
+            if libc::statfs(path.as_ptr(), &mut stats) != 0 {
+                return false;
+            }
+            stats.f_type
+        };
+        // The type is signed on some targets, but the magic numbers are
+        // 32 bits.
+        #[allow(clippy::unnecessary_cast)]
+        NETWORK_MAGIC.contains(&(kind as u32))
+    }
+
+    /// The sync clients on GNU/Linux keep whole files, so there are no
+    /// placeholders to detect.
+    pub(super) fn is_placeholder(_path: &Path) -> bool {
+        false
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::{os::macos::fs::MetadataExt, path::Path};
+
+    /// The flag of files whose contents have been evicted by a file
+    /// provider, such as iCloud Drive, from `sys/stat.h`.
+    const SF_DATALESS: u32 = 0x4000_0000;
+
+    pub(super) fn is_network(path: &Path) -> bool {
+        let path = match super::existing_ancestor(path) {
+            Some(path) => path,
+            None => return false,
+        };
+        // Safety: `path` is a valid C string, and `stats` is only read
+        // once statfs has filled it in.
+        unsafe {
+            let mut stats: libc::statfs = std::mem::zeroed();
+            if libc::statfs(path.as_ptr(), &mut stats) != 0 {
+                return false;
+            }
+            stats.f_flags & libc::MNT_LOCAL as u32 == 0
+        }
+    }
+
+    pub(super) fn is_placeholder(path: &Path) -> bool {
+        // Older versions of iCloud Drive replace evicted files with a
+        // hidden `.name.icloud` stub.
+        let stub = path.file_name().is_some_and(|name| {
+            let name = name.to_string_lossy();
+            name.starts_with('.') && name.ends_with(".icloud")
+        });
+        stub || path
+            .symlink_metadata()
+            .is_ok_and(|metadata| metadata.st_flags() & SF_DATALESS != 0)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::{
+        os::windows::fs::MetadataExt,
+        path::{Component, Path, Prefix},
+    };
+
+    use winapi::um::fileapi::GetDriveTypeW;
+
+    /// The drive type of mapped network drives, from `winbase.h`.
+    const DRIVE_REMOTE: u32 = 4;
+    /// The attributes cloud files providers set on files which are
+    /// only stored online, from `winnt.h`.
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x4_0000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x40_0000;
+
+    pub(super) fn is_network(path: &Path) -> bool {
+        let prefix = match path.components().next() {
+            Some(Component::Prefix(prefix)) => prefix.kind(),
+            _ => return false,
+        };
+        let letter = match prefix {
+            Prefix::UNC(..) | Prefix::VerbatimUNC(..) => return true,
+            Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => letter,
+            _ => return false,
+        };
+        let root = [letter as u16, b':' as u16, b'\\' as u16, 0];
+        // Safety: `root` is nul terminated.
+        unsafe { GetDriveTypeW(root.as_ptr()) == DRIVE_REMOTE }
+    }
+
+    pub(super) fn is_placeholder(path: &Path) -> bool {
+        const PLACEHOLDER: u32 = FILE_ATTRIBUTE_OFFLINE
+            | FILE_ATTRIBUTE_RECALL_ON_OPEN
+            | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS;
+        // Reading the attributes doesn't download the file.
+        path.symlink_metadata()
+            .is_ok_and(|metadata| metadata.file_attributes() & PLACEHOLDER != 0)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod imp {
+    use std::path::Path;
+
+    pub(super) fn is_network(_path: &Path) -> bool {
+        false
+    }
+
+    pub(super) fn is_placeholder(_path: &Path) -> bool {
+        false
+    }
+}
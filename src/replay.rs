@@ -0,0 +1,92 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+use crate::{DialogKind, DialogObserver, DialogOutcome};
+
+/// A [DialogObserver] which records the [DialogOutcome] of every
+/// dialog to a file as JSON lines, to be replayed in ui tests using
+/// [start_replay()]. Register it using [crate::set_observer()].
+pub struct Recorder {
+    file: Mutex<File>,
+}
+
+impl Recorder {
+    /// Creates a recorder writing to the file at `path`, replacing the
+    /// file if it exists.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self {
+            file: Mutex::new(File::create(path)?),
+        })
+    }
+}
+
+impl DialogObserver for Recorder {
+    fn on_outcome(&self, outcome: &DialogOutcome) {
+        let mut file = self.file.lock().expect("recorder lock poisoned");
+        let result = serde_json::to_writer(&mut *file, outcome)
+            .map_err(io::Error::from)
+            .and_then(|_| file.write_all(b"\n"));
+        if let Err(_error) = result {
+            #[cfg(feature = "log")]
+            log::error!("Error while recording dialog outcome: {}", _error);
+            #[cfg(feature = "tracing")]
+            tracing::error!(error = %_error, "Error while recording dialog outcome");
+        }
+    }
+}
+
+static REPLAY: Mutex<Option<VecDeque<DialogOutcome>>> = Mutex::new(None);
+
+/// Answers dialogs using the outcomes recorded by a [Recorder] to the
+/// file at `path`, instead of showing them. Each dialog takes the next
+/// recorded outcome of the same [DialogKind], and dialogs are shown as
+/// usual once there are none left.
+pub fn start_replay<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let mut outcomes = VecDeque::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            outcomes.push_back(serde_json::from_str(&line)?);
+        }
+    }
+    *REPLAY.lock().expect("replay lock poisoned") = Some(outcomes);
+    Ok(())
+}
+
+/// Stops answering dialogs started using [start_replay()].
+pub fn stop_replay() {
+    *REPLAY.lock().expect("replay lock poisoned") = None;
+}
+
+/// Answers the next dialog of the same kind using `outcome`, starting
+/// to replay if no recording is being replayed.
+#[cfg(feature = "testing")]
+pub(crate) fn push(outcome: DialogOutcome) {
+    REPLAY
+        .lock()
+        .expect("replay lock poisoned")
+        .get_or_insert_with(VecDeque::new)
+        .push_back(outcome);
+}
+
+/// Takes the next recorded outcome of `kind` being replayed, if
+/// `convert` can recreate a result from it.
+pub(crate) fn next<R>(
+    kind: DialogKind,
+    convert: impl Fn(&DialogOutcome) -> Option<R>,
+) -> Option<R> {
+    let mut replay = REPLAY.lock().expect("replay lock poisoned");
+    let outcomes = replay.as_mut()?;
+    let (index, result) = outcomes
+        .iter()
+        .enumerate()
+        .filter(|(_, outcome)| outcome.kind == kind)
+        .find_map(|(index, outcome)| convert(outcome).map(|result| (index, result)))?;
+    outcomes.remove(index);
+    Some(result)
+}
@@ -0,0 +1,31 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{DialogKind, DialogOptions};
+
+/// Identifies a single call of a `show*()` method, returned by the
+/// method and delivered along with the result using
+/// [crate::ImNativeFileDialog::check_request()], so the application
+/// can tell which request a result belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestToken(u64);
+
+impl RequestToken {
+    pub(crate) fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// The request a result returned by
+/// [crate::ImNativeFileDialog::check_request()] belongs to.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub token: RequestToken,
+    pub kind: DialogKind,
+    /// The options the dialog was shown with, or `None` for dialogs
+    /// which don't take [DialogOptions].
+    pub options: Option<DialogOptions>,
+    /// The priority of the request in the queue, see
+    /// [crate::ImNativeFileDialog::with_priority()].
+    pub priority: i32,
+}
@@ -0,0 +1,39 @@
+use crate::DialogError;
+
+/// The result of a dialog returned by
+/// [crate::ImNativeFileDialog::check_result()], which tells a dialog
+/// the user cancelled apart from one which failed or whose thread
+/// exited without a result, where [crate::ImNativeFileDialog::check()]
+/// returns the same empty result for all of them.
+#[derive(Debug)]
+pub enum DialogResult<T> {
+    /// The user made a selection.
+    Selected(T),
+    /// The user cancelled the dialog, or it was cancelled using
+    /// [crate::ImNativeFileDialog::cancel()] or a
+    /// [crate::CancelToken]. Dialogs shown using
+    /// [crate::ImNativeFileDialog::show()] are never reported as
+    /// cancelled, because the result is sent by the caller.
+    Cancelled,
+    /// The dialog thread exited without sending a result, for example
+    /// because the dialog panicked, whatever the
+    /// [crate::DisconnectPolicy].
+    Disconnected,
+    /// The dialog failed.
+    Failed(DialogError),
+}
+
+impl<T> DialogResult<T> {
+    /// The selection, or `None` if there is none.
+    pub fn selected(self) -> Option<T> {
+        match self {
+            DialogResult::Selected(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the user cancelled the dialog.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, DialogResult::Cancelled)
+    }
+}
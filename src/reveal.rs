@@ -0,0 +1,96 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{channel, DialogError, ImNativeDialogError, Receiver};
+
+/// Opens the platform's file manager with `path` selected, such as
+/// Finder on macOS or Explorer on Windows, for a "Show in folder"
+/// button. This runs in a new thread, and the result is returned
+/// through the receiver ready to be polled by the ui.
+///
+/// On GNU/Linux and BSD Unix the file manager is asked to select the
+/// file using the `org.freedesktop.FileManager1` D-Bus interface, and
+/// if that isn't available the containing directory is opened using
+/// `xdg-open` instead.
+pub fn reveal_in_file_manager(
+    path: impl Into<PathBuf>,
+) -> Result<Receiver<Result<(), DialogError>>, ImNativeDialogError> {
+    let path = path.into();
+    let (sender, receiver) = channel::bounded(1);
+    std::thread::Builder::new()
+        .spawn(move || {
+            let result = reveal(&path);
+            if let Err(_error) = &result {
+                #[cfg(feature = "log")]
+                log::error!("Error while revealing {:?}: {}", path, _error);
+                #[cfg(feature = "tracing")]
+                tracing::error!(path = ?path, error = %_error, "error while revealing path");
+            }
+            // The receiver may have been dropped without polling it.
+            let _ = sender.send(result);
+        })
+        .map_err(ImNativeDialogError::SpawnFailed)?;
+    Ok(receiver)
+}
+
+/// Runs `command`, returning an error with its output if it fails.
+#[cfg(not(target_os = "windows"))]
+fn status(command: &mut Command) -> Result<(), DialogError> {
+    let output = command.output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(DialogError::Implementation(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ))
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn reveal(path: &Path) -> Result<(), DialogError> {
+    let path = path.canonicalize()?;
+    if which::which("dbus-send").is_ok() {
+        let result = status(
+            Command::new("dbus-send")
+                .args([
+                    "--session",
+                    "--print-reply",
+                    "--dest=org.freedesktop.FileManager1",
+                    "/org/freedesktop/FileManager1",
+                    "org.freedesktop.FileManager1.ShowItems",
+                ])
+                .arg(format!("array:string:{}", crate::portal::file_uri(&path)))
+                .arg("string:"),
+        );
+        if result.is_ok() {
+            return result;
+        }
+    }
+
+    let dir = if path.is_dir() {
+        &path
+    } else {
+        path.parent().unwrap_or(&path)
+    };
+    status(Command::new("xdg-open").arg(dir))
+}
+
+#[cfg(target_os = "macos")]
+fn reveal(path: &Path) -> Result<(), DialogError> {
+    status(Command::new("open").arg("-R").arg(path))
+}
+
+#[cfg(target_os = "windows")]
+fn reveal(path: &Path) -> Result<(), DialogError> {
+    use std::os::windows::process::CommandExt;
+
+    let path = path.canonicalize()?;
+    // Explorer parses its own command line, and its exit code doesn't
+    // indicate failure.
+    Command::new("explorer")
+        .raw_arg(format!("/select,\"{}\"", path.display()))
+        .spawn()?;
+    Ok(())
+}
@@ -0,0 +1,295 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use crate::worker::Selection;
+
+type Predicate = Arc<dyn Fn(&Path) -> Result<(), String> + Send + Sync>;
+
+/// Rules applied in the dialog thread to the files selected using
+/// [crate::ImNativeFileDialog::show_open_multiple_file_filtered()],
+/// for limits which the platform's file filters can't express. Files
+/// which break any rule are reported in [FilteredSelection::rejected].
+#[derive(Clone, Default)]
+pub struct SelectionFilter {
+    max_size: Option<u64>,
+    modified_after: Option<SystemTime>,
+    extensions: Option<Vec<String>>,
+    predicates: Vec<Predicate>,
+}
+
+impl fmt::Debug for SelectionFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SelectionFilter")
+            .field("max_size", &self.max_size)
+            .field("modified_after", &self.modified_after)
+            .field("extensions", &self.extensions)
+            .field("predicates", &self.predicates.len())
+            .finish()
+    }
+}
+
+impl SelectionFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject files larger than `bytes`.
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Reject files last modified before `time`.
+    pub fn modified_after(mut self, time: SystemTime) -> Self {
+        self.modified_after = Some(time);
+        self
+    }
+
+    /// Reject files without one of `extensions`, compared ignoring
+    /// case, for example `.extensions(&["png", "jpg"])`.
+    pub fn extensions(mut self, extensions: &[&str]) -> Self {
+        self.extensions = Some(
+            extensions
+                .iter()
+                .map(|extension| extension.to_lowercase())
+                .collect(),
+        );
+        self
+    }
+
+    /// Reject files for which `predicate` returns an error, with the
+    /// error as the reason.
+    pub fn predicate<P>(mut self, predicate: P) -> Self
+    where
+        P: Fn(&Path) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.predicates.push(Arc::new(predicate));
+        self
+    }
+
+    /// Splits `paths` into the accepted and rejected files, where
+    /// `excess` are the files beyond [crate::DialogOptions::max_files()].
+    pub(crate) fn apply(&self, paths: Vec<PathBuf>, excess: Vec<PathBuf>) -> FilteredSelection {
+        let mut selection = FilteredSelection::default();
+        for path in paths {
+            match self.check(&path) {
+                Ok(()) => selection.accepted.push(path),
+                Err(reason) => selection.rejected.push(Rejected { path, reason }),
+            }
+        }
+        selection
+            .rejected
+            .extend(excess.into_iter().map(|path| Rejected {
+                path,
+                reason: RejectReason::TooMany,
+            }));
+        selection
+    }
+
+    fn check(&self, path: &Path) -> Result<(), RejectReason> {
+        if let Some(extensions) = &self.extensions {
+            let extension = path
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_lowercase());
+            if !extension.is_some_and(|extension| extensions.contains(&extension)) {
+                return Err(RejectReason::Extension);
+            }
+        }
+
+        if self.max_size.is_some() || self.modified_after.is_some() {
+            let metadata = path
+                .metadata()
+                .map_err(|error| RejectReason::Unreadable(error.to_string()))?;
+            if let Some(max) = self.max_size {
+                if metadata.len() > max {
+                    return Err(RejectReason::TooLarge {
+                        size: metadata.len(),
+                        max,
+                    });
+                }
+            }
+            if let Some(after) = self.modified_after {
+                let modified = metadata
+                    .modified()
+                    .map_err(|error| RejectReason::Unreadable(error.to_string()))?;
+                if modified < after {
+                    return Err(RejectReason::TooOld { modified });
+                }
+            }
+        }
+
+        for predicate in &self.predicates {
+            predicate(path).map_err(RejectReason::Predicate)?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a file was rejected by a [SelectionFilter].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RejectReason {
+    /// The file is `size` bytes, larger than the `max` set using
+    /// [SelectionFilter::max_size()].
+    TooLarge { size: u64, max: u64 },
+    /// The file was last modified at `modified`, before the time set
+    /// using [SelectionFilter::modified_after()].
+    TooOld { modified: SystemTime },
+    /// The file doesn't have one of the extensions set using
+    /// [SelectionFilter::extensions()].
+    Extension,
+    /// The metadata of the file couldn't be read.
+    Unreadable(String),
+    /// A predicate added using [SelectionFilter::predicate()] rejected
+    /// the file.
+    Predicate(String),
+    /// The file was selected beyond the limit set using
+    /// [crate::DialogOptions::max_files()] with
+    /// [crate::LimitPolicy::Truncate].
+    TooMany,
+}
+
+/// A file rejected by a [SelectionFilter].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejected {
+    pub path: PathBuf,
+    pub reason: RejectReason,
+}
+
+/// The files selected using
+/// [crate::ImNativeFileDialog::show_open_multiple_file_filtered()],
+/// split by the [SelectionFilter], so the ui can tell users which files
+/// were skipped and why. Both are empty if the user cancelled the
+/// dialog.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilteredSelection {
+    pub accepted: Vec<PathBuf>,
+    pub rejected: Vec<Rejected>,
+}
+
+impl Selection for FilteredSelection {
+    fn is_cancelled(&self) -> bool {
+        self.accepted.is_empty() && self.rejected.is_empty()
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        self.accepted.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// A directory for `name` under the temporary directory, created
+    /// empty.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "im-native-dialog-selection-filter-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn accepts_everything_without_rules() {
+        let paths = vec![PathBuf::from("a.txt"), PathBuf::from("missing/b")];
+        let selection = SelectionFilter::new().apply(paths.clone(), Vec::new());
+        assert_eq!(selection.accepted, paths);
+        assert!(selection.rejected.is_empty());
+    }
+
+    #[test]
+    fn compares_extensions_ignoring_case() {
+        let filter = SelectionFilter::new().extensions(&["PNG", "jpg"]);
+        assert_eq!(filter.check(Path::new("a.png")), Ok(()));
+        assert_eq!(filter.check(Path::new("b.JPG")), Ok(()));
+        assert_eq!(
+            filter.check(Path::new("c.gif")),
+            Err(RejectReason::Extension)
+        );
+        assert_eq!(filter.check(Path::new("png")), Err(RejectReason::Extension));
+    }
+
+    #[test]
+    fn rejects_large_and_old_files() {
+        let dir = temp_dir("metadata");
+        let (small, large) = (dir.join("small.bin"), dir.join("large.bin"));
+        std::fs::write(&small, [0; 4]).unwrap();
+        std::fs::write(&large, [0; 16]).unwrap();
+
+        let filter = SelectionFilter::new().max_size(8);
+        assert_eq!(filter.check(&small), Ok(()));
+        assert_eq!(
+            filter.check(&large),
+            Err(RejectReason::TooLarge { size: 16, max: 8 })
+        );
+        assert!(matches!(
+            filter.check(&dir.join("missing.bin")),
+            Err(RejectReason::Unreadable(_))
+        ));
+
+        let future = SystemTime::now() + Duration::from_secs(3600);
+        assert!(matches!(
+            SelectionFilter::new().modified_after(future).check(&small),
+            Err(RejectReason::TooOld { .. })
+        ));
+        assert_eq!(
+            SelectionFilter::new()
+                .modified_after(SystemTime::UNIX_EPOCH)
+                .check(&small),
+            Ok(())
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_predicate_errors() {
+        let filter = SelectionFilter::new()
+            .predicate(|path| match path.starts_with("private") {
+                true => Err("private".to_owned()),
+                false => Ok(()),
+            })
+            .predicate(|_| Ok(()));
+        assert_eq!(filter.check(Path::new("public/a")), Ok(()));
+        assert_eq!(
+            filter.check(Path::new("private/a")),
+            Err(RejectReason::Predicate("private".to_owned()))
+        );
+    }
+
+    #[test]
+    fn splits_selection_and_reports_excess() {
+        let filter = SelectionFilter::new().extensions(&["txt"]);
+        let selection = filter.apply(
+            vec![PathBuf::from("a.txt"), PathBuf::from("b.png")],
+            vec![PathBuf::from("c.txt")],
+        );
+        assert_eq!(selection.accepted, vec![PathBuf::from("a.txt")]);
+        assert_eq!(
+            selection.rejected,
+            vec![
+                Rejected {
+                    path: PathBuf::from("b.png"),
+                    reason: RejectReason::Extension,
+                },
+                Rejected {
+                    path: PathBuf::from("c.txt"),
+                    reason: RejectReason::TooMany,
+                },
+            ]
+        );
+        assert!(!selection.is_cancelled());
+        assert_eq!(selection.paths(), vec![PathBuf::from("a.txt")]);
+        assert!(FilteredSelection::default().is_cancelled());
+    }
+}
@@ -0,0 +1,185 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{DialogError, DialogKind, DialogState, ImNativeFileDialog, Request};
+
+struct Shared<T> {
+    dialog: ImNativeFileDialog<T>,
+    /// The request of the last result returned by
+    /// [SharedDialog::check()].
+    taken: Option<Request>,
+}
+
+/// A cloneable handle to an [ImNativeFileDialog], for uis where the
+/// component which opens the dialog isn't the one which consumes the
+/// result. Clones share the same dialog, and whichever holder first
+/// polls a result takes it, while the others see
+/// [DialogState::Taken].
+pub struct SharedDialog<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+impl<T> Clone for SharedDialog<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Default for SharedDialog<T> {
+    fn default() -> Self {
+        ImNativeFileDialog::default().into()
+    }
+}
+
+impl<T> From<ImNativeFileDialog<T>> for SharedDialog<T> {
+    fn from(dialog: ImNativeFileDialog<T>) -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(Shared {
+                dialog,
+                taken: None,
+            })),
+        }
+    }
+}
+
+impl<T: Send + 'static + Default> SharedDialog<T> {
+    /// Calls `f` with the shared dialog, to show a dialog using one of
+    /// the `show*()` methods or to configure it. Results should be
+    /// polled using [SharedDialog::check()] rather than
+    /// [ImNativeFileDialog::check()], so the other holders see that
+    /// the result was taken.
+    pub fn with<R>(&self, f: impl FnOnce(&mut ImNativeFileDialog<T>) -> R) -> R {
+        let mut shared = self.lock();
+        let result = f(&mut shared.dialog);
+        // Another dialog was shown, whose result hasn't been taken.
+        let token = shared.dialog.worker.request().map(|request| request.token);
+        if token.is_some() && token != shared.taken.as_ref().map(|request| request.token) {
+            shared.taken = None;
+        }
+        result
+    }
+
+    /// Check if the dialog is complete, see
+    /// [ImNativeFileDialog::check()]. The result is only returned to
+    /// the first holder which checks it.
+    pub fn check(&self) -> Option<Result<T, DialogError>> {
+        self.check_request().map(|(_, result)| result)
+    }
+
+    /// Like [SharedDialog::check()], but also returns the [Request]
+    /// the result belongs to, see [ImNativeFileDialog::check_request()].
+    pub fn check_request(&self) -> Option<(Request, Result<T, DialogError>)> {
+        let mut shared = self.lock();
+        let (request, result) = shared.dialog.check_request()?;
+        shared.taken = Some(request.clone());
+        Some((request, result))
+    }
+
+    /// Returns the [DialogState] of the shared dialog, which is
+    /// [DialogState::Taken] once a holder has checked the result of the
+    /// last dialog, until another dialog is shown.
+    pub fn state(&self) -> DialogState {
+        let shared = self.lock();
+        match (shared.dialog.state(), &shared.taken) {
+            (DialogState::Idle, Some(request)) => DialogState::Taken {
+                kind: request.kind,
+                token: request.token,
+            },
+            (state, _) => state,
+        }
+    }
+
+    /// Returns `true` if the shared dialog is currently open, see
+    /// [ImNativeFileDialog::is_open()].
+    pub fn is_open(&self) -> bool {
+        self.lock().dialog.is_open()
+    }
+
+    /// See [ImNativeFileDialog::kind()].
+    pub fn kind(&self) -> Option<DialogKind> {
+        self.lock().dialog.kind()
+    }
+
+    /// See [ImNativeFileDialog::status()].
+    pub fn status(&self) -> Option<String> {
+        self.lock().dialog.status()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Shared<T>> {
+        self.shared.lock().expect("shared dialog lock poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::serial_test as serial;
+
+    type Dialog = SharedDialog<Option<PathBuf>>;
+
+    fn show(dialog: &Dialog, released: mpsc::Receiver<()>) {
+        dialog
+            .with(|dialog| {
+                dialog
+                    .worker
+                    .spawn(DialogKind::OpenSingleFile, None, move || {
+                        let _ = released.recv();
+                        Ok::<_, DialogError>(Some(PathBuf::from("shared.txt")))
+                    })
+            })
+            .unwrap();
+    }
+
+    fn wait(dialog: &Dialog) -> Result<Option<PathBuf>, DialogError> {
+        let started_at = Instant::now();
+        loop {
+            if let Some(result) = dialog.check() {
+                return result;
+            }
+            assert!(started_at.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn result_is_taken_by_first_holder() {
+        let _serial = serial();
+        let dialog = Dialog::default();
+        let other = dialog.clone();
+        let (release, released) = mpsc::channel();
+        show(&dialog, released);
+        drop(release);
+
+        assert_eq!(wait(&dialog).unwrap(), Some(PathBuf::from("shared.txt")));
+        assert!(other.check().is_none());
+        assert!(matches!(
+            other.state(),
+            DialogState::Taken {
+                kind: DialogKind::OpenSingleFile,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn taken_is_cleared_by_next_dialog() {
+        let _serial = serial();
+        let dialog = Dialog::default();
+        let (release, released) = mpsc::channel();
+        show(&dialog, released);
+        drop(release);
+        wait(&dialog).unwrap();
+
+        let (release, released) = mpsc::channel::<()>();
+        show(&dialog, released);
+        assert!(!matches!(dialog.state(), DialogState::Taken { .. }));
+        drop(release);
+        dialog.with(|dialog| dialog.reset());
+        assert!(matches!(dialog.state(), DialogState::Idle));
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    sync::{Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{guard, CancelToken, DialogKind, RequestToken};
+
+/// A dialog whose thread is running.
+struct Running {
+    token: RequestToken,
+    kind: DialogKind,
+    /// Cancelled by [shutdown()], so the result is discarded.
+    abandoned: CancelToken,
+}
+
+static RUNNING: Mutex<Vec<Running>> = Mutex::new(Vec::new());
+/// Notified whenever a dialog thread finishes.
+static FINISHED: Condvar = Condvar::new();
+
+/// A request whose result was discarded by [shutdown()] or
+/// [crate::DialogManager::shutdown()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiscardedRequest {
+    pub token: RequestToken,
+    pub kind: DialogKind,
+    /// The dialog had been shown, rather than waiting in the queue.
+    pub shown: bool,
+    /// The dialog thread finished within the timeout. Otherwise the
+    /// native dialog is still open, and its thread is left running
+    /// until the user closes it or the process exits.
+    pub finished: bool,
+}
+
+/// The result of [shutdown()] or [crate::DialogManager::shutdown()].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// The queued and open requests, which return the same empty
+    /// result as when the user cancels a dialog if they are checked,
+    /// and don't forward their results to a route.
+    pub discarded: Vec<DiscardedRequest>,
+}
+
+impl ShutdownReport {
+    /// Returns `true` if every dialog thread finished within the
+    /// timeout, so none are left behind.
+    pub fn is_clean(&self) -> bool {
+        self.discarded.iter().all(|request| request.finished)
+    }
+}
+
+/// Registers the dialog thread of the request `token`, until the
+/// returned registration is dropped when the thread finishes, along
+/// with the token cancelled if it is abandoned.
+pub(crate) fn register(token: RequestToken, kind: DialogKind) -> (Registration, CancelToken) {
+    let abandoned = CancelToken::new();
+    RUNNING
+        .lock()
+        .expect("shutdown lock poisoned")
+        .push(Running {
+            token,
+            kind,
+            abandoned: abandoned.clone(),
+        });
+    (Registration(token), abandoned)
+}
+
+/// A running dialog thread, see [register()].
+pub(crate) struct Registration(RequestToken);
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        RUNNING
+            .lock()
+            .expect("shutdown lock poisoned")
+            .retain(|running| running.token != self.0);
+        FINISHED.notify_all();
+    }
+}
+
+/// Discards the result of the open dialog of the request `token`,
+/// returning `false` if its thread has finished.
+pub(crate) fn abandon(token: RequestToken) -> bool {
+    RUNNING
+        .lock()
+        .expect("shutdown lock poisoned")
+        .iter()
+        .find(|running| running.token == token)
+        .map(|running| running.abandoned.cancel())
+        .is_some()
+}
+
+/// Waits until the threads of the dialogs in `tokens` have finished,
+/// or `timeout` has passed, returning the dialogs still running.
+pub(crate) fn wait(tokens: &[RequestToken], timeout: Duration) -> Vec<RequestToken> {
+    let deadline = Instant::now() + timeout;
+    let mut running = RUNNING.lock().expect("shutdown lock poisoned");
+    loop {
+        let unfinished: Vec<RequestToken> = tokens
+            .iter()
+            .copied()
+            .filter(|token| running.iter().any(|running| running.token == *token))
+            .collect();
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if unfinished.is_empty() || remaining.is_zero() {
+            return unfinished;
+        }
+        running = FINISHED
+            .wait_timeout(running, remaining)
+            .expect("shutdown lock poisoned")
+            .0;
+    }
+}
+
+/// Discards every queued and open dialog shown by this crate, and
+/// waits up to `timeout` for their threads to finish, so applications
+/// can exit cleanly. Queued dialogs are never shown. Native dialogs
+/// can't be closed by the application, so the threads of the dialogs
+/// the user hasn't closed within the timeout are left running, which
+/// the report lists. Call this before exiting, once the ui has stopped
+/// showing dialogs.
+pub fn shutdown(timeout: Duration) -> ShutdownReport {
+    let mut discarded: Vec<DiscardedRequest> = guard::queued_requests()
+        .into_iter()
+        .filter(|queued| guard::cancel_queued(queued.token))
+        .map(|queued| DiscardedRequest {
+            token: queued.token,
+            kind: queued.kind,
+            shown: false,
+            finished: true,
+        })
+        .collect();
+    let open: Vec<(RequestToken, DialogKind)> = RUNNING
+        .lock()
+        .expect("shutdown lock poisoned")
+        .iter()
+        .map(|running| {
+            running.abandoned.cancel();
+            (running.token, running.kind)
+        })
+        .collect();
+    #[cfg(feature = "log")]
+    log::info!(
+        "Shutting down {} queued and {} open dialogs",
+        discarded.len(),
+        open.len()
+    );
+    #[cfg(feature = "tracing")]
+    tracing::info!(
+        queued = discarded.len(),
+        open = open.len(),
+        "shutting down dialogs"
+    );
+    let tokens: Vec<RequestToken> = open.iter().map(|(token, _)| *token).collect();
+    let unfinished = wait(&tokens, timeout);
+    discarded.extend(open.into_iter().map(|(token, kind)| DiscardedRequest {
+        token,
+        kind,
+        shown: true,
+        finished: !unfinished.contains(&token),
+    }));
+    ShutdownReport { discarded }
+}
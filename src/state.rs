@@ -0,0 +1,42 @@
+use std::time::Instant;
+
+use crate::{DialogKind, RequestToken};
+
+/// The state of an [crate::ImNativeFileDialog], returned by
+/// [crate::ImNativeFileDialog::state()] for debugging and app state
+/// dumps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DialogState {
+    /// No dialog has been requested, or its result has been returned
+    /// by [crate::ImNativeFileDialog::check()].
+    Idle,
+    /// The dialog is waiting for another dialog to close, see
+    /// [crate::GuardPolicy::Queue].
+    Queued {
+        kind: DialogKind,
+        token: RequestToken,
+    },
+    /// The dialog is open.
+    Pending {
+        kind: DialogKind,
+        token: RequestToken,
+        opened_at: Instant,
+    },
+    /// The dialog has closed, and its result is waiting to be returned
+    /// by [crate::ImNativeFileDialog::check()].
+    Completed {
+        kind: DialogKind,
+        token: RequestToken,
+        /// When the dialog was shown, or `None` for results which were
+        /// never shown in a dialog, such as those injected or replayed.
+        opened_at: Option<Instant>,
+    },
+    /// The result of the last dialog has been checked by another holder
+    /// of a [crate::SharedDialog], only returned by
+    /// [crate::SharedDialog::state()].
+    Taken {
+        kind: DialogKind,
+        token: RequestToken,
+    },
+}
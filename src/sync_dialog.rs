@@ -0,0 +1,88 @@
+use std::sync::{Mutex, MutexGuard};
+
+use crate::{DialogError, DialogKind, DialogState, ImNativeFileDialog, Request};
+
+/// An [ImNativeFileDialog] behind an internal lock, which is `Sync`
+/// so it can live in application state shared between the ui thread
+/// and background threads. The lock is only held for the duration of
+/// each method call, none of which wait for the dialog.
+pub struct SyncDialog<T> {
+    dialog: Mutex<ImNativeFileDialog<T>>,
+}
+
+impl<T> Default for SyncDialog<T> {
+    fn default() -> Self {
+        ImNativeFileDialog::default().into()
+    }
+}
+
+impl<T> From<ImNativeFileDialog<T>> for SyncDialog<T> {
+    fn from(dialog: ImNativeFileDialog<T>) -> Self {
+        Self {
+            dialog: Mutex::new(dialog),
+        }
+    }
+}
+
+impl<T: Send + 'static + Default> SyncDialog<T> {
+    /// Calls `f` with the dialog, to show a dialog using one of the
+    /// `show*()` methods or to configure it. The lock is held while
+    /// `f` runs, so avoid blocking in it.
+    pub fn with<R>(&self, f: impl FnOnce(&mut ImNativeFileDialog<T>) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    /// See [ImNativeFileDialog::check()].
+    pub fn check(&self) -> Option<Result<T, DialogError>> {
+        self.lock().check()
+    }
+
+    /// See [ImNativeFileDialog::check_request()].
+    pub fn check_request(&self) -> Option<(Request, Result<T, DialogError>)> {
+        self.lock().check_request()
+    }
+
+    /// See [ImNativeFileDialog::check_context()].
+    pub fn check_context<C: 'static>(&self) -> Option<(Option<C>, Result<T, DialogError>)> {
+        self.lock().check_context()
+    }
+
+    /// See [ImNativeFileDialog::state()].
+    pub fn state(&self) -> DialogState {
+        self.lock().state()
+    }
+
+    /// See [ImNativeFileDialog::is_open()].
+    pub fn is_open(&self) -> bool {
+        self.lock().is_open()
+    }
+
+    /// See [ImNativeFileDialog::kind()].
+    pub fn kind(&self) -> Option<DialogKind> {
+        self.lock().kind()
+    }
+
+    /// See [ImNativeFileDialog::status()].
+    pub fn status(&self) -> Option<String> {
+        self.lock().status()
+    }
+
+    /// See [ImNativeFileDialog::cancel()].
+    pub fn cancel(&self) -> bool {
+        self.lock().cancel()
+    }
+
+    /// See [ImNativeFileDialog::reset()].
+    pub fn reset(&self) -> bool {
+        self.lock().reset()
+    }
+
+    /// Returns the inner dialog, consuming this wrapper.
+    pub fn into_inner(self) -> ImNativeFileDialog<T> {
+        self.dialog.into_inner().expect("sync dialog lock poisoned")
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ImNativeFileDialog<T>> {
+        self.dialog.lock().expect("sync dialog lock poisoned")
+    }
+}
@@ -0,0 +1,216 @@
+//! File names for save dialogs expanded from templates, see
+//! [crate::DialogOptions::filename_template()].
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+static COUNTERS: Mutex<Option<TemplateCounters>> = Mutex::new(None);
+
+/// The next `{counter}` of each file name template, see
+/// [crate::DialogOptions::filename_template()]. With the `serde`
+/// feature, the counters can be saved along with the application's
+/// state using [template_counters()], and restored on the next run
+/// using [set_template_counters()], so the numbers keep counting up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TemplateCounters {
+    next: HashMap<String, u64>,
+}
+
+impl TemplateCounters {
+    /// The number `{counter}` expands to in `template`, which counts up
+    /// from 1.
+    pub fn next(&self, template: &str) -> u64 {
+        self.next.get(template).copied().unwrap_or(1)
+    }
+
+    /// Sets the number `{counter}` expands to in `template`.
+    pub fn set_next(&mut self, template: impl Into<String>, next: u64) {
+        self.next.insert(template.into(), next);
+    }
+}
+
+/// The counters of every file name template, to save them.
+pub fn template_counters() -> TemplateCounters {
+    COUNTERS
+        .lock()
+        .expect("counters lock poisoned")
+        .clone()
+        .unwrap_or_default()
+}
+
+/// Replace the counters of every file name template, such as with the
+/// counters saved in the previous run.
+pub fn set_template_counters(counters: TemplateCounters) {
+    *COUNTERS.lock().expect("counters lock poisoned") = Some(counters);
+}
+
+/// A file name expanded from a template, whose counter is only used up
+/// once the user saves using it.
+pub(crate) struct Expanded {
+    pub(crate) name: String,
+    template: String,
+    counter: Option<u64>,
+}
+
+impl Expanded {
+    /// Expands the tokens of `template` into a file name for a file in
+    /// `dir`, where `{counter}` is the next counter of the template
+    /// for which no file exists yet.
+    pub(crate) fn new(template: &str, dir: &Path) -> Self {
+        let (year, month, day, hour, minute, second) = local_time();
+        let name = template
+            .replace("{date}", &format!("{:04}-{:02}-{:02}", year, month, day))
+            // Colons aren't allowed in file names on Windows.
+            .replace(
+                "{time}",
+                &format!("{:02}-{:02}-{:02}", hour, minute, second),
+            );
+        if !name.contains("{counter}") {
+            return Self {
+                name,
+                template: template.to_owned(),
+                counter: None,
+            };
+        }
+        let first = template_counters().next(template);
+        let (name, counter) = (first..)
+            .map(|counter| (name.replace("{counter}", &counter.to_string()), counter))
+            .find(|(name, _)| !dir.join(name).exists())
+            .expect("unbounded counter");
+        Self {
+            name,
+            template: template.to_owned(),
+            counter: Some(counter),
+        }
+    }
+
+    /// Counts the counter as used if the user saved to `path`, so the
+    /// next file name gets the next number.
+    pub(crate) fn saved(&self, path: Option<&PathBuf>) {
+        if let (Some(counter), Some(_)) = (self.counter, path) {
+            COUNTERS
+                .lock()
+                .expect("counters lock poisoned")
+                .get_or_insert_with(TemplateCounters::default)
+                .set_next(self.template.clone(), counter + 1);
+        }
+    }
+}
+
+/// The local year, month, day, hour, minute and second.
+#[cfg(unix)]
+fn local_time() -> (i32, u32, u32, u32, u32, u32) {
+    // Safety: `localtime_r` only writes to the `tm` passed to it.
+    let tm = unsafe {
+        let now = libc::time(std::ptr::null_mut());
+        let mut tm: libc::tm = std::mem::zeroed();
+        libc::localtime_r(&now, &mut tm);
+        tm
+    };
+    (
+        tm.tm_year + 1900,
+        (tm.tm_mon + 1) as u32,
+        tm.tm_mday as u32,
+        tm.tm_hour as u32,
+        tm.tm_min as u32,
+        tm.tm_sec as u32,
+    )
+}
+
+/// The local year, month, day, hour, minute and second.
+#[cfg(target_os = "windows")]
+fn local_time() -> (i32, u32, u32, u32, u32, u32) {
+    use winapi::um::{minwinbase::SYSTEMTIME, sysinfoapi::GetLocalTime};
+
+    // Safety: `GetLocalTime` only writes to the `SYSTEMTIME` passed to
+    // it.
+    let time = unsafe {
+        let mut time: SYSTEMTIME = std::mem::zeroed();
+        GetLocalTime(&mut time);
+        time
+    };
+    (
+        time.wYear as i32,
+        time.wMonth as u32,
+        time.wDay as u32,
+        time.wHour as u32,
+        time.wMinute as u32,
+        time.wSecond as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A directory for `name` under the temporary directory, created
+    /// empty.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "im-native-dialog-template-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn skips_counters_of_existing_files() {
+        let dir = temp_dir("existing");
+        std::fs::write(dir.join("existing-1.txt"), b"").unwrap();
+        std::fs::write(dir.join("existing-2.txt"), b"").unwrap();
+        let expanded = Expanded::new("existing-{counter}.txt", &dir);
+        assert_eq!(expanded.name, "existing-3.txt");
+        assert_eq!(expanded.counter, Some(3));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn uses_counter_once_saved() {
+        let dir = temp_dir("saved");
+        let template = "saved-{counter}.txt";
+        let expanded = Expanded::new(template, &dir);
+        assert_eq!(expanded.name, "saved-1.txt");
+        // Cancelling the dialog keeps the number for the next one.
+        expanded.saved(None);
+        assert_eq!(template_counters().next(template), 1);
+        expanded.saved(Some(&dir.join(&expanded.name)));
+        assert_eq!(template_counters().next(template), 2);
+        assert_eq!(Expanded::new(template, &dir).name, "saved-2.txt");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn expands_date_and_time() {
+        let dir = temp_dir("date");
+        let expanded = Expanded::new("{date}_{time}.csv", &dir);
+        assert_eq!(expanded.counter, None);
+        // Like `2024-01-31_23-59-59.csv`.
+        let name = expanded.name.as_bytes();
+        assert_eq!(name.len(), "2024-01-31_23-59-59.csv".len());
+        for (index, &byte) in name[..19].iter().enumerate() {
+            match index {
+                4 | 7 | 13 | 16 => assert_eq!(byte, b'-'),
+                10 => assert_eq!(byte, b'_'),
+                _ => assert!(byte.is_ascii_digit()),
+            }
+        }
+        assert!(expanded.name.ends_with(".csv"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn counts_up_from_one() {
+        let mut counters = TemplateCounters::default();
+        assert_eq!(counters.next("a-{counter}"), 1);
+        counters.set_next("a-{counter}", 7);
+        assert_eq!(counters.next("a-{counter}"), 7);
+        assert_eq!(counters.next("b-{counter}"), 1);
+    }
+}
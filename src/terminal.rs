@@ -0,0 +1,131 @@
+//! Prompting for paths on the terminal when there is no display to
+//! show a dialog on, available with the `terminal` feature, see
+//! [crate::DialogOptions].
+
+use std::{
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use native_dialog::Error;
+use rustyline::{
+    completion::FilenameCompleter, error::ReadlineError, history::DefaultHistory, Completer,
+    Editor, Helper, Highlighter, Hinter, Validator,
+};
+
+use crate::{options::Prompt, DialogOptions};
+
+impl Prompt {
+    fn describe(self, multiple: bool) -> &'static str {
+        match (self, multiple) {
+            (Prompt::File, false) => "file to open",
+            (Prompt::File, true) => "files to open",
+            (Prompt::Dir, false) => "directory to open",
+            (Prompt::Dir, true) => "directories to open",
+            (Prompt::Save, _) => "file to save",
+        }
+    }
+
+    fn accepts(self, path: &Path, options: &DialogOptions) -> bool {
+        match self {
+            Prompt::File => path.is_file(),
+            Prompt::Dir => path.is_dir(),
+            Prompt::Save => {
+                !path.is_dir()
+                    && (options.create_parent_dirs || path.parent().is_none_or(Path::is_dir))
+            }
+        }
+    }
+}
+
+#[derive(Helper, Completer, Hinter, Highlighter, Validator)]
+struct PathHelper {
+    #[rustyline(Completer)]
+    completer: FilenameCompleter,
+}
+
+/// Only one dialog can use the terminal at a time.
+static TERMINAL: Mutex<()> = Mutex::new(());
+
+/// Returns `true` if there is no display for [native_dialog] to show a
+/// dialog on, and the application is attached to a terminal.
+pub(crate) fn is_available() -> bool {
+    let has_display = ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty()));
+    !has_display && std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+/// Prompts for paths, one per line, until the user enters an empty
+/// line, or for a single path if `multiple` is `false`. Relative paths
+/// are resolved against the location of `options`. The result is empty
+/// if the user cancelled.
+pub(crate) fn prompt(
+    options: &DialogOptions,
+    prompt: Prompt,
+    multiple: bool,
+) -> Result<Vec<PathBuf>, Error> {
+    let _terminal = TERMINAL.lock().unwrap_or_else(|error| error.into_inner());
+
+    let mut editor: Editor<PathHelper, DefaultHistory> = Editor::new().map_err(readline_error)?;
+    editor.set_helper(Some(PathHelper {
+        completer: FilenameCompleter::new(),
+    }));
+
+    if let Some(title) = &options.title {
+        println!("{}", title);
+    }
+    if multiple {
+        println!(
+            "Enter the {} one per line, Tab completes, an empty line finishes:",
+            prompt.describe(true)
+        );
+    } else {
+        println!(
+            "Enter the {}, Tab completes, an empty line cancels:",
+            prompt.describe(false)
+        );
+    }
+
+    let mut paths = Vec::new();
+    let mut preselect = options.preselect.iter();
+    loop {
+        let initial = preselect
+            .next()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let line = match editor.readline_with_initial("> ", (&initial, "")) {
+            Ok(line) => line,
+            Err(ReadlineError::Eof | ReadlineError::Interrupted) => return Ok(Vec::new()),
+            Err(error) => return Err(readline_error(error)),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(paths);
+        }
+
+        let path = match &options.location {
+            Some(location) => location.join(line),
+            None => PathBuf::from(line),
+        };
+        if !prompt.accepts(&path, options) {
+            println!("Not a {}: {}", prompt.describe(false), path.display());
+            continue;
+        }
+
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+        if !multiple {
+            return Ok(paths);
+        }
+    }
+}
+
+fn readline_error(error: ReadlineError) -> Error {
+    match error {
+        ReadlineError::Io(error) => Error::IoFailure(error),
+        error => Error::ImplementationError(error.to_string()),
+    }
+}
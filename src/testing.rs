@@ -0,0 +1,269 @@
+//! Utilities for testing code which shows dialogs, available with the
+//! `testing` feature. A [MockBackend] answers dialogs with scripted
+//! results instead of showing them, records the dialogs which were
+//! opened, and controls the time used for debouncing and measuring how
+//! long dialogs are open. On GNU/Linux and BSD Unix, a
+//! [VirtualDisplay] runs the code under test with a display in
+//! headless CI.
+
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard,
+    },
+    time::{Duration, SystemTime},
+};
+
+use crate::{clock, replay, DialogKind, DialogOptions, DialogOutcome, OptionsSnapshot};
+
+/// Only one [MockBackend] can be active at a time, because it answers
+/// every dialog in the process.
+static LOCK: Mutex<()> = Mutex::new(());
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+static OPENED: Mutex<Vec<OpenedDialog>> = Mutex::new(Vec::new());
+
+/// A dialog which was opened while a [MockBackend] was active.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenedDialog {
+    pub kind: DialogKind,
+    /// The options the dialog was opened with, or `None` for dialogs
+    /// which don't take [DialogOptions].
+    pub options: Option<OptionsSnapshot>,
+}
+
+/// Answers the dialogs shown by this crate with scripted results
+/// instead of showing them, until it is dropped. Each dialog takes the
+/// next response of the same [DialogKind], like a recording replayed
+/// using [crate::start_replay()], and fails with
+/// [crate::DialogError::Implementation] if there is none, so tests
+/// never block on a real dialog. Dialogs shown using
+/// [crate::ImNativeFileDialog::show()] are shown as usual.
+///
+/// Starting a mock backend waits for any other one to be dropped, so
+/// tests running in parallel take turns.
+pub struct MockBackend {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl MockBackend {
+    pub fn start() -> Self {
+        let lock = LOCK.lock().unwrap_or_else(|error| error.into_inner());
+        crate::stop_replay();
+        OPENED.lock().expect("opened lock poisoned").clear();
+        clock::reset();
+        ACTIVE.store(true, Ordering::Release);
+        Self { _lock: lock }
+    }
+
+    /// Answers the next dialog of `kind` by selecting `paths`, or by
+    /// cancelling it if `paths` is empty.
+    pub fn respond<I, P>(&self, kind: DialogKind, paths: I) -> &Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let paths: Vec<PathBuf> = paths.into_iter().map(Into::into).collect();
+        let cancelled = paths.is_empty();
+        self.respond_with(DialogOutcome {
+            paths,
+            cancelled,
+            ..outcome(kind)
+        })
+    }
+
+    /// Answers the next dialog of `kind` by cancelling it.
+    pub fn cancel(&self, kind: DialogKind) -> &Self {
+        self.respond_with(DialogOutcome {
+            cancelled: true,
+            ..outcome(kind)
+        })
+    }
+
+    /// Fails the next dialog of `kind` with
+    /// [crate::DialogError::Implementation] containing `error`.
+    pub fn fail(&self, kind: DialogKind, error: impl Into<String>) -> &Self {
+        self.respond_with(DialogOutcome {
+            error: Some(error.into()),
+            ..outcome(kind)
+        })
+    }
+
+    /// Answers the next dialog of the same kind using `outcome`, for
+    /// results other than paths.
+    pub fn respond_with(&self, outcome: DialogOutcome) -> &Self {
+        replay::push(outcome);
+        self
+    }
+
+    /// Moves the time used for debouncing and measuring how long
+    /// dialogs are open forward by `duration`, so tests of
+    /// [crate::ImNativeFileDialog::with_debounce()] and
+    /// [crate::ImNativeFileDialog::is_stale()] don't have to sleep. The
+    /// watchdog thread of [crate::ImNativeFileDialog::with_watchdog()]
+    /// still uses the real time.
+    pub fn advance(&self, duration: Duration) -> &Self {
+        clock::advance(duration);
+        self
+    }
+
+    /// The dialogs which have been opened since the mock backend was
+    /// started, in the order they were opened.
+    pub fn opened(&self) -> Vec<OpenedDialog> {
+        OPENED.lock().expect("opened lock poisoned").clone()
+    }
+
+    /// Panics unless a dialog of `kind` has been opened.
+    pub fn assert_dialog_opened(&self, kind: DialogKind) {
+        let opened = self.opened();
+        assert!(
+            opened.iter().any(|dialog| dialog.kind == kind),
+            "no {:?} dialog was opened, the opened dialogs were {:?}",
+            kind,
+            opened
+        );
+    }
+
+    /// Panics unless a dialog of `kind` has been opened with options
+    /// for which `matches` returns `true`, for example
+    /// `mock.assert_dialog_opened_with(DialogKind::OpenSingleFile, |options| options.filters.len() == 2)`.
+    pub fn assert_dialog_opened_with<F>(&self, kind: DialogKind, matches: F)
+    where
+        F: Fn(&OptionsSnapshot) -> bool,
+    {
+        let opened = self.opened();
+        assert!(
+            opened
+                .iter()
+                .filter(|dialog| dialog.kind == kind)
+                .filter_map(|dialog| dialog.options.as_ref())
+                .any(matches),
+            "no {:?} dialog was opened with the expected options, the opened dialogs were {:?}",
+            kind,
+            opened
+        );
+    }
+}
+
+impl Drop for MockBackend {
+    fn drop(&mut self) {
+        ACTIVE.store(false, Ordering::Release);
+        crate::stop_replay();
+        clock::reset();
+    }
+}
+
+fn outcome(kind: DialogKind) -> DialogOutcome {
+    DialogOutcome {
+        kind,
+        options: None,
+        paths: Vec::new(),
+        cancelled: false,
+        error: None,
+        duration: Duration::ZERO,
+        timestamp: SystemTime::now(),
+    }
+}
+
+/// Returns `true` while a [MockBackend] is active.
+pub(crate) fn is_active() -> bool {
+    ACTIVE.load(Ordering::Acquire)
+}
+
+/// Records that a dialog was opened, while a [MockBackend] is active.
+pub(crate) fn record_opened(kind: DialogKind, options: Option<&DialogOptions>) {
+    if is_active() {
+        OPENED
+            .lock()
+            .expect("opened lock poisoned")
+            .push(OpenedDialog {
+                kind,
+                options: options.map(OptionsSnapshot::from),
+            });
+    }
+}
+
+/// Runs an `Xvfb` virtual display and sets `DISPLAY` to it until it is
+/// dropped, for tests which need a display in headless CI, available on
+/// GNU/Linux and BSD Unix. The previous `DISPLAY` is restored when it
+/// is dropped.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub struct VirtualDisplay {
+    xvfb: std::process::Child,
+    display: String,
+    previous: Option<std::ffi::OsString>,
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl VirtualDisplay {
+    /// How long to wait for `Xvfb` to accept connections.
+    const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Starts `Xvfb` on the first free display number from `:99`.
+    pub fn start() -> std::io::Result<Self> {
+        use std::{
+            io,
+            path::Path,
+            process::{Command, Stdio},
+            time::Instant,
+        };
+
+        let number = (99..199)
+            .find(|number| {
+                !Path::new(&format!("/tmp/.X11-unix/X{}", number)).exists()
+                    && !Path::new(&format!("/tmp/.X{}-lock", number)).exists()
+            })
+            .ok_or_else(|| io::Error::other("no free display number"))?;
+        let display = format!(":{}", number);
+        let mut xvfb = Command::new("Xvfb")
+            .arg(&display)
+            .args(["-screen", "0", "1280x1024x24", "-nolisten", "tcp"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let socket = format!("/tmp/.X11-unix/X{}", number);
+        let started_at = Instant::now();
+        while !Path::new(&socket).exists() {
+            if let Some(status) = xvfb.try_wait()? {
+                return Err(io::Error::other(format!("Xvfb exited with {}", status)));
+            }
+            if started_at.elapsed() > Self::STARTUP_TIMEOUT {
+                let _ = xvfb.kill();
+                let _ = xvfb.wait();
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "Xvfb didn't start in time",
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let previous = std::env::var_os("DISPLAY");
+        std::env::set_var("DISPLAY", &display);
+        Ok(Self {
+            xvfb,
+            display,
+            previous,
+        })
+    }
+
+    /// The name of the display, such as `:99`.
+    pub fn display(&self) -> &str {
+        &self.display
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+impl Drop for VirtualDisplay {
+    fn drop(&mut self) {
+        match &self.previous {
+            Some(previous) => std::env::set_var("DISPLAY", previous),
+            None => std::env::remove_var("DISPLAY"),
+        }
+        let _ = self.xvfb.kill();
+        let _ = self.xvfb.wait();
+    }
+}
@@ -0,0 +1,373 @@
+use std::{
+    convert::TryInto,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+/// How much of a file is read to recognize an image and its size.
+const HEADER_LEN: u64 = 512;
+
+/// The largest image decoded for a thumbnail, in pixels, so the pixels
+/// of a file claiming to be a huge image aren't read.
+const MAX_PIXELS: usize = 8192 * 8192;
+
+/// An image decoded into RGBA pixels, such as a thumbnail returned in
+/// a [ThumbnailPath].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    /// Four bytes of red, green, blue and alpha for each pixel, row by
+    /// row from the top left.
+    pub rgba: Vec<u8>,
+}
+
+impl Thumbnail {
+    /// Scales the image down to fit within `max_size` pixels in both
+    /// dimensions, keeping its aspect ratio, by averaging the pixels
+    /// each thumbnail pixel covers.
+    fn fit(self, max_size: u32) -> Self {
+        let max_size = max_size.max(1) as u64;
+        let (width, height) = (self.width as u64, self.height as u64);
+        if width <= max_size && height <= max_size {
+            return self;
+        }
+        let scale = max_size as f64 / width.max(height) as f64;
+        let fit_width = ((width as f64 * scale).round() as u64).clamp(1, max_size);
+        let fit_height = ((height as f64 * scale).round() as u64).clamp(1, max_size);
+
+        let mut rgba = Vec::with_capacity((fit_width * fit_height * 4) as usize);
+        for y in 0..fit_height {
+            let (top, bottom) = (y * height / fit_height, (y + 1) * height / fit_height);
+            for x in 0..fit_width {
+                let (left, right) = (x * width / fit_width, (x + 1) * width / fit_width);
+                let mut sum = [0u64; 4];
+                for source_y in top..bottom {
+                    for source_x in left..right {
+                        let index = ((source_y * width + source_x) * 4) as usize;
+                        for (sum, &value) in sum.iter_mut().zip(&self.rgba[index..index + 4]) {
+                            *sum += value as u64;
+                        }
+                    }
+                }
+                let count = (bottom - top) * (right - left);
+                rgba.extend(sum.iter().map(|sum| (sum / count) as u8));
+            }
+        }
+        Self {
+            width: fit_width as u32,
+            height: fit_height as u32,
+            rgba,
+        }
+    }
+}
+
+/// A path chosen using
+/// [crate::ImNativeFileDialog::open_single_file_thumbnail()] or
+/// [crate::ImNativeFileDialog::show_open_multiple_file_thumbnails()],
+/// along with a thumbnail decoded in the dialog thread, so the ui can
+/// show a preview without decoding the image itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThumbnailPath {
+    pub path: PathBuf,
+    /// The thumbnail, or `None` if the file isn't an image which could
+    /// be decoded.
+    pub thumbnail: Option<Thumbnail>,
+}
+
+impl ThumbnailPath {
+    pub(crate) fn new(path: PathBuf, max_size: u32) -> Self {
+        let thumbnail = decode(&path).map(|image| image.fit(max_size));
+        // Images are decoded at once, so progress is reported per file.
+        let len = path.metadata().map_or(0, |metadata| metadata.len());
+        crate::progress::report(&path, len, len);
+        Self { path, thumbnail }
+    }
+}
+
+/// Decodes the images thumbnails are made from, registered using
+/// [set_image_decoder()], for formats this crate can't decode itself,
+/// for example using the `image` crate.
+pub trait ImageDecoder: Send + Sync {
+    /// Decodes the image at `path` at its full size, or returns `None`
+    /// to decode it using the formats this crate supports.
+    fn decode(&self, path: &Path) -> Option<Thumbnail>;
+}
+
+static DECODER: RwLock<Option<Arc<dyn ImageDecoder>>> = RwLock::new(None);
+
+/// Set the decoder used for the thumbnails of every dialog shown by
+/// this crate from now on, replacing any previous decoder. Without a
+/// decoder, only uncompressed BMP and binary PPM images are decoded.
+pub fn set_image_decoder<D: ImageDecoder + 'static>(decoder: D) {
+    *DECODER.write().expect("decoder lock poisoned") = Some(Arc::new(decoder));
+}
+
+/// Remove the decoder set using [set_image_decoder()].
+pub fn clear_image_decoder() {
+    *DECODER.write().expect("decoder lock poisoned") = None;
+}
+
+/// Decodes the image at `path` using the decoder set using
+/// [set_image_decoder()], or the formats this crate supports.
+fn decode(path: &Path) -> Option<Thumbnail> {
+    let decoder = DECODER.read().expect("decoder lock poisoned").clone();
+    if let Some(image) = decoder.and_then(|decoder| decoder.decode(path)) {
+        let size = image.width as usize * image.height as usize * 4;
+        return (image.rgba.len() == size && size > 0).then_some(image);
+    }
+    let mut file = File::open(path).ok()?;
+    decode_from(&mut file)
+}
+
+/// Decodes an image in the formats this crate supports, reading its
+/// header before the pixels, so files which aren't images or are too
+/// large are rejected without reading all of them.
+fn decode_from<R: Read + Seek>(reader: &mut R) -> Option<Thumbnail> {
+    let mut header = Vec::new();
+    reader
+        .by_ref()
+        .take(HEADER_LEN)
+        .read_to_end(&mut header)
+        .ok()?;
+    match header.get(..2)? {
+        b"BM" => decode_bmp(&header, reader),
+        b"P6" => decode_ppm(&header, reader),
+        _ => None,
+    }
+}
+
+/// Reads the `len` bytes at `offset`, or returns `None` if the file
+/// ends before them.
+fn read_at<R: Read + Seek>(reader: &mut R, offset: usize, len: usize) -> Option<Vec<u8>> {
+    // Checked before allocating, for files claiming more pixels than
+    // they have.
+    let file_len = reader.seek(SeekFrom::End(0)).ok()?;
+    if (offset as u64).checked_add(len as u64)? > file_len {
+        return None;
+    }
+    reader.seek(SeekFrom::Start(offset as u64)).ok()?;
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes).ok()?;
+    Some(bytes)
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+/// Decodes an uncompressed 24 or 32 bit BMP image, whose header is
+/// `bytes`.
+fn decode_bmp<R: Read + Seek>(bytes: &[u8], reader: &mut R) -> Option<Thumbnail> {
+    const COMPRESSION_NONE: u32 = 0;
+
+    let data = u32_at(bytes, 10)? as usize;
+    let width = u32_at(bytes, 18)? as i32;
+    let height = u32_at(bytes, 22)? as i32;
+    let bits = u16_at(bytes, 28)?;
+    if width <= 0 || height == 0 || u32_at(bytes, 30)? != COMPRESSION_NONE {
+        return None;
+    }
+    if bits != 24 && bits != 32 {
+        return None;
+    }
+    let (width, top_down) = (width as usize, height < 0);
+    let height = height.unsigned_abs() as usize;
+    if width.checked_mul(height)? > MAX_PIXELS {
+        return None;
+    }
+    let pixel = bits as usize / 8;
+    // Rows are padded to a multiple of four bytes.
+    let stride = (width * pixel + 3) & !3;
+    let pixels = read_at(reader, data, stride * height)?;
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let row = if top_down { y } else { height - 1 - y };
+        for bgr in pixels[row * stride..][..width * pixel].chunks_exact(pixel) {
+            // The fourth byte of 32 bit images is usually unused.
+            rgba.extend_from_slice(&[bgr[2], bgr[1], bgr[0], u8::MAX]);
+        }
+    }
+    Some(Thumbnail {
+        width: width as u32,
+        height: height as u32,
+        rgba,
+    })
+}
+
+/// Decodes a binary PPM image with at most 8 bits per channel, whose
+/// header is at the start of `bytes`.
+fn decode_ppm<R: Read + Seek>(bytes: &[u8], reader: &mut R) -> Option<Thumbnail> {
+    // The header is the magic number followed by the width, height and
+    // maximum value, separated by whitespace and comments.
+    let mut position = 2;
+    let mut fields = [0usize; 3];
+    for field in &mut fields {
+        loop {
+            match bytes.get(position)? {
+                byte if byte.is_ascii_whitespace() => position += 1,
+                b'#' => {
+                    while *bytes.get(position)? != b'\n' {
+                        position += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+        let start = position;
+        while bytes.get(position)?.is_ascii_digit() {
+            position += 1;
+        }
+        *field = std::str::from_utf8(&bytes[start..position])
+            .ok()?
+            .parse()
+            .ok()?;
+    }
+    let [width, height, max] = fields;
+    if width == 0 || height == 0 || max == 0 || max > u8::MAX as usize {
+        return None;
+    }
+    if width.checked_mul(height)? > MAX_PIXELS {
+        return None;
+    }
+    // A single whitespace character separates the header from the
+    // pixels.
+    let pixels = read_at(reader, position + 1, width * height * 3)?;
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for rgb in pixels.chunks_exact(3) {
+        rgba.extend(rgb.iter().map(|&value| (value as usize * 255 / max) as u8));
+        rgba.push(u8::MAX);
+    }
+    Some(Thumbnail {
+        width: width as u32,
+        height: height as u32,
+        rgba,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// A 24 bit BMP image stored bottom up, with `rows` as the rows
+    /// of blue, green and red bytes from the top.
+    fn bmp(width: u32, height: u32, rows: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"BM");
+        bytes.extend_from_slice(&[0; 8]);
+        bytes.extend_from_slice(&54u32.to_le_bytes());
+        bytes.extend_from_slice(&40u32.to_le_bytes());
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&24u16.to_le_bytes());
+        bytes.extend_from_slice(&[0; 24]);
+        for row in rows.iter().rev() {
+            bytes.extend_from_slice(row);
+            // Rows are padded to a multiple of four bytes.
+            bytes.resize(bytes.len() + (4 - row.len() % 4) % 4, 0);
+        }
+        bytes
+    }
+
+    fn decode_bytes(bytes: Vec<u8>) -> Option<Thumbnail> {
+        decode_from(&mut Cursor::new(bytes))
+    }
+
+    #[test]
+    fn decodes_bmp() {
+        let image = bmp(2, 2, &[&[0, 0, 255, 0, 255, 0], &[255, 0, 0, 1, 2, 3]]);
+        assert_eq!(
+            decode_bytes(image),
+            Some(Thumbnail {
+                width: 2,
+                height: 2,
+                rgba: vec![
+                    255, 0, 0, 255, 0, 255, 0, 255, //
+                    0, 0, 255, 255, 3, 2, 1, 255,
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_ppm() {
+        let mut image = b"P6\n# a comment\n2 1\n255\n".to_vec();
+        image.extend_from_slice(&[255, 0, 0, 0, 128, 255]);
+        assert_eq!(
+            decode_bytes(image),
+            Some(Thumbnail {
+                width: 2,
+                height: 1,
+                rgba: vec![255, 0, 0, 255, 0, 128, 255, 255],
+            })
+        );
+    }
+
+    #[test]
+    fn scales_ppm_with_smaller_maximum() {
+        let mut image = b"P6 1 1 15 ".to_vec();
+        image.extend_from_slice(&[15, 0, 5]);
+        assert_eq!(decode_bytes(image).unwrap().rgba, vec![255, 0, 85, 255]);
+    }
+
+    #[test]
+    fn rejects_truncated_images() {
+        let mut image = bmp(2, 2, &[&[0; 6], &[0; 6]]);
+        image.truncate(image.len() - 1);
+        assert_eq!(decode_bytes(image), None);
+
+        let mut image = b"P6 2 2 255 ".to_vec();
+        image.extend_from_slice(&[0; 11]);
+        assert_eq!(decode_bytes(image), None);
+
+        assert_eq!(decode_bytes(b"B".to_vec()), None);
+    }
+
+    #[test]
+    fn rejects_wrong_magic() {
+        let mut image = bmp(1, 1, &[&[0, 0, 0]]);
+        image[..2].copy_from_slice(b"MB");
+        assert_eq!(decode_bytes(image), None);
+        assert_eq!(decode_bytes(b"P3 1 1 255 0 0 0".to_vec()), None);
+    }
+
+    #[test]
+    fn rejects_images_over_the_size_limit() {
+        // The pixels aren't there, but the header is rejected first.
+        assert_eq!(decode_bytes(bmp(100_000, 100_000, &[])), None);
+        assert_eq!(decode_bytes(b"P6 100000 100000 255 ".to_vec()), None);
+    }
+
+    #[test]
+    fn fits_thumbnail_within_max_size() {
+        let image = Thumbnail {
+            width: 4,
+            height: 2,
+            rgba: [[0, 0, 0, 255], [255, 255, 255, 255]]
+                .iter()
+                .cycle()
+                .take(8)
+                .flatten()
+                .copied()
+                .collect(),
+        };
+        let thumbnail = image.fit(2);
+        assert_eq!((thumbnail.width, thumbnail.height), (2, 1));
+        assert_eq!(thumbnail.rgba, vec![127, 127, 127, 255, 127, 127, 127, 255]);
+    }
+}
@@ -0,0 +1,252 @@
+use std::path::PathBuf;
+
+/// A mounted drive or volume, returned by [volumes()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Volume {
+    /// Where the volume is mounted, such as `C:\` or `/media/usb`.
+    pub path: PathBuf,
+    /// The label of the volume, or `None` if it has none.
+    pub label: Option<String>,
+    /// The size of the volume in bytes, if it could be read.
+    pub total: Option<u64>,
+    /// The space available to the current user in bytes, if it could be
+    /// read.
+    pub free: Option<u64>,
+}
+
+/// Returns the mounted drives and volumes which hold the user's files,
+/// such as the drive letters on Windows, the volumes in `/Volumes` on
+/// macOS, and the disk, removable and network file systems on
+/// GNU/Linux, for applications which show their own picker. Reading
+/// the free space of an unresponsive network volume can block, so this
+/// is best called outside the ui thread.
+pub fn volumes() -> Vec<Volume> {
+    imp::volumes()
+}
+
+#[cfg(unix)]
+fn space(path: &std::path::Path) -> (Option<u64>, Option<u64>) {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let path = match CString::new(path.as_os_str().as_bytes()) {
+        Ok(path) => path,
+        Err(_) => return (None, None),
+    };
+    // Safety: `statvfs` only writes to the struct passed to it.
+    unsafe {
+        let mut stats: libc::statvfs = std::mem::zeroed();
+        if libc::statvfs(path.as_ptr(), &mut stats) != 0 {
+            return (None, None);
+        }
+        let block = stats.f_frsize as u64;
+        (
+            Some(stats.f_blocks as u64 * block),
+            Some(stats.f_bavail as u64 * block),
+        )
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod imp {
+    use std::{collections::HashMap, path::PathBuf};
+
+    use super::{space, Volume};
+
+    /// The file systems on other devices which hold user files.
+    const NETWORK: &[&str] = &["nfs", "nfs4", "cifs", "smbfs", "sshfs", "fuse.sshfs"];
+
+    /// Decodes the octal escapes of spaces and the like in
+    /// `/proc/self/mounts`.
+    fn unescape(field: &str) -> String {
+        let bytes = field.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut index = 0;
+        while index < bytes.len() {
+            let octal = bytes
+                .get(index + 1..index + 4)
+                .and_then(|digits| std::str::from_utf8(digits).ok())
+                .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+            match (bytes[index], octal) {
+                (b'\\', Some(byte)) => {
+                    decoded.push(byte);
+                    index += 4;
+                }
+                (byte, _) => {
+                    decoded.push(byte);
+                    index += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
+    }
+
+    /// The labels of the devices which have one, by device path.
+    fn labels() -> HashMap<PathBuf, String> {
+        let entries = match std::fs::read_dir("/dev/disk/by-label") {
+            Ok(entries) => entries,
+            Err(_) => return HashMap::new(),
+        };
+        entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let device = entry.path().canonicalize().ok()?;
+                let label = unescape(&entry.file_name().to_string_lossy());
+                Some((device, label))
+            })
+            .collect()
+    }
+
+    pub(super) fn volumes() -> Vec<Volume> {
+        let mounts = match std::fs::read_to_string("/proc/self/mounts") {
+            Ok(mounts) => mounts,
+            // Without procfs, such as on BSD Unix, only the root is
+            // listed.
+            Err(_) => {
+                let (total, free) = space("/".as_ref());
+                return vec![Volume {
+                    path: PathBuf::from("/"),
+                    label: None,
+                    total,
+                    free,
+                }];
+            }
+        };
+        let labels = labels();
+        let mut volumes: Vec<Volume> = Vec::new();
+        for line in mounts.lines() {
+            let mut fields = line.split(' ');
+            let (device, path, kind) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(device), Some(path), Some(kind)) => (device, unescape(path), kind),
+                _ => continue,
+            };
+            // Loop devices are mostly read only images, such as snaps.
+            let disk = device.starts_with("/dev/")
+                && !device.starts_with("/dev/loop")
+                && kind != "squashfs";
+            if !disk && !NETWORK.contains(&kind) {
+                continue;
+            }
+            let path = PathBuf::from(path);
+            // Bind mounts and subvolumes repeat the same device.
+            if volumes.iter().any(|volume| volume.path == path) {
+                continue;
+            }
+            let label = std::fs::canonicalize(device)
+                .ok()
+                .and_then(|device| labels.get(&device).cloned());
+            let (total, free) = space(&path);
+            volumes.push(Volume {
+                path,
+                label,
+                total,
+                free,
+            });
+        }
+        volumes
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::path::PathBuf;
+
+    use super::{space, Volume};
+
+    pub(super) fn volumes() -> Vec<Volume> {
+        let entries = match std::fs::read_dir("/Volumes") {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+        let mut volumes: Vec<Volume> = entries
+            .filter_map(Result::ok)
+            .map(|entry| {
+                let path: PathBuf = entry.path();
+                let (total, free) = space(&path);
+                Volume {
+                    label: Some(entry.file_name().to_string_lossy().into_owned()),
+                    path,
+                    total,
+                    free,
+                }
+            })
+            .collect();
+        volumes.sort_by(|a, b| a.label.cmp(&b.label));
+        volumes
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::{ffi::OsString, os::windows::ffi::OsStringExt, path::PathBuf, ptr::null_mut};
+
+    use winapi::{
+        shared::minwindef::{FALSE, MAX_PATH},
+        um::{
+            fileapi::{GetDiskFreeSpaceExW, GetLogicalDriveStringsW, GetVolumeInformationW},
+            winnt::ULARGE_INTEGER,
+        },
+    };
+
+    use super::Volume;
+
+    pub(super) fn volumes() -> Vec<Volume> {
+        // Each drive is like `C:\` followed by a nul, and the list ends
+        // with another nul.
+        let mut drives = vec![0u16; 4 * 26 + 1];
+        let len = unsafe { GetLogicalDriveStringsW(drives.len() as u32, drives.as_mut_ptr()) };
+        if len == 0 || len as usize > drives.len() {
+            return Vec::new();
+        }
+        drives[..len as usize]
+            .split(|&unit| unit == 0)
+            .filter(|drive| !drive.is_empty())
+            .map(|drive| {
+                let root: Vec<u16> = drive.iter().copied().chain(Some(0)).collect();
+                let path = PathBuf::from(OsString::from_wide(drive));
+
+                let mut name = vec![0u16; MAX_PATH + 1];
+                // Safety: the buffers outlive the calls, which only write
+                // within the sizes passed to them.
+                let label = unsafe {
+                    GetVolumeInformationW(
+                        root.as_ptr(),
+                        name.as_mut_ptr(),
+                        name.len() as u32,
+                        null_mut(),
+                        null_mut(),
+                        null_mut(),
+                        null_mut(),
+                        0,
+                    ) != FALSE
+                };
+                let label = label
+                    .then(|| {
+                        let len = name
+                            .iter()
+                            .position(|&unit| unit == 0)
+                            .unwrap_or(name.len());
+                        OsString::from_wide(&name[..len])
+                            .to_string_lossy()
+                            .into_owned()
+                    })
+                    .filter(|label| !label.is_empty());
+
+                let (mut free, mut total): (ULARGE_INTEGER, ULARGE_INTEGER) =
+                    unsafe { std::mem::zeroed() };
+                let space = unsafe {
+                    GetDiskFreeSpaceExW(root.as_ptr(), &mut free, &mut total, null_mut()) != FALSE
+                };
+                let (total, free) = match space {
+                    true => unsafe { (Some(*total.QuadPart()), Some(*free.QuadPart())) },
+                    false => (None, None),
+                };
+                Volume {
+                    path,
+                    label,
+                    total,
+                    free,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use notify::{RecursiveMode, Watcher};
+
+use crate::channel::{self, Receiver};
+
+/// Watches paths for changes using [notify], such as the paths
+/// selected in a dialog shown by an [crate::ImNativeFileDialog] after
+/// [crate::ImNativeFileDialog::with_watcher()], available with the
+/// `watch` feature. Directories are watched recursively. The watch
+/// stops when this is dropped.
+pub struct PathWatcher {
+    paths: Vec<PathBuf>,
+    receiver: Receiver<notify::Result<notify::Event>>,
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl PathWatcher {
+    /// Start watching `paths`.
+    pub fn new<I, P>(paths: I) -> notify::Result<Self>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let (sender, receiver) = channel::unbounded();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            // The receiver is dropped along with the watcher.
+            let _ = sender.send(event);
+        })?;
+
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .collect();
+        for path in &paths {
+            let mode = if path.is_dir() {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            };
+            watcher.watch(path, mode)?;
+        }
+
+        Ok(Self {
+            paths,
+            receiver,
+            _watcher: watcher,
+        })
+    }
+
+    /// The paths being watched.
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Returns the next change to the watched paths, or `None` if
+    /// there are no changes, without blocking. Call this repeatedly
+    /// every frame until it returns `None`.
+    pub fn check(&self) -> Option<notify::Result<notify::Event>> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// The watcher for the paths selected in the last dialog, shared with
+/// the dialog thread which creates it.
+pub(crate) type WatcherSlot = Arc<Mutex<Option<PathWatcher>>>;
+
+/// Replace the watcher in `slot` with one watching `paths`, unless the
+/// user didn't select anything.
+pub(crate) fn watch(slot: &WatcherSlot, paths: Vec<PathBuf>) {
+    if paths.is_empty() {
+        return;
+    }
+    match PathWatcher::new(paths) {
+        Ok(watcher) => *slot.lock().expect("watcher lock poisoned") = Some(watcher),
+        Err(_error) => {
+            #[cfg(feature = "log")]
+            log::error!("Error while watching the selected paths: {}", _error);
+            #[cfg(feature = "tracing")]
+            tracing::error!(error = %_error, "error while watching the selected paths");
+        }
+    }
+}
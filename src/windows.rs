@@ -0,0 +1,469 @@
+//! Windows specific options for the file dialogs, which are shown
+//! using the `IFileDialog` COM interfaces directly when any of them
+//! are set, because [native_dialog] doesn't expose them.
+
+use std::{
+    ffi::{OsStr, OsString},
+    os::windows::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+    ptr::null_mut,
+};
+
+use native_dialog::Error;
+use raw_window_handle::RawWindowHandle;
+use winapi::{
+    shared::{
+        minwindef::{BOOL, DWORD},
+        windef::HWND,
+        winerror::{ERROR_CANCELLED, HRESULT, HRESULT_FROM_WIN32, SUCCEEDED},
+        wtypesbase::CLSCTX_INPROC_SERVER,
+    },
+    um::{
+        combaseapi::{CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize},
+        objbase::COINIT_APARTMENTTHREADED,
+        shobjidl::{
+            IFileDialog, IFileDialogCustomize, IFileOpenDialog, FDAP_BOTTOM, FDAP_TOP,
+            FOS_ALLOWMULTISELECT, FOS_FORCEFILESYSTEM, FOS_FORCESHOWHIDDEN, FOS_NODEREFERENCELINKS,
+            FOS_PICKFOLDERS,
+        },
+        shobjidl_core::{
+            CLSID_FileOpenDialog, CLSID_FileSaveDialog, IShellItem, IShellItemArray,
+            SHCreateItemFromParsingName, SIGDN_FILESYSPATH,
+        },
+        shtypes::COMDLG_FILTERSPEC,
+        unknwnbase::IUnknown,
+    },
+    Interface,
+};
+
+use crate::{
+    choices::Choice,
+    options::{OwnerHandle, Prompt},
+    ChoiceValue, DialogOptions,
+};
+
+/// The options set using [DialogOptionsExtWindows].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WindowsOptions {
+    ok_button_label: Option<String>,
+    /// The places added to the navigation pane, and whether they are
+    /// added at the top.
+    places: Vec<(PathBuf, bool)>,
+    flags: u32,
+}
+
+/// Windows specific [DialogOptions], available on Windows only. Setting
+/// any of them shows the dialog using the `IFileDialog` interfaces of
+/// the shell directly.
+pub trait DialogOptionsExtWindows {
+    /// Sets the label of the OK button, such as "Import", in place of
+    /// [DialogOptions::accept_label()].
+    fn ok_button_label(self, label: impl Into<String>) -> Self;
+
+    /// Adds `path` to the places in the navigation pane of the dialog,
+    /// at the top if `top` is set, otherwise at the bottom.
+    fn add_place(self, path: impl Into<PathBuf>, top: bool) -> Self;
+
+    /// Sets whether shortcuts are returned as chosen, instead of the
+    /// targets they link to, using `FOS_NODEREFERENCELINKS`.
+    fn no_dereference_links(self, no_dereference_links: bool) -> Self;
+
+    /// Adds `FOS_*` flags to the options of the dialog, see
+    /// `IFileDialog::SetOptions()`. `FOS_FORCEFILESYSTEM` is always
+    /// set, because only file system paths can be returned.
+    fn file_dialog_options(self, flags: u32) -> Self;
+}
+
+impl DialogOptionsExtWindows for DialogOptions {
+    fn ok_button_label(mut self, label: impl Into<String>) -> Self {
+        self.windows
+            .get_or_insert_with(WindowsOptions::default)
+            .ok_button_label = Some(label.into());
+        self
+    }
+
+    fn add_place(mut self, path: impl Into<PathBuf>, top: bool) -> Self {
+        self.windows
+            .get_or_insert_with(WindowsOptions::default)
+            .places
+            .push((path.into(), top));
+        self
+    }
+
+    fn no_dereference_links(mut self, no_dereference_links: bool) -> Self {
+        let windows = self.windows.get_or_insert_with(WindowsOptions::default);
+        if no_dereference_links {
+            windows.flags |= FOS_NODEREFERENCELINKS;
+        } else {
+            windows.flags &= !FOS_NODEREFERENCELINKS;
+        }
+        self
+    }
+
+    fn file_dialog_options(mut self, flags: u32) -> Self {
+        self.windows
+            .get_or_insert_with(WindowsOptions::default)
+            .flags |= flags;
+        self
+    }
+}
+
+/// Releases the COM object when dropped.
+struct Com<T>(*mut T);
+
+impl<T> Drop for Com<T> {
+    fn drop(&mut self) {
+        // Safety: every COM interface starts with the methods of
+        // `IUnknown`, and the pointer was returned with a reference.
+        unsafe {
+            (*(self.0 as *mut IUnknown)).Release();
+        }
+    }
+}
+
+/// Uninitializes COM when dropped, if it was initialized.
+struct ComInit(bool);
+
+impl Drop for ComInit {
+    fn drop(&mut self) {
+        if self.0 {
+            unsafe { CoUninitialize() };
+        }
+    }
+}
+
+fn check(result: HRESULT, call: &str) -> Result<(), Error> {
+    if SUCCEEDED(result) {
+        Ok(())
+    } else {
+        Err(Error::ImplementationError(format!(
+            "{} failed: {:#x}",
+            call, result
+        )))
+    }
+}
+
+fn wide(text: impl AsRef<OsStr>) -> Vec<u16> {
+    text.as_ref().encode_wide().chain(Some(0)).collect()
+}
+
+unsafe fn shell_item(path: &Path) -> Result<Com<IShellItem>, Error> {
+    let path = wide(path);
+    let mut item: *mut IShellItem = null_mut();
+    check(
+        SHCreateItemFromParsingName(
+            path.as_ptr(),
+            null_mut(),
+            &IShellItem::uuidof(),
+            &mut item as *mut *mut IShellItem as *mut _,
+        ),
+        "SHCreateItemFromParsingName",
+    )?;
+    Ok(Com(item))
+}
+
+unsafe fn item_path(item: &Com<IShellItem>) -> Result<PathBuf, Error> {
+    let mut name = null_mut();
+    check(
+        (*item.0).GetDisplayName(SIGDN_FILESYSPATH, &mut name),
+        "IShellItem::GetDisplayName",
+    )?;
+    let len = (0..).take_while(|&i| *name.add(i) != 0).count();
+    let path = OsString::from_wide(std::slice::from_raw_parts(name, len));
+    CoTaskMemFree(name as *mut _);
+    Ok(path.into())
+}
+
+/// The ids of the control for the choice at `index`, and of the
+/// visual group holding its label.
+fn control_ids(index: usize) -> (DWORD, DWORD) {
+    let id = index as DWORD * 2;
+    (id, id + 1)
+}
+
+/// Adds the controls for `choices` to `dialog`.
+unsafe fn customize(
+    dialog: &Com<IFileDialog>,
+    choices: &[Choice],
+) -> Result<Com<IFileDialogCustomize>, Error> {
+    let mut customize: *mut IFileDialogCustomize = null_mut();
+    check(
+        (*dialog.0).QueryInterface(
+            &IFileDialogCustomize::uuidof(),
+            &mut customize as *mut *mut IFileDialogCustomize as *mut _,
+        ),
+        "IFileDialog::QueryInterface",
+    )?;
+    let customize = Com(customize);
+    for (index, choice) in choices.iter().enumerate() {
+        let (id, group) = control_ids(index);
+        match choice {
+            Choice::Checkbox { label, checked } => {
+                let label = wide(label);
+                check(
+                    (*customize.0).AddCheckButton(id, label.as_ptr(), *checked as BOOL),
+                    "IFileDialogCustomize::AddCheckButton",
+                )?;
+            }
+            Choice::Combo {
+                label,
+                items,
+                selected,
+            } => {
+                let label = wide(label);
+                check(
+                    (*customize.0).StartVisualGroup(group, label.as_ptr()),
+                    "IFileDialogCustomize::StartVisualGroup",
+                )?;
+                check(
+                    (*customize.0).AddComboBox(id),
+                    "IFileDialogCustomize::AddComboBox",
+                )?;
+                for (item_index, item) in items.iter().enumerate() {
+                    let item = wide(item);
+                    check(
+                        (*customize.0).AddControlItem(id, item_index as DWORD, item.as_ptr()),
+                        "IFileDialogCustomize::AddControlItem",
+                    )?;
+                }
+                check(
+                    (*customize.0).SetSelectedControlItem(id, *selected as DWORD),
+                    "IFileDialogCustomize::SetSelectedControlItem",
+                )?;
+                check(
+                    (*customize.0).EndVisualGroup(),
+                    "IFileDialogCustomize::EndVisualGroup",
+                )?;
+            }
+        }
+    }
+    Ok(customize)
+}
+
+/// Reads the values of the controls added by [customize()].
+unsafe fn choice_values(
+    customize: &Com<IFileDialogCustomize>,
+    choices: &[Choice],
+) -> Result<Vec<ChoiceValue>, Error> {
+    choices
+        .iter()
+        .enumerate()
+        .map(|(index, choice)| {
+            let (id, _group) = control_ids(index);
+            match choice {
+                Choice::Checkbox { .. } => {
+                    let mut checked: BOOL = 0;
+                    check(
+                        (*customize.0).GetCheckButtonState(id, &mut checked),
+                        "IFileDialogCustomize::GetCheckButtonState",
+                    )?;
+                    Ok(ChoiceValue::Checked(checked != 0))
+                }
+                Choice::Combo { .. } => {
+                    let mut item: DWORD = 0;
+                    check(
+                        (*customize.0).GetSelectedControlItem(id, &mut item),
+                        "IFileDialogCustomize::GetSelectedControlItem",
+                    )?;
+                    Ok(ChoiceValue::Selected(item as usize))
+                }
+            }
+        })
+        .collect()
+}
+
+/// Shows the dialog described by `options` and `prompt` using
+/// `IFileDialog`, returning the chosen paths, which are empty if the
+/// user cancelled the dialog.
+pub(crate) fn show(
+    options: &DialogOptions,
+    prompt: Prompt,
+    multiple: bool,
+) -> Result<Vec<PathBuf>, Error> {
+    let default_windows = WindowsOptions::default();
+    let windows = options.windows.as_ref().unwrap_or(&default_windows);
+
+    // Declared first, so COM is uninitialized after the objects are
+    // released.
+    let _init = ComInit(SUCCEEDED(unsafe {
+        CoInitializeEx(null_mut(), COINIT_APARTMENTTHREADED)
+    }));
+
+    // Safety: the objects are only used while their references are
+    // held, and the strings passed to them outlive the calls.
+    unsafe {
+        let class = match prompt {
+            Prompt::Save => &CLSID_FileSaveDialog,
+            Prompt::File | Prompt::Dir => &CLSID_FileOpenDialog,
+        };
+        let mut dialog: *mut IFileDialog = null_mut();
+        check(
+            CoCreateInstance(
+                class,
+                null_mut(),
+                CLSCTX_INPROC_SERVER,
+                &IFileDialog::uuidof(),
+                &mut dialog as *mut *mut IFileDialog as *mut _,
+            ),
+            "CoCreateInstance",
+        )?;
+        let dialog = Com(dialog);
+
+        let mut flags = 0;
+        check(
+            (*dialog.0).GetOptions(&mut flags),
+            "IFileDialog::GetOptions",
+        )?;
+        flags |= FOS_FORCEFILESYSTEM | windows.flags;
+        if prompt == Prompt::Dir {
+            flags |= FOS_PICKFOLDERS;
+        }
+        if multiple {
+            flags |= FOS_ALLOWMULTISELECT;
+        }
+        if options.show_hidden {
+            flags |= FOS_FORCESHOWHIDDEN;
+        }
+        check((*dialog.0).SetOptions(flags), "IFileDialog::SetOptions")?;
+
+        if let Some(title) = &options.title {
+            let title = wide(title);
+            check(
+                (*dialog.0).SetTitle(title.as_ptr()),
+                "IFileDialog::SetTitle",
+            )?;
+        }
+        let label = windows
+            .ok_button_label
+            .as_ref()
+            .or(options.accept_label.as_ref());
+        if let Some(label) = label {
+            let label = wide(label);
+            check(
+                (*dialog.0).SetOkButtonLabel(label.as_ptr()),
+                "IFileDialog::SetOkButtonLabel",
+            )?;
+        }
+        // Locations and places which don't exist are skipped.
+        let preselection = options.preselection(multiple && options.preselect.len() > 1);
+        let location = match &preselection {
+            Some((dir, _name)) => Some(*dir),
+            None => options.location.as_deref(),
+        };
+        if let Some(Ok(folder)) = location.map(|path| shell_item(path)) {
+            check((*dialog.0).SetFolder(folder.0), "IFileDialog::SetFolder")?;
+        }
+        if let Some((_dir, name)) = &preselection {
+            let name = wide(name);
+            check(
+                (*dialog.0).SetFileName(name.as_ptr()),
+                "IFileDialog::SetFileName",
+            )?;
+        }
+        for (place, top) in &windows.places {
+            if let Ok(item) = shell_item(place) {
+                let position = if *top { FDAP_TOP } else { FDAP_BOTTOM };
+                check(
+                    (*dialog.0).AddPlace(item.0, position),
+                    "IFileDialog::AddPlace",
+                )?;
+            }
+        }
+
+        if prompt != Prompt::Dir && !options.filters.is_empty() {
+            let strings: Vec<(Vec<u16>, Vec<u16>)> = options
+                .filters
+                .iter()
+                .map(|filter| {
+                    let spec: Vec<String> = filter
+                        .extensions
+                        .iter()
+                        .map(|extension| format!("*.{}", extension))
+                        .collect();
+                    (wide(&filter.description), wide(spec.join(";")))
+                })
+                .collect();
+            let specs: Vec<COMDLG_FILTERSPEC> = strings
+                .iter()
+                .map(|(name, spec)| COMDLG_FILTERSPEC {
+                    pszName: name.as_ptr(),
+                    pszSpec: spec.as_ptr(),
+                })
+                .collect();
+            check(
+                (*dialog.0).SetFileTypes(specs.len() as u32, specs.as_ptr()),
+                "IFileDialog::SetFileTypes",
+            )?;
+        }
+
+        let customize = match options.choices.is_empty() {
+            true => None,
+            false => Some(customize(&dialog, &options.choices)?),
+        };
+
+        let owner = match options.owner {
+            Some(OwnerHandle(RawWindowHandle::Win32(handle))) => handle.hwnd as HWND,
+            _ => null_mut(),
+        };
+        if options.bring_to_front && options.owner.is_none() {
+            crate::foreground::raise_next_window();
+        }
+        if let Some(rect) = options.near {
+            crate::foreground::move_next_window(rect);
+        }
+        let result = (*dialog.0).Show(owner);
+        if result == HRESULT_FROM_WIN32(ERROR_CANCELLED) {
+            return Ok(Vec::new());
+        }
+        check(result, "IFileDialog::Show")?;
+        if let Some(customize) = &customize {
+            options.store_choices(choice_values(customize, &options.choices)?);
+        }
+        if options.selected_filter.is_some() && prompt != Prompt::Dir && !options.filters.is_empty()
+        {
+            // The index is one-based.
+            let mut index = 0;
+            check(
+                (*dialog.0).GetFileTypeIndex(&mut index),
+                "IFileDialog::GetFileTypeIndex",
+            )?;
+            if index > 0 {
+                options.store_selected_filter(index as usize - 1);
+            }
+        }
+
+        if multiple {
+            let mut open: *mut IFileOpenDialog = null_mut();
+            check(
+                (*dialog.0).QueryInterface(
+                    &IFileOpenDialog::uuidof(),
+                    &mut open as *mut *mut IFileOpenDialog as *mut _,
+                ),
+                "IFileDialog::QueryInterface",
+            )?;
+            let open = Com(open);
+            let mut items: *mut IShellItemArray = null_mut();
+            check(
+                (*open.0).GetResults(&mut items),
+                "IFileOpenDialog::GetResults",
+            )?;
+            let items = Com(items);
+            let mut count = 0;
+            check((*items.0).GetCount(&mut count), "IShellItemArray::GetCount")?;
+            (0..count)
+                .map(|index| {
+                    let mut item = null_mut();
+                    check(
+                        (*items.0).GetItemAt(index, &mut item),
+                        "IShellItemArray::GetItemAt",
+                    )?;
+                    item_path(&Com(item))
+                })
+                .collect()
+        } else {
+            let mut item = null_mut();
+            check((*dialog.0).GetResult(&mut item), "IFileDialog::GetResult")?;
+            Ok(vec![item_path(&Com(item))?])
+        }
+    }
+}
@@ -0,0 +1,1734 @@
+//! The dialog thread and result polling shared by all the dialog
+//! types in this crate.
+
+use std::{
+    any::Any,
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::Waker,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    channel, clock, config,
+    event::{self, DialogEvent, DialogId},
+    expiry::{Completion, ExpiryPolicy},
+    guard::{self, Group, GuardPolicy},
+    i18n, observer, panic_hook,
+    progress::{self, Progress},
+    rate_limit::{self, RateLimitPolicy},
+    shutdown, CancelToken, DialogError, DialogKind, DialogObserver, DialogOptions, DialogOutcome,
+    DialogResult, DialogState, DisconnectPolicy, ImNativeDialogError, Request, RequestToken,
+};
+
+/// The result of a dialog, which may be empty if the user cancelled
+/// the dialog.
+pub(crate) trait Selection {
+    fn is_cancelled(&self) -> bool;
+
+    /// The paths selected, for the [DialogOutcome] of the dialog.
+    fn paths(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// The result recorded in `outcome`, or `None` if it can't be
+    /// recreated from the outcome.
+    #[cfg(feature = "replay")]
+    fn replay(_outcome: &DialogOutcome) -> Option<Result<Self, DialogError>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// An item selected in a dialog, which may refer to a path.
+pub(crate) trait Item {
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// The item for `path`, or `None` if it can't be recreated from a
+    /// path.
+    #[cfg(feature = "replay")]
+    fn from_path(_path: &Path) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+impl<T: Item> Selection for Option<T> {
+    fn is_cancelled(&self) -> bool {
+        self.is_none()
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        self.iter()
+            .filter_map(Item::path)
+            .map(Path::to_path_buf)
+            .collect()
+    }
+
+    #[cfg(feature = "replay")]
+    fn replay(outcome: &DialogOutcome) -> Option<Result<Self, DialogError>> {
+        if let Some(error) = &outcome.error {
+            return Some(Err(DialogError::Implementation(error.clone())));
+        }
+        match outcome.paths.first() {
+            Some(path) if !outcome.cancelled => T::from_path(path).map(|item| Ok(Some(item))),
+            _ => Some(Ok(None)),
+        }
+    }
+}
+
+impl<T: Item> Selection for Vec<T> {
+    fn is_cancelled(&self) -> bool {
+        self.is_empty()
+    }
+
+    fn paths(&self) -> Vec<PathBuf> {
+        self.iter()
+            .filter_map(Item::path)
+            .map(Path::to_path_buf)
+            .collect()
+    }
+
+    #[cfg(feature = "replay")]
+    fn replay(outcome: &DialogOutcome) -> Option<Result<Self, DialogError>> {
+        if let Some(error) = &outcome.error {
+            return Some(Err(DialogError::Implementation(error.clone())));
+        }
+        if outcome.cancelled {
+            return Some(Ok(Vec::new()));
+        }
+        outcome
+            .paths
+            .iter()
+            .map(|path| T::from_path(path))
+            .collect::<Option<_>>()
+            .map(Ok)
+    }
+}
+
+impl Item for PathBuf {
+    fn path(&self) -> Option<&Path> {
+        Some(self)
+    }
+
+    #[cfg(feature = "replay")]
+    fn from_path(path: &Path) -> Option<Self> {
+        Some(path.to_path_buf())
+    }
+}
+
+impl Item for crate::SelectedEntry {
+    fn path(&self) -> Option<&Path> {
+        match self {
+            crate::SelectedEntry::File(path) | crate::SelectedEntry::Dir(path) => Some(path),
+        }
+    }
+
+    #[cfg(feature = "replay")]
+    fn from_path(path: &Path) -> Option<Self> {
+        Some(if path.is_dir() {
+            crate::SelectedEntry::Dir(path.to_path_buf())
+        } else {
+            crate::SelectedEntry::File(path.to_path_buf())
+        })
+    }
+}
+
+impl Item for crate::Bookmark {
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    #[cfg(feature = "replay")]
+    fn from_path(path: &Path) -> Option<Self> {
+        crate::Bookmark::create(path).ok()
+    }
+}
+
+impl Item for crate::CheckedPath {
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    #[cfg(feature = "replay")]
+    fn from_path(path: &Path) -> Option<Self> {
+        Some(crate::CheckedPath::new(path.to_path_buf(), false))
+    }
+}
+
+impl Item for crate::DisplayPath {
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    #[cfg(feature = "replay")]
+    fn from_path(path: &Path) -> Option<Self> {
+        Some(path.to_path_buf().into())
+    }
+}
+
+impl Item for crate::ChoicePath {
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    #[cfg(feature = "replay")]
+    fn from_path(path: &Path) -> Option<Self> {
+        Some(crate::ChoicePath {
+            path: path.to_path_buf(),
+            choices: Vec::new(),
+        })
+    }
+}
+
+impl Item for crate::FilterPath {
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    #[cfg(feature = "replay")]
+    fn from_path(path: &Path) -> Option<Self> {
+        Some(crate::FilterPath {
+            path: path.to_path_buf(),
+            filter: None,
+        })
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Item for crate::HashedPath {
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    #[cfg(feature = "replay")]
+    fn from_path(path: &Path) -> Option<Self> {
+        crate::HashedPath::new(path.to_path_buf(), crate::HashAlgorithm::default()).ok()
+    }
+}
+
+impl Item for crate::ThumbnailPath {
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    #[cfg(feature = "replay")]
+    fn from_path(path: &Path) -> Option<Self> {
+        Some(crate::ThumbnailPath {
+            path: path.to_path_buf(),
+            thumbnail: None,
+        })
+    }
+}
+
+impl Item for crate::Application {}
+impl Item for crate::Font {}
+impl Item for crate::Rgba {}
+impl Item for String {}
+impl Item for secrecy::SecretString {}
+
+impl Selection for () {
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// The number of items selected, for results which are streamed
+/// separately.
+impl Selection for usize {
+    fn is_cancelled(&self) -> bool {
+        *self == 0
+    }
+}
+
+impl Selection for bool {
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+impl Selection for crate::MessageChoice {
+    fn is_cancelled(&self) -> bool {
+        *self == crate::MessageChoice::Cancel
+    }
+}
+
+/// Runs a dialog using `run`, or with the `replay` feature answers it
+/// using the next outcome of the same kind in the recording being
+/// replayed, see [crate::start_replay()].
+pub(crate) fn run_dialog<S, E>(
+    kind: DialogKind,
+    run: impl FnOnce() -> Result<S, E>,
+) -> Result<S, DialogError>
+where
+    S: Selection,
+    E: Into<DialogError>,
+{
+    #[cfg(feature = "replay")]
+    if let Some(result) = crate::replay::next(kind, S::replay) {
+        return result;
+    }
+    // Dialogs can't be shown in tests, so they fail instead.
+    #[cfg(feature = "testing")]
+    if crate::testing::is_active() {
+        return Err(DialogError::Implementation(format!(
+            "no mock response for the {:?} dialog",
+            kind
+        )));
+    }
+    #[cfg(not(feature = "replay"))]
+    let _ = kind;
+
+    run().map_err(Into::into)
+}
+
+/// Callback called in the dialog thread upon dialog close, see
+/// [crate::ImNativeFileDialog::with_callback()].
+pub(crate) type Callback<T> = Box<dyn FnOnce(&Result<T, DialogError>) + Send>;
+
+/// Sends a clone of a successful result to a receiver returned by
+/// [crate::ImNativeFileDialog::subscribe()], returning `false` once the
+/// receiver has been dropped.
+pub(crate) type Subscriber<T> = Box<dyn Fn(&T) -> bool + Send>;
+
+/// Called in the dialog thread once the result of a dialog has been
+/// sent, to wake the ui.
+pub(crate) type Wake = Arc<dyn Fn() + Send + Sync>;
+
+/// Forwards the result of a dialog from the dialog thread instead of
+/// returning it from [Worker::check()], see
+/// [crate::ImNativeFileDialog::route_to()].
+pub(crate) type Route<T> = Arc<dyn Fn(Result<T, DialogError>) + Send + Sync>;
+
+/// Data attached to a dialog by the application, see
+/// [crate::ImNativeFileDialog::with_context()].
+pub(crate) type Context = Box<dyn Any + Send>;
+
+/// A dialog waiting for another dialog to close, see
+/// [crate::GuardPolicy::Queue].
+type Queued<T> =
+    Box<dyn FnOnce(&mut Worker<T>) -> Result<RequestToken, ImNativeDialogError> + Send>;
+
+/// Notifies the observers and event subscribers of a dialog about its
+/// lifecycle.
+#[derive(Clone)]
+pub(crate) struct Notifier {
+    id: DialogId,
+    kind: DialogKind,
+    options: Option<DialogOptions>,
+    opened_at: Instant,
+    timestamp: SystemTime,
+    observers: Vec<Arc<dyn DialogObserver>>,
+    /// Set once the dialog has closed, for [ExpiryPolicy].
+    completion: Arc<Mutex<Option<Completion>>>,
+}
+
+impl Notifier {
+    /// Create a notifier for a new dialog shown with `options`,
+    /// notifying the global observer and `observer`.
+    pub(crate) fn new(
+        kind: DialogKind,
+        options: Option<DialogOptions>,
+        observer: Option<Arc<dyn DialogObserver>>,
+    ) -> Self {
+        Self {
+            id: DialogId::next(),
+            kind,
+            options,
+            opened_at: clock::now(),
+            timestamp: SystemTime::now(),
+            observers: observer::global_observer()
+                .into_iter()
+                .chain(observer)
+                .collect(),
+            completion: Arc::default(),
+        }
+    }
+
+    pub(crate) fn opened(&self) {
+        #[cfg(feature = "testing")]
+        crate::testing::record_opened(self.kind, self.options.as_ref());
+        for observer in &self.observers {
+            observer.on_open(self.kind);
+        }
+        event::emit(DialogEvent::Opened {
+            id: self.id,
+            kind: self.kind,
+        });
+    }
+
+    pub(crate) fn closed<T: Selection>(&self, result: &Result<T, DialogError>) {
+        #[cfg(feature = "tracing")]
+        match result {
+            Ok(selection) if selection.is_cancelled() => tracing::info!("dialog cancelled"),
+            Ok(_) => tracing::info!("dialog completed"),
+            Err(error) => tracing::warn!(%error, "dialog failed"),
+        }
+
+        *self.completion.lock().expect("completion lock poisoned") = Some(Completion::now(
+            result.as_ref().map(Selection::paths).unwrap_or_default(),
+            result.as_ref().is_ok_and(Selection::is_cancelled),
+        ));
+
+        let duration = clock::elapsed(self.opened_at);
+        for observer in &self.observers {
+            match result {
+                Ok(selection) => {
+                    observer.on_complete(self.kind, selection.is_cancelled(), duration)
+                }
+                Err(error) => observer.on_error(self.kind, error, duration),
+            }
+        }
+
+        if !self.observers.is_empty() {
+            let outcome = DialogOutcome::new(
+                self.kind,
+                self.options.as_ref(),
+                result,
+                duration,
+                self.timestamp,
+            );
+            for observer in &self.observers {
+                observer.on_outcome(&outcome);
+            }
+        }
+
+        let (id, kind) = (self.id, self.kind);
+        event::emit(match result {
+            Ok(selection) if selection.is_cancelled() => {
+                DialogEvent::Cancelled { id, kind, duration }
+            }
+            Ok(_) => DialogEvent::Completed { id, kind, duration },
+            Err(error) => DialogEvent::Failed {
+                id,
+                kind,
+                error: error.to_string(),
+                duration,
+            },
+        });
+    }
+
+    /// Notify that the dialog has been open for longer than the
+    /// watchdog threshold.
+    fn maybe_hung(&self) {
+        let duration = clock::elapsed(self.opened_at);
+        #[cfg(feature = "log")]
+        log::warn!(
+            "{:?} dialog open for {:?}, it may be hung",
+            self.kind,
+            duration
+        );
+        #[cfg(feature = "tracing")]
+        tracing::warn!(kind = ?self.kind, ?duration, "dialog may be hung");
+        event::emit(DialogEvent::MaybeHung {
+            id: self.id,
+            kind: self.kind,
+            duration,
+        });
+    }
+
+    /// Notify that the native dialog has closed, after its result was
+    /// delivered.
+    pub(crate) fn finished(&self) {
+        self.completion
+            .lock()
+            .expect("completion lock poisoned")
+            .get_or_insert_with(|| Completion::now(Vec::new(), false));
+        for observer in &self.observers {
+            observer.on_close(self.kind);
+        }
+        event::emit(DialogEvent::Closed {
+            id: self.id,
+            kind: self.kind,
+        });
+    }
+}
+
+/// How a result was received by [Worker::receive()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Received {
+    /// The dialog sent the result.
+    Sent,
+    /// The user cancelled the dialog, or it was cancelled before it
+    /// sent a result.
+    Cancelled,
+    /// The dialog thread exited without sending a result.
+    Disconnected,
+}
+
+/// A result received by [Worker::receive()], along with the request
+/// and context of the dialog.
+type Receipt<T> = (Request, Option<Context>, Result<T, DialogError>, Received);
+
+/// Runs a dialog in a new thread, and holds the channel the result is
+/// returned through.
+pub(crate) struct Worker<T> {
+    callback: Option<Callback<T>>,
+    receiver: Option<channel::Receiver<Result<T, DialogError>>>,
+    opened_at: Option<Instant>,
+    id: Option<DialogId>,
+    observer: Option<Arc<dyn DialogObserver>>,
+    subscribers: Arc<Mutex<Vec<Subscriber<T>>>>,
+    waker: Arc<Mutex<Option<Waker>>>,
+    wake: Option<Wake>,
+    route: Option<Route<T>>,
+    /// Set while the open dialog forwards its result using the route.
+    routed: bool,
+    next_cancel_token: Option<CancelToken>,
+    cancel_token: Option<CancelToken>,
+    /// Cancelled by [crate::shutdown()] once the dialog is abandoned.
+    abandoned: Option<CancelToken>,
+    next_context: Option<Context>,
+    context: Option<Context>,
+    debounce: Option<Duration>,
+    watchdog: Option<Duration>,
+    last_shown_at: Option<Instant>,
+    queued: Option<Queued<T>>,
+    /// The place of the queued dialog in the queue.
+    ticket: Option<guard::Ticket>,
+    next_priority: Option<i32>,
+    group: Option<Group>,
+    injected: VecDeque<(Request, T)>,
+    request: Option<Request>,
+    last_token: Option<RequestToken>,
+    /// Set when a request is coalesced into the previous dialog instead
+    /// of showing one.
+    coalesced: bool,
+    /// Set once the dialog thread has finished.
+    done: Option<Arc<AtomicBool>>,
+    /// Set once the dialog has closed.
+    completion: Option<Arc<Mutex<Option<Completion>>>>,
+    expiry: Option<ExpiryPolicy>,
+    progress: Option<channel::Receiver<Progress>>,
+    defaults: Option<DialogOptions>,
+    thread_name: Option<String>,
+    stack_size: Option<usize>,
+    disconnect: DisconnectPolicy,
+    #[cfg(feature = "watch")]
+    watcher: Option<crate::watch::WatcherSlot>,
+}
+
+impl<T> Default for Worker<T> {
+    fn default() -> Self {
+        let worker = Self {
+            callback: None,
+            receiver: None,
+            opened_at: None,
+            id: None,
+            observer: None,
+            subscribers: Arc::default(),
+            waker: Arc::default(),
+            wake: None,
+            route: None,
+            routed: false,
+            next_cancel_token: None,
+            cancel_token: None,
+            abandoned: None,
+            next_context: None,
+            context: None,
+            debounce: None,
+            watchdog: None,
+            last_shown_at: None,
+            queued: None,
+            ticket: None,
+            next_priority: None,
+            group: None,
+            injected: VecDeque::new(),
+            request: None,
+            last_token: None,
+            coalesced: false,
+            done: None,
+            completion: None,
+            expiry: None,
+            progress: None,
+            defaults: None,
+            thread_name: None,
+            stack_size: None,
+            disconnect: DisconnectPolicy::default(),
+            #[cfg(feature = "watch")]
+            watcher: None,
+        };
+        match config::global_config() {
+            Some(config) => Self {
+                defaults: config.options(),
+                wake: config.waker,
+                thread_name: config.thread_name,
+                stack_size: config.stack_size,
+                disconnect: config.disconnect,
+                ..worker
+            },
+            None => worker,
+        }
+    }
+}
+
+impl<T: Send + 'static + Default> Worker<T> {
+    /// Set the callback to use for the next dialog.
+    pub fn set_callback(&mut self, callback: Callback<T>) {
+        self.callback = Some(callback);
+    }
+
+    /// Set the observer to use for every dialog.
+    pub fn set_observer(&mut self, observer: Arc<dyn DialogObserver>) {
+        self.observer = Some(observer);
+    }
+
+    /// Add a subscriber for the results of every dialog.
+    pub fn add_subscriber(&mut self, subscriber: Subscriber<T>) {
+        self.subscribers
+            .lock()
+            .expect("subscribers lock poisoned")
+            .push(subscriber);
+    }
+
+    /// Set the function to call once the result of every dialog has
+    /// been sent.
+    pub fn set_wake(&mut self, wake: Wake) {
+        self.wake = Some(wake);
+    }
+
+    /// Set the function to forward the result of every dialog to.
+    pub fn set_route(&mut self, route: Route<T>) {
+        self.route = Some(route);
+    }
+
+    /// Set the cancel token to use for the next dialog.
+    pub fn set_cancel_token(&mut self, token: CancelToken) {
+        self.next_cancel_token = Some(token);
+    }
+
+    /// Set the priority of the next dialog in the queue.
+    pub fn set_priority(&mut self, priority: i32) {
+        self.next_priority = Some(priority);
+    }
+
+    /// Set the context to attach to the next dialog.
+    pub fn set_context(&mut self, context: Context) {
+        self.next_context = Some(context);
+    }
+
+    /// Set the window after showing a dialog in which further dialogs
+    /// are ignored.
+    pub fn set_debounce(&mut self, window: Duration) {
+        self.debounce = Some(window);
+    }
+
+    /// Watch the paths selected in every dialog from now on.
+    #[cfg(feature = "watch")]
+    pub fn enable_watcher(&mut self) {
+        self.watcher.get_or_insert_with(Arc::default);
+    }
+
+    /// The watcher for the paths selected in the last dialog.
+    #[cfg(feature = "watch")]
+    pub fn watcher(&self) -> Option<&crate::watch::WatcherSlot> {
+        self.watcher.as_ref()
+    }
+
+    /// Set the time after which open dialogs are reported as hung.
+    pub fn set_watchdog(&mut self, threshold: Duration) {
+        self.watchdog = Some(threshold);
+    }
+
+    /// Set the policy for results which aren't checked in time.
+    pub fn set_expiry(&mut self, expiry: ExpiryPolicy) {
+        self.expiry = Some(expiry);
+    }
+
+    /// Set the group to use for every dialog.
+    pub fn set_group(&mut self, group: Group) {
+        self.group = Some(group);
+    }
+
+    /// Set the options which fill in the options of every dialog,
+    /// themselves filled in from the global defaults.
+    pub fn set_defaults(&mut self, defaults: DialogOptions) {
+        self.defaults = Some(match self.defaults.take() {
+            Some(global) => defaults.or_defaults(&global),
+            None => defaults,
+        });
+    }
+
+    /// Set the name of the dialog threads.
+    pub fn set_thread_name(&mut self, name: String) {
+        self.thread_name = Some(name);
+    }
+
+    /// Set the stack size of the dialog threads in bytes.
+    pub fn set_stack_size(&mut self, size: usize) {
+        self.stack_size = Some(size);
+    }
+
+    /// Set what [Worker::check()] returns when the dialog thread exits
+    /// without sending a result.
+    pub fn set_disconnect_policy(&mut self, policy: DisconnectPolicy) {
+        self.disconnect = policy;
+    }
+
+    /// The options to show a dialog with, filled in from the defaults.
+    pub fn options(&self, options: DialogOptions) -> DialogOptions {
+        match &self.defaults {
+            Some(defaults) => options.or_defaults(defaults),
+            None => options,
+        }
+    }
+
+    /// Add a result obtained elsewhere to return from the next
+    /// [Worker::check()], ahead of the result of any open dialog.
+    pub fn inject(&mut self, result: T) -> RequestToken {
+        let token = RequestToken::next();
+        let request = Request {
+            token,
+            kind: DialogKind::Custom,
+            options: None,
+            priority: 0,
+        };
+        self.injected.push_back((request, result));
+        if let Some(waker) = self.waker.lock().expect("waker lock poisoned").take() {
+            waker.wake();
+        }
+        token
+    }
+
+    /// Set the waker to wake once the result of the currently open
+    /// dialog has been sent.
+    #[cfg_attr(not(feature = "futures"), allow(dead_code))]
+    pub fn register_waker(&self, waker: &Waker) {
+        let mut current = self.waker.lock().expect("waker lock poisoned");
+        if !current
+            .as_ref()
+            .is_some_and(|current| current.will_wake(waker))
+        {
+            *current = Some(waker.clone());
+        }
+    }
+
+    /// Spawn the dialog thread, `run` is responsible for calling the
+    /// callback and sending the result.
+    pub fn spawn_with<F>(
+        &mut self,
+        kind: DialogKind,
+        options: Option<DialogOptions>,
+        run: F,
+    ) -> Result<RequestToken, ImNativeDialogError>
+    where
+        F: FnOnce(channel::Sender<Result<T, DialogError>>, Callback<T>) + Send + 'static,
+    {
+        self.spawn_notified(
+            RequestToken::next(),
+            kind,
+            options,
+            |sender, callback, _notifier| run(sender, callback),
+        )
+    }
+
+    fn spawn_notified<F>(
+        &mut self,
+        token: RequestToken,
+        kind: DialogKind,
+        options: Option<DialogOptions>,
+        run: F,
+    ) -> Result<RequestToken, ImNativeDialogError>
+    where
+        F: FnOnce(channel::Sender<Result<T, DialogError>>, Callback<T>, &Notifier) + Send + 'static,
+    {
+        if let (Some(window), Some(last_shown_at)) = (self.debounce, self.last_shown_at) {
+            if clock::elapsed(last_shown_at) < window {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(kind = ?kind, "dialog debounced");
+                // The result is delivered for the request which showed
+                // the dialog.
+                self.coalesced = true;
+                return Ok(self.last_token.unwrap_or(token));
+            }
+        }
+
+        if self.is_open() {
+            return Err(ImNativeDialogError::AlreadyOpen);
+        }
+
+        // A queued dialog was counted when it was requested.
+        let resumed = self
+            .request
+            .as_ref()
+            .is_some_and(|request| request.token == token);
+        if !resumed {
+            match rate_limit::admit(kind) {
+                Ok(()) => {}
+                // Without a previous dialog there is no result to
+                // coalesce the request into.
+                Err(RateLimitPolicy::Coalesce) => match self.last_token {
+                    Some(last_token) => {
+                        self.coalesced = true;
+                        return Ok(last_token);
+                    }
+                    None => return Err(ImNativeDialogError::RateLimited),
+                },
+                Err(RateLimitPolicy::Reject) => return Err(ImNativeDialogError::RateLimited),
+            }
+        }
+
+        // The priority is kept while the dialog is queued, so it can be
+        // changed using [Worker::reprioritize()].
+        let priority = self.next_priority.unwrap_or_default();
+        let permit = match guard::acquire(self.group.as_ref(), priority, token) {
+            Ok(permit) => permit,
+            Err(GuardPolicy::Reject) => return Err(ImNativeDialogError::Busy),
+            Err(GuardPolicy::Queue) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(kind = ?kind, priority, "dialog queued");
+                self.request = Some(Request {
+                    token,
+                    kind,
+                    options: options.clone(),
+                    priority,
+                });
+                self.ticket = Some(guard::Ticket::new(
+                    token,
+                    kind,
+                    priority,
+                    self.group.as_ref(),
+                ));
+                self.queued = Some(Box::new(move |worker: &mut Worker<T>| {
+                    worker.spawn_notified(token, kind, options, run)
+                }));
+                return Ok(token);
+            }
+        };
+
+        let (sender, receiver) = channel::bounded(1);
+        // With a route the dialog thread forwards the result itself, and
+        // the worker only watches for the thread to finish.
+        let (receiver, route) = match self.route.clone() {
+            Some(route) => {
+                let (forwarded, watch) = channel::bounded::<Result<T, DialogError>>(1);
+                (watch, Some((route, receiver, forwarded)))
+            }
+            None => (receiver, None),
+        };
+        let routed = route.is_some();
+
+        let notifier = Notifier::new(kind, options.clone(), self.observer.clone());
+        let (id, opened_at) = (notifier.id, notifier.opened_at);
+        let completion = notifier.completion.clone();
+        let (progress_sender, progress) = channel::unbounded();
+        let request = Request {
+            token,
+            kind,
+            options,
+            priority,
+        };
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "dialog",
+            id = ?id,
+            kind = ?kind,
+            options = ?request.options,
+            thread = tracing::field::Empty,
+        );
+
+        notifier.opened();
+
+        let callback = self.callback.take().unwrap_or_else(|| Box::new(|_| {}));
+        self.cancel_token = self.next_cancel_token.take();
+        self.context = self.next_context.take();
+        self.next_priority = None;
+        let waker = self.waker.clone();
+        let wake = self.wake.clone();
+        // Disconnected once the dialog has finished.
+        let (finished, watched) = channel::bounded::<()>(1);
+        let done = Arc::new(AtomicBool::new(false));
+        let thread_done = done.clone();
+        // Kept to notify the observers if the thread can't be spawned.
+        let thread_notifier = notifier.clone();
+        // Dropped with the closure if the thread can't be spawned.
+        let (registration, abandoned) = shutdown::register(token, kind);
+        let thread_abandoned = abandoned.clone();
+        let show = move || {
+            let notifier = thread_notifier;
+            let _finished = finished;
+            #[cfg(feature = "tracing")]
+            let _span = {
+                span.record("thread", tracing::field::debug(std::thread::current().id()));
+                span.entered()
+            };
+            #[cfg(feature = "tracing")]
+            tracing::debug!("showing dialog");
+            #[cfg(any(feature = "log", feature = "tracing"))]
+            crate::diagnostics::log_once();
+
+            // The channel is disconnected if `run` panics, so the dialog
+            // returns the default result.
+            panic_hook::catch(notifier.kind, notifier.options.as_ref(), || {
+                progress::with_current(notifier.id, notifier.kind, progress_sender, || {
+                    run(sender, callback, &notifier)
+                })
+            });
+            if let Some((route, receiver, _forwarded)) = route {
+                let result = receiver.try_recv();
+                // Nothing was sent if the dialog panicked.
+                #[cfg(feature = "log")]
+                if result.is_err() {
+                    log::warn!("Dialog exited without a result to forward");
+                }
+                #[cfg(feature = "tracing")]
+                if result.is_err() {
+                    tracing::warn!("dialog exited without a result to forward");
+                }
+                match result {
+                    Ok(result) if !thread_abandoned.is_cancelled() => route(result),
+                    _ => {}
+                }
+            }
+            thread_done.store(true, Ordering::Release);
+            notifier.finished();
+            drop(permit);
+            drop(registration);
+
+            if let Some(waker) = waker.lock().expect("waker lock poisoned").take() {
+                waker.wake();
+            }
+            if let Some(wake) = wake {
+                wake();
+            }
+        };
+        // With the `inline` feature the dialog is shown on the calling
+        // thread, which blocks until it is closed, and the result waits
+        // in the channel for the next check.
+        #[cfg(feature = "inline")]
+        let spawned: std::io::Result<()> = {
+            show();
+            Ok(())
+        };
+        #[cfg(not(feature = "inline"))]
+        let spawned = {
+            let mut builder = std::thread::Builder::new();
+            if let Some(name) = &self.thread_name {
+                builder = builder.name(name.clone());
+            }
+            if let Some(size) = self.stack_size {
+                builder = builder.stack_size(size);
+            }
+            builder.spawn(show).map(drop)
+        };
+        if let Err(error) = spawned {
+            // The permit is released with the dropped closure.
+            let result: Result<(), DialogError> = Err(DialogError::Implementation(format!(
+                "failed to spawn dialog thread: {}",
+                error
+            )));
+            notifier.closed(&result);
+            notifier.finished();
+            self.context = None;
+            self.cancel_token = None;
+            return Err(ImNativeDialogError::SpawnFailed(error));
+        }
+
+        // The dialog has already closed, so it can only be reported
+        // afterwards.
+        #[cfg(feature = "inline")]
+        {
+            drop(watched);
+            if self
+                .watchdog
+                .is_some_and(|threshold| clock::elapsed(opened_at) >= threshold)
+            {
+                notifier.maybe_hung();
+            }
+        }
+        #[cfg(not(feature = "inline"))]
+        if let Some(threshold) = self.watchdog {
+            // The watchdog is best effort, so failing to spawn its
+            // thread is ignored.
+            let _ = std::thread::Builder::new().spawn(move || {
+                if let Err(channel::RecvTimeoutError::Timeout) = watched.recv_timeout(threshold) {
+                    notifier.maybe_hung();
+                }
+            });
+        }
+
+        self.receiver = Some(receiver);
+        self.abandoned = Some(abandoned);
+        self.routed = routed;
+        self.opened_at = Some(opened_at);
+        self.last_shown_at = Some(opened_at);
+        self.id = Some(id);
+        self.request = Some(request);
+        self.last_token = Some(token);
+        self.done = Some(done);
+        self.completion = Some(completion);
+        self.progress = Some(progress);
+
+        Ok(token)
+    }
+
+    /// Spawn the dialog thread, calling the callback and sending the
+    /// result returned by `run`.
+    pub fn spawn<F, E>(
+        &mut self,
+        kind: DialogKind,
+        options: Option<DialogOptions>,
+        run: F,
+    ) -> Result<RequestToken, ImNativeDialogError>
+    where
+        T: Selection,
+        F: FnOnce() -> Result<T, E> + Send + 'static,
+        E: Into<DialogError>,
+    {
+        self.spawn_map(kind, options, run, |value| value)
+    }
+
+    /// Spawn the dialog thread, calling the callback and sending the
+    /// result returned by `run` converted using `map`, where the
+    /// observers are notified about the result before conversion.
+    pub fn spawn_map<S, F, E, M>(
+        &mut self,
+        kind: DialogKind,
+        options: Option<DialogOptions>,
+        run: F,
+        map: M,
+    ) -> Result<RequestToken, ImNativeDialogError>
+    where
+        S: Selection,
+        F: FnOnce() -> Result<S, E> + Send + 'static,
+        E: Into<DialogError>,
+        M: FnOnce(S) -> T + Send + 'static,
+    {
+        let subscribers = self.subscribers.clone();
+        #[cfg(feature = "watch")]
+        let watcher = self.watcher.clone();
+        self.spawn_notified(
+            RequestToken::next(),
+            kind,
+            options,
+            move |sender, callback, notifier| {
+                let result = run_dialog(kind, run);
+                notifier.closed(&result);
+                #[cfg(feature = "watch")]
+                if let (Some(watcher), Ok(selection)) = (&watcher, &result) {
+                    crate::watch::watch(watcher, selection.paths());
+                }
+                let result = result.map(map);
+                callback(&result);
+                if let Ok(value) = &result {
+                    subscribers
+                        .lock()
+                        .expect("subscribers lock poisoned")
+                        .retain(|subscriber| subscriber(value));
+                }
+                // The receiver is dropped if the dialog was cancelled.
+                let _ = sender.send(result);
+
+                #[cfg(feature = "tracing")]
+                tracing::debug!("dialog result delivered");
+            },
+        )
+    }
+
+    /// Move the currently open dialog into a new worker, leaving this
+    /// worker closed, see [crate::ImNativeFileDialog::detach()].
+    pub fn detach(&mut self) -> Option<Worker<T>> {
+        if !self.is_open() {
+            return None;
+        }
+
+        Some(Worker {
+            receiver: self.receiver.take(),
+            opened_at: self.opened_at.take(),
+            id: self.id.take(),
+            observer: self.observer.clone(),
+            subscribers: self.subscribers.clone(),
+            // The dialog thread wakes the waker it was spawned with.
+            waker: std::mem::take(&mut self.waker),
+            wake: self.wake.clone(),
+            route: self.route.clone(),
+            routed: std::mem::take(&mut self.routed),
+            cancel_token: self.cancel_token.take(),
+            abandoned: self.abandoned.take(),
+            context: self.context.take(),
+            queued: self.queued.take(),
+            ticket: self.ticket.take(),
+            next_priority: self.next_priority.take(),
+            request: self.request.take(),
+            group: self.group.clone(),
+            done: self.done.take(),
+            completion: self.completion.take(),
+            expiry: self.expiry,
+            progress: self.progress.take(),
+            disconnect: self.disconnect,
+            ..Worker::default()
+        })
+    }
+
+    /// See [crate::ImNativeFileDialog::cancel()].
+    pub fn cancel(&mut self) -> bool {
+        if self.queued.take().is_some() {
+            self.ticket = None;
+            self.next_priority = None;
+            // The queued dialog is never shown, so its result is sent
+            // here to return it through the usual path.
+            let (sender, receiver) = channel::bounded(1);
+            let _ = sender.send(Ok(T::default()));
+            self.receiver = Some(receiver);
+            self.done = Some(Arc::new(AtomicBool::new(true)));
+            return true;
+        }
+        if self.receiver.is_none() {
+            return false;
+        }
+        let token = CancelToken::new();
+        token.cancel();
+        self.cancel_token = Some(token);
+        true
+    }
+
+    /// Returns `true` if the open dialog has been cancelled using its
+    /// [CancelToken], or abandoned by [crate::shutdown()].
+    fn is_cancelled(&self) -> bool {
+        self.cancel_token
+            .iter()
+            .chain(&self.abandoned)
+            .any(CancelToken::is_cancelled)
+    }
+
+    /// See [crate::ImNativeFileDialog::reprioritize()].
+    pub fn reprioritize(&mut self, priority: i32) -> bool {
+        match (&self.ticket, &mut self.request) {
+            (Some(ticket), Some(request)) => {
+                ticket.set_priority(priority);
+                request.priority = priority;
+                self.next_priority = Some(priority);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The request of the open or queued dialog.
+    pub fn request(&self) -> Option<&Request> {
+        self.request.as_ref()
+    }
+
+    /// See [crate::ImNativeFileDialog::kind()].
+    pub fn kind(&self) -> Option<DialogKind> {
+        self.request().map(|request| request.kind)
+    }
+
+    /// See [crate::ImNativeFileDialog::status()].
+    pub fn status(&self) -> Option<String> {
+        let text = i18n::text(self.kind()?.waiting_text());
+        Some(match self.elapsed().map(|elapsed| elapsed.as_secs()) {
+            Some(secs) if secs >= 60 => format!("{} ({}m {}s)", text, secs / 60, secs % 60),
+            Some(secs) => format!("{} ({}s)", text, secs),
+            None => text,
+        })
+    }
+
+    /// See [crate::ImNativeFileDialog::pending_options()].
+    pub fn pending_options(&self) -> Option<&DialogOptions> {
+        self.request()?.options.as_ref()
+    }
+
+    /// Returns whether a request was coalesced into the previous dialog
+    /// since the last call, instead of showing a dialog.
+    pub fn take_coalesced(&mut self) -> bool {
+        std::mem::take(&mut self.coalesced)
+    }
+
+    /// See [crate::ImNativeFileDialog::reset()].
+    pub fn reset(&mut self) -> bool {
+        let discarded =
+            self.receiver.is_some() || self.queued.is_some() || !self.injected.is_empty();
+        self.receiver = None;
+        self.routed = false;
+        self.queued = None;
+        self.ticket = None;
+        self.next_priority = None;
+        self.injected.clear();
+        self.opened_at = None;
+        self.id = None;
+        self.request = None;
+        self.cancel_token = None;
+        self.abandoned = None;
+        self.context = None;
+        self.done = None;
+        self.completion = None;
+        self.progress = None;
+        discarded
+    }
+
+    /// See [crate::ImNativeFileDialog::check()].
+    pub fn check(&mut self) -> Option<Result<T, DialogError>> {
+        self.check_request().map(|(_, result)| result)
+    }
+
+    /// See [crate::ImNativeFileDialog::check_request()].
+    pub fn check_request(&mut self) -> Option<(Request, Result<T, DialogError>)> {
+        self.check_context()
+            .map(|(request, _context, result)| (request, result))
+    }
+
+    /// See [crate::ImNativeFileDialog::check_context()].
+    pub fn check_context(&mut self) -> Option<(Request, Option<Context>, Result<T, DialogError>)> {
+        self.receive()
+            .map(|(request, context, result, _received)| (request, context, result))
+    }
+
+    /// See [crate::ImNativeFileDialog::check_result()].
+    pub fn check_result(&mut self) -> Option<DialogResult<T>> {
+        self.receive().map(
+            |(_request, _context, result, received)| match (result, received) {
+                (_, Received::Disconnected) => DialogResult::Disconnected,
+                (_, Received::Cancelled) => DialogResult::Cancelled,
+                (Ok(value), Received::Sent) => DialogResult::Selected(value),
+                (Err(error), Received::Sent) => DialogResult::Failed(error),
+            },
+        )
+    }
+
+    /// Receives the result of the dialog, if it has completed, along
+    /// with how it was received.
+    fn receive(&mut self) -> Option<Receipt<T>> {
+        if let Some((request, result)) = self.injected.pop_front() {
+            return Some((request, None, Ok(result), Received::Sent));
+        }
+
+        // Retrying a queued dialog allocates, so it is only retried
+        // once the dialog blocking it has closed.
+        if self
+            .ticket
+            .as_ref()
+            .is_some_and(guard::Ticket::is_cancelled)
+        {
+            self.cancel();
+        }
+        let blocked = match (&self.queued, &self.request) {
+            (Some(_), Some(request)) => {
+                guard::is_blocked(self.group.as_ref(), request.priority, request.token)
+            }
+            _ => true,
+        };
+        if !blocked {
+            let queued = self.queued.take().expect("queued dialog");
+            self.ticket = None;
+            // Shows the dialog, or queues it again if another dialog
+            // opened in the meantime.
+            if queued(self).is_err() {
+                // The guard policy has changed to reject the dialog.
+                self.next_priority = None;
+                let request = self.request.take().expect("queued dialog has a request");
+                return Some((
+                    request,
+                    self.next_context.take(),
+                    Ok(T::default()),
+                    Received::Cancelled,
+                ));
+            }
+        }
+
+        let cancelled = self.is_cancelled();
+        let running = self
+            .done
+            .as_ref()
+            .is_some_and(|done| !done.load(Ordering::Acquire));
+        if running && !cancelled {
+            // The result is sent before the dialog thread finishes, so
+            // there is nothing to receive yet.
+            return None;
+        }
+
+        if self.routed && !cancelled {
+            // The dialog thread has forwarded the result, so the dialog
+            // closes without returning one.
+            self.routed = false;
+            self.receiver = None;
+            self.opened_at = None;
+            self.id = None;
+            self.cancel_token = None;
+            self.abandoned = None;
+            self.context = None;
+            self.done = None;
+            self.completion = None;
+            self.progress = None;
+            self.request = None;
+            return None;
+        }
+
+        let result = match &self.receiver {
+            Some(receiver) => match receiver.try_recv() {
+                Ok(result) => Some((result, Received::Sent)),
+                Err(channel::TryRecvError::Disconnected) => {
+                    #[cfg(feature = "log")]
+                    log::warn!("OpenDialog channel disconnected");
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        result = std::any::type_name::<T>(),
+                        "dialog channel disconnected"
+                    );
+                    let result = match self.disconnect {
+                        DisconnectPolicy::Default => Ok(T::default()),
+                        DisconnectPolicy::Error => Err(DialogError::Implementation(
+                            "dialog thread exited without a result".to_owned(),
+                        )),
+                    };
+                    Some((result, Received::Disconnected))
+                }
+                Err(channel::TryRecvError::Empty) if cancelled => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!("dialog cancelled using token");
+                    Some((Ok(T::default()), Received::Cancelled))
+                }
+                Err(channel::TryRecvError::Empty) => None,
+            },
+            None => None,
+        };
+
+        let (result, mut received) = result?;
+        self.routed = false;
+        self.receiver = None;
+        self.opened_at = None;
+        let id = self.id.take();
+        self.cancel_token = None;
+        self.abandoned = None;
+        self.done = None;
+        self.progress = None;
+        let request = self.request.take().expect("open dialog has a request");
+
+        let completion = self
+            .completion
+            .take()
+            .and_then(|completion| completion.lock().expect("completion lock poisoned").take());
+        if let Some(completion) = completion {
+            if received == Received::Sent && completion.is_cancelled() {
+                received = Received::Cancelled;
+            }
+            if let (Some(expiry), Some(id)) = (&self.expiry, id) {
+                if expiry.expire(id, request.kind, completion) {
+                    self.context = None;
+                    return None;
+                }
+            }
+        }
+
+        Some((request, self.context.take(), result, received))
+    }
+
+    /// See [crate::ImNativeFileDialog::check_progress()].
+    pub fn check_progress(&self) -> Option<Progress> {
+        let progress = self.progress.as_ref()?;
+        std::iter::from_fn(|| progress.try_recv().ok()).last()
+    }
+
+    /// See [crate::ImNativeFileDialog::is_open()].
+    pub fn is_open(&self) -> bool {
+        self.receiver.is_some() || self.queued.is_some()
+    }
+
+    /// See [crate::ImNativeFileDialog::state()].
+    pub fn state(&self) -> DialogState {
+        if let Some((request, _)) = self.injected.front() {
+            return DialogState::Completed {
+                kind: request.kind,
+                token: request.token,
+                opened_at: None,
+            };
+        }
+        let request = match &self.request {
+            Some(request) => request,
+            None => return DialogState::Idle,
+        };
+        let (kind, token) = (request.kind, request.token);
+        if self.queued.is_some() {
+            return DialogState::Queued { kind, token };
+        }
+        let done = self
+            .done
+            .as_ref()
+            .is_some_and(|done| done.load(Ordering::Acquire))
+            || self.is_cancelled();
+        match self.opened_at {
+            _ if done => DialogState::Completed {
+                kind,
+                token,
+                opened_at: self.opened_at,
+            },
+            Some(opened_at) => DialogState::Pending {
+                kind,
+                token,
+                opened_at,
+            },
+            None => DialogState::Idle,
+        }
+    }
+
+    /// See [crate::ImNativeFileDialog::is_stale()].
+    pub fn is_stale(&self) -> bool {
+        match (self.watchdog, self.elapsed()) {
+            (Some(threshold), Some(elapsed)) => elapsed > threshold,
+            _ => false,
+        }
+    }
+
+    /// See [crate::ImNativeFileDialog::opened_at()].
+    pub fn opened_at(&self) -> Option<Instant> {
+        self.opened_at
+    }
+
+    /// See [crate::ImNativeFileDialog::id()].
+    pub fn id(&self) -> Option<DialogId> {
+        self.id
+    }
+
+    /// See [crate::ImNativeFileDialog::elapsed()].
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.opened_at.map(clock::elapsed)
+    }
+}
+
+// With the `inline` feature the dialogs block the test that shows them.
+#[cfg(all(test, not(feature = "inline")))]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::{serial_test as serial, RateLimit, RateLimitPolicy};
+
+    type Dialog = Worker<Option<PathBuf>>;
+
+    /// Shows a dialog which returns `path` once the returned sender is
+    /// used or dropped.
+    fn show_blocking(
+        worker: &mut Dialog,
+        path: &str,
+    ) -> (Result<RequestToken, ImNativeDialogError>, mpsc::Sender<()>) {
+        let (release, released) = mpsc::channel::<()>();
+        let path = PathBuf::from(path);
+        let token = worker.spawn(DialogKind::OpenSingleFile, None, move || {
+            let _ = released.recv();
+            Ok::<_, DialogError>(Some(path))
+        });
+        (token, release)
+    }
+
+    /// Checks `worker` until it returns a result.
+    fn wait(worker: &mut Dialog) -> Result<Option<PathBuf>, DialogError> {
+        let started_at = Instant::now();
+        loop {
+            if let Some(result) = worker.check() {
+                return result;
+            }
+            assert!(
+                started_at.elapsed() < Duration::from_secs(5),
+                "no result from the dialog"
+            );
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn group(name: &str, policy: GuardPolicy) -> Group {
+        Group {
+            name: name.to_owned(),
+            policy,
+        }
+    }
+
+    #[test]
+    fn shows_dialog_and_returns_result() {
+        let _serial = serial();
+        let mut worker = Dialog::default();
+        let (token, release) = show_blocking(&mut worker, "a.txt");
+        let token = token.unwrap();
+        assert!(worker.is_open());
+        assert!(matches!(worker.state(), DialogState::Pending { .. }));
+        drop(release);
+        let (request, result) = loop {
+            if let Some(received) = worker.check_request() {
+                break received;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        };
+        assert_eq!(request.token, token);
+        assert_eq!(result.unwrap(), Some(PathBuf::from("a.txt")));
+        assert!(!worker.is_open());
+        assert_eq!(worker.state(), DialogState::Idle);
+    }
+
+    #[test]
+    fn rejects_showing_while_open() {
+        let _serial = serial();
+        let mut worker = Dialog::default();
+        let (token, release) = show_blocking(&mut worker, "a.txt");
+        token.unwrap();
+        let (second, _second_release) = show_blocking(&mut worker, "b.txt");
+        assert!(matches!(second, Err(ImNativeDialogError::AlreadyOpen)));
+        drop(release);
+        assert_eq!(wait(&mut worker).unwrap(), Some(PathBuf::from("a.txt")));
+    }
+
+    #[test]
+    fn reports_spawn_failure_and_stays_closed() {
+        let _serial = serial();
+        let mut worker = Dialog::default();
+        // No thread can have a stack this large.
+        worker.set_stack_size(isize::MAX as usize / 2);
+        worker.set_group(group("spawn failure", GuardPolicy::Reject));
+        let result = worker.spawn(DialogKind::OpenSingleFile, None, || {
+            Ok::<_, DialogError>(None)
+        });
+        assert!(matches!(result, Err(ImNativeDialogError::SpawnFailed(_))));
+        assert!(!worker.is_open());
+        assert_eq!(worker.state(), DialogState::Idle);
+        assert!(worker.check().is_none());
+
+        // The group was released, so another dialog can be shown.
+        let mut other = Dialog::default();
+        other.set_group(group("spawn failure", GuardPolicy::Reject));
+        let (token, release) = show_blocking(&mut other, "a.txt");
+        token.unwrap();
+        drop(release);
+        wait(&mut other).unwrap();
+    }
+
+    #[test]
+    fn cancels_open_dialog() {
+        let _serial = serial();
+        let mut worker = Dialog::default();
+        let (token, release) = show_blocking(&mut worker, "a.txt");
+        token.unwrap();
+        assert!(worker.cancel());
+        assert_eq!(worker.check().unwrap().unwrap(), None);
+        assert!(!worker.is_open());
+        assert!(!worker.cancel());
+        drop(release);
+    }
+
+    #[test]
+    fn rejects_when_group_is_busy() {
+        let _serial = serial();
+        let (mut first, mut second) = (Dialog::default(), Dialog::default());
+        first.set_group(group("busy", GuardPolicy::Reject));
+        second.set_group(group("busy", GuardPolicy::Reject));
+        let (token, release) = show_blocking(&mut first, "a.txt");
+        token.unwrap();
+        let (busy, _busy_release) = show_blocking(&mut second, "b.txt");
+        assert!(matches!(busy, Err(ImNativeDialogError::Busy)));
+        assert!(!second.is_open());
+        drop(release);
+        wait(&mut first).unwrap();
+    }
+
+    #[test]
+    fn queues_and_resumes_when_group_is_free() {
+        let _serial = serial();
+        let (mut first, mut second) = (Dialog::default(), Dialog::default());
+        first.set_group(group("queue", GuardPolicy::Queue));
+        second.set_group(group("queue", GuardPolicy::Queue));
+        let (token, release) = show_blocking(&mut first, "a.txt");
+        token.unwrap();
+        let (queued, queued_release) = show_blocking(&mut second, "b.txt");
+        let queued = queued.unwrap();
+        assert_eq!(
+            second.state(),
+            DialogState::Queued {
+                kind: DialogKind::OpenSingleFile,
+                token: queued,
+            }
+        );
+        assert!(second.check().is_none());
+        drop(queued_release);
+
+        drop(release);
+        assert_eq!(wait(&mut first).unwrap(), Some(PathBuf::from("a.txt")));
+        // The next check shows the queued dialog.
+        assert_eq!(wait(&mut second).unwrap(), Some(PathBuf::from("b.txt")));
+    }
+
+    #[test]
+    fn cancels_queued_dialog() {
+        let _serial = serial();
+        let (mut first, mut second) = (Dialog::default(), Dialog::default());
+        first.set_group(group("cancel queued", GuardPolicy::Queue));
+        second.set_group(group("cancel queued", GuardPolicy::Queue));
+        let (token, release) = show_blocking(&mut first, "a.txt");
+        token.unwrap();
+        let (queued, _queued_release) = show_blocking(&mut second, "b.txt");
+        queued.unwrap();
+        assert!(second.cancel());
+        assert_eq!(second.check().unwrap().unwrap(), None);
+        assert!(!second.is_open());
+        drop(release);
+        wait(&mut first).unwrap();
+    }
+
+    #[test]
+    fn debounces_repeated_requests() {
+        let _serial = serial();
+        let mut worker = Dialog::default();
+        worker.set_debounce(Duration::from_secs(3600));
+        let (token, release) = show_blocking(&mut worker, "a.txt");
+        let token = token.unwrap();
+        drop(release);
+        wait(&mut worker).unwrap();
+        let (debounced, _release) = show_blocking(&mut worker, "b.txt");
+        assert_eq!(debounced.unwrap(), token);
+        assert!(!worker.is_open());
+    }
+
+    #[test]
+    fn show_if_closed_is_false_when_debounced() {
+        let _serial = serial();
+        let mut dialog = crate::ImNativeFileDialog::<Option<PathBuf>>::default();
+        dialog.with_debounce(Duration::from_secs(3600));
+        let (release, released) = mpsc::channel::<()>();
+        let shown = dialog.show_if_closed(|dialog| {
+            dialog
+                .worker
+                .spawn(DialogKind::OpenSingleFile, None, move || {
+                    let _ = released.recv();
+                    Ok::<_, DialogError>(None)
+                })
+        });
+        assert!(shown.unwrap());
+        drop(release);
+        wait(&mut dialog.worker).unwrap();
+        let shown = dialog.show_if_closed(|dialog| show_blocking(&mut dialog.worker, "b.txt").0);
+        assert!(!shown.unwrap());
+        assert!(!dialog.worker.is_open());
+    }
+
+    /// Removes the rate limit when dropped, even if the test fails.
+    struct RateLimited;
+
+    impl Drop for RateLimited {
+        fn drop(&mut self) {
+            crate::set_rate_limit(None);
+        }
+    }
+
+    #[test]
+    fn limits_rate_of_dialogs() {
+        let _serial = serial();
+        let _limited = RateLimited;
+        crate::set_rate_limit(Some(
+            RateLimit::new(1, Duration::from_secs(3600)).policy(RateLimitPolicy::Reject),
+        ));
+        let mut worker = Dialog::default();
+        let (token, release) = show_blocking(&mut worker, "a.txt");
+        token.unwrap();
+        drop(release);
+        wait(&mut worker).unwrap();
+        let (limited, _release) = show_blocking(&mut worker, "b.txt");
+        assert!(matches!(limited, Err(ImNativeDialogError::RateLimited)));
+        assert!(!worker.is_open());
+    }
+
+    #[test]
+    fn coalesces_rate_limited_requests_into_previous_dialog() {
+        let _serial = serial();
+        let _limited = RateLimited;
+        crate::set_rate_limit(Some(RateLimit::new(1, Duration::from_secs(3600))));
+        let mut dialog = crate::ImNativeFileDialog::<Option<PathBuf>>::default();
+        let (release, released) = mpsc::channel::<()>();
+        let mut token = None;
+        let shown = dialog.show_if_closed(|dialog| {
+            let shown = dialog
+                .worker
+                .spawn(DialogKind::OpenSingleFile, None, move || {
+                    let _ = released.recv();
+                    Ok::<_, DialogError>(None)
+                });
+            token = shown.as_ref().ok().copied();
+            shown
+        });
+        assert!(shown.unwrap());
+        drop(release);
+        wait(&mut dialog.worker).unwrap();
+
+        let (coalesced, _release) = show_blocking(&mut dialog.worker, "b.txt");
+        assert_eq!(coalesced.unwrap(), token.unwrap());
+        let shown = dialog.show_if_closed(|dialog| show_blocking(&mut dialog.worker, "c.txt").0);
+        assert!(!shown.unwrap());
+        assert!(!dialog.worker.is_open());
+
+        // A dialog which has never been shown has nothing to coalesce
+        // into.
+        let mut other = crate::ImNativeFileDialog::<Option<PathBuf>>::default();
+        let (limited, _release) = show_blocking(&mut other.worker, "d.txt");
+        assert!(matches!(limited, Err(ImNativeDialogError::RateLimited)));
+        let shown = other.show_if_closed(|dialog| show_blocking(&mut dialog.worker, "e.txt").0);
+        assert!(!shown.unwrap());
+        assert!(!other.worker.is_open());
+    }
+
+    #[test]
+    fn routes_results_instead_of_returning_them() {
+        let _serial = serial();
+        let (sender, routed) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let mut worker = Dialog::default();
+        worker.set_route(Arc::new(move |result| {
+            let _ = sender.lock().unwrap().send(result);
+        }));
+        let (token, release) = show_blocking(&mut worker, "a.txt");
+        token.unwrap();
+        drop(release);
+        let result = routed.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(result.unwrap(), Some(PathBuf::from("a.txt")));
+        let started_at = Instant::now();
+        while worker.is_open() {
+            assert!(worker.check().is_none());
+            assert!(started_at.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn discards_expired_results() {
+        let _serial = serial();
+        let mut worker = Dialog::default();
+        worker.set_expiry(ExpiryPolicy::new().after(Duration::ZERO));
+        let (token, release) = show_blocking(&mut worker, "a.txt");
+        token.unwrap();
+        drop(release);
+        let started_at = Instant::now();
+        while worker.is_open() {
+            assert!(worker.check().is_none());
+            assert!(started_at.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn wakes_once_result_is_sent() {
+        let _serial = serial();
+        let mut worker = Dialog::default();
+        let (woken, wakes) = mpsc::channel::<()>();
+        let woken = Mutex::new(woken);
+        worker.set_wake(Arc::new(move || {
+            let _ = woken.lock().expect("wake sender lock poisoned").send(());
+        }));
+
+        let (token, release) = show_blocking(&mut worker, "woken.txt");
+        token.unwrap();
+        assert!(wakes.try_recv().is_err());
+        drop(release);
+        wakes
+            .recv_timeout(Duration::from_secs(5))
+            .expect("dialog thread didn't wake the ui");
+        // The result is waiting by the time the ui is woken.
+        assert_eq!(
+            worker.check().unwrap().unwrap(),
+            Some(PathBuf::from("woken.txt"))
+        );
+    }
+}